@@ -1,7 +1,7 @@
 use firefly_client::models::WalletAddress;
 use poem::web::Data;
 use poem_openapi::OpenApi;
-use poem_openapi::param::Path;
+use poem_openapi::param::{Path, Query};
 use poem_openapi::payload::Json;
 
 use crate::ai_agents::api::dtos::{
@@ -17,7 +17,9 @@ use crate::ai_agents::api::dtos::{
     SaveAgentResp,
 };
 use crate::ai_agents::handlers::AgentsService;
+use crate::ai_agents::models;
 use crate::common::api::dtos::{ApiTags, MaybeNotFound, SendResp, SignedContract, Stringified};
+use crate::common::prepared_contracts::SendEndpoint;
 
 mod dtos;
 
@@ -30,9 +32,12 @@ impl AIAgents {
     async fn list(
         &self,
         Path(address): Path<Stringified<WalletAddress>>,
+        Query(block_hash): Query<Option<String>>,
         Data(agents): Data<&AgentsService>,
     ) -> poem::Result<Json<Agents>> {
-        let agents = agents.list_agents(address.0).await?;
+        let agents = agents
+            .list_agents(address.0, block_hash.map(Into::into))
+            .await?;
         Ok(Json(agents.into()))
     }
 
@@ -61,9 +66,13 @@ impl AIAgents {
     async fn prepare_create(
         &self,
         Json(body): Json<CreateAgentReq>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(agents): Data<&AgentsService>,
     ) -> poem::Result<Json<CreateAgentResp>> {
-        let contract = agents.prepare_create_agent_contract(body.into()).await?;
+        let phlo_price = agents.deploy_config.phlo_price(phlo_price)?;
+        let contract = agents
+            .prepare_create_agent_contract(body.into(), phlo_price)
+            .await?;
         Ok(Json(contract.into()))
     }
 
@@ -73,7 +82,13 @@ impl AIAgents {
         Json(body): Json<SignedContract>,
         Data(agents): Data<&AgentsService>,
     ) -> poem::Result<Json<SendResp>> {
-        let deploy_id = agents.deploy_signed_create_agent(body.into()).await?;
+        let contract = body.into();
+        let sending =
+            agents
+                .prepared_contracts
+                .verify([&contract], SendEndpoint::CreateAgent, None)?;
+        let deploy_id = agents.deploy_signed_create_agent(contract).await?;
+        sending.sent();
         Ok(Json(deploy_id.into()))
     }
 
@@ -81,9 +96,16 @@ impl AIAgents {
     async fn prepare_deploy_agent(
         &self,
         Json(body): Json<DeployAgentReq>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(agents): Data<&AgentsService>,
     ) -> poem::Result<Json<DeployAgentResp>> {
-        let contract = agents.prepare_deploy_agent_contract(body.into()).await?;
+        let phlo_price = agents.deploy_config.phlo_price(phlo_price)?;
+        if matches!(body, DeployAgentReq::Code(_)) {
+            agents.deploy_config.check_arbitrary_code()?;
+        }
+        let contract = agents
+            .prepare_deploy_agent_contract(body.into(), phlo_price)
+            .await?;
         Ok(Json(contract.into()))
     }
 
@@ -93,7 +115,14 @@ impl AIAgents {
         Json(body): Json<DeploySignedAgentReq>,
         Data(agents): Data<&AgentsService>,
     ) -> poem::Result<Json<SendResp>> {
-        let deploy_id = agents.deploy_signed_deploy_agent(body.into()).await?;
+        let request: models::DeploySignedAgentReq = body.into();
+        let sending = agents.prepared_contracts.verify(
+            std::iter::once(&request.contract).chain(&request.system),
+            SendEndpoint::DeployAgent,
+            None,
+        )?;
+        let deploy_id = agents.deploy_signed_deploy_agent(request).await?;
+        sending.sent();
         Ok(Json(deploy_id.into()))
     }
 
@@ -102,9 +131,13 @@ impl AIAgents {
         &self,
         Path(id): Path<String>,
         Json(body): Json<SaveAgentReq>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(agents): Data<&AgentsService>,
     ) -> poem::Result<Json<SaveAgentResp>> {
-        let contract = agents.prepare_save_agent_contract(id, body.into()).await?;
+        let phlo_price = agents.deploy_config.phlo_price(phlo_price)?;
+        let contract = agents
+            .prepare_save_agent_contract(id, body.into(), phlo_price)
+            .await?;
         Ok(Json(contract.into()))
     }
 
@@ -115,7 +148,13 @@ impl AIAgents {
         Json(body): Json<SignedContract>,
         Data(agents): Data<&AgentsService>,
     ) -> poem::Result<Json<SendResp>> {
-        let deploy_id = agents.deploy_signed_save_agent(body.into()).await?;
+        let contract = body.into();
+        let sending =
+            agents
+                .prepared_contracts
+                .verify([&contract], SendEndpoint::SaveAgent, None)?;
+        let deploy_id = agents.deploy_signed_save_agent(contract).await?;
+        sending.sent();
         Ok(Json(deploy_id.into()))
     }
 
@@ -123,9 +162,11 @@ impl AIAgents {
     async fn prepare_delete(
         &self,
         Path(id): Path<String>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(agents): Data<&AgentsService>,
     ) -> poem::Result<Json<DeleteAgentResp>> {
-        let contract = agents.prepare_delete_agent_contract(id).await?;
+        let phlo_price = agents.deploy_config.phlo_price(phlo_price)?;
+        let contract = agents.prepare_delete_agent_contract(id, phlo_price).await?;
         Ok(Json(contract.into()))
     }
 
@@ -136,7 +177,13 @@ impl AIAgents {
         Json(body): Json<SignedContract>,
         Data(agents): Data<&AgentsService>,
     ) -> poem::Result<Json<SendResp>> {
-        let deploy_id = agents.deploy_signed_delete_agent(body.into()).await?;
+        let contract = body.into();
+        let sending =
+            agents
+                .prepared_contracts
+                .verify([&contract], SendEndpoint::DeleteAgent, None)?;
+        let deploy_id = agents.deploy_signed_delete_agent(contract).await?;
+        sending.sent();
         Ok(Json(deploy_id.into()))
     }
 }
@@ -1,5 +1,10 @@
+use std::sync::Arc;
+
 use firefly_client::models::Uri;
-use firefly_client::{ReadNodeClient, WriteNodeClient};
+use firefly_client::{DeployTransport, Proposer, ReadNodeClient};
+
+use crate::common::models::DeployConfig;
+use crate::common::prepared_contracts::PreparedContracts;
 
 mod create_agent;
 mod delete_agent;
@@ -12,6 +17,134 @@ mod save_agent;
 #[derive(Clone)]
 pub struct AgentsService {
     pub uri: Uri,
-    pub write_client: WriteNodeClient,
+    pub write_client: Arc<dyn DeployTransport>,
     pub read_client: ReadNodeClient,
+    pub propose_scheduler: Arc<dyn Proposer>,
+    pub deploy_config: DeployConfig,
+    pub prepared_contracts: PreparedContracts,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use blake2::digest::consts::U32;
+    use blake2::{Blake2b, Digest};
+    use firefly_client::models::SignedCode;
+    use firefly_client::rendering::Value;
+    use firefly_client::{DeployPreview, ProposeScheduler, WriteNodeClient};
+    use firefly_mock_node::MockNode;
+    use secp256k1::{Message, Secp256k1, SecretKey};
+
+    use super::*;
+    use crate::ai_agents::models::CreateAgentReq;
+    use crate::common::models::PreparedContract;
+
+    const KEY: &str = "f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc";
+
+    fn key() -> SecretKey {
+        SecretKey::from_str(KEY).unwrap()
+    }
+
+    fn sign(contract: PreparedContract) -> SignedCode {
+        let secp = Secp256k1::new();
+        let hash = Blake2b::<U32>::new().chain_update(&contract.0).finalize();
+
+        SignedCode {
+            sig: secp
+                .sign_ecdsa(Message::from_digest(hash.into()), &key())
+                .serialize_der()
+                .to_vec(),
+            sig_algorithm: "secp256k1".to_owned(),
+            deployer: key().public_key(&secp).serialize_uncompressed().to_vec(),
+            contract: contract.0,
+        }
+    }
+
+    async fn service(node: &MockNode) -> AgentsService {
+        let write_client = WriteNodeClient::new(node.grpc_url(), node.grpc_url())
+            .await
+            .unwrap();
+        let propose_scheduler = ProposeScheduler::builder()
+            .write_client(write_client.clone())
+            .debounce(Duration::from_millis(10))
+            .build();
+
+        AgentsService {
+            uri: key().public_key(&Secp256k1::new()).into(),
+            write_client: Arc::new(write_client),
+            read_client: ReadNodeClient::new(node.http_url()),
+            propose_scheduler: Arc::new(propose_scheduler),
+            deploy_config: DeployConfig::default(),
+            prepared_contracts: PreparedContracts::new(&DeployConfig::default(), &[0; 32]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_agent() {
+        let node = MockNode::start().await.unwrap();
+        let service = service(&node).await;
+
+        let resp = service
+            .prepare_create_agent_contract(
+                CreateAgentReq {
+                    name: "agent".to_owned(),
+                    description: None,
+                    shard: None,
+                    logo: None,
+                    code: Some("Nil".to_owned()),
+                },
+                1,
+            )
+            .await
+            .unwrap();
+        let preview = DeployPreview::decode(&resp.contract.0).unwrap();
+        assert!(preview.term.contains(&resp.id));
+        assert!(preview.term.contains(&resp.version));
+
+        service
+            .deploy_signed_create_agent(sign(resp.contract))
+            .await
+            .unwrap();
+
+        let deploys = node.deploys();
+        assert_eq!(deploys.len(), 1);
+        assert_eq!(deploys[0].term, preview.term);
+        assert_eq!(
+            service.write_client.get_head_block_index().await.unwrap(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_agents() {
+        let node = MockNode::start().await.unwrap();
+        let service = service(&node).await;
+        node.on_explore(|_| {
+            Some(Value::List(vec![Value::Map(BTreeMap::from([
+                ("id".to_owned(), Value::String("1".to_owned())),
+                ("version".to_owned(), Value::String("2".to_owned())),
+                ("created_at".to_owned(), Value::Int(1_700_000_000)),
+                ("last_deploy".to_owned(), Value::Nil),
+                ("name".to_owned(), Value::String("agent".to_owned())),
+                ("description".to_owned(), Value::Nil),
+                ("shard".to_owned(), Value::Nil),
+                ("logo".to_owned(), Value::Nil),
+            ]))]))
+        });
+
+        let agents = service
+            .list_agents(key().public_key(&Secp256k1::new()).into(), None)
+            .await
+            .unwrap()
+            .agents;
+        assert_eq!(agents.len(), 1);
+        assert_eq!(agents[0].id, "1");
+        assert_eq!(agents[0].version, "2");
+        assert_eq!(agents[0].name, "agent");
+        assert_eq!(agents[0].created_at.timestamp(), 1_700_000_000);
+        assert!(agents[0].last_deploy.is_none());
+    }
 }
@@ -6,6 +6,7 @@ use firefly_client::rendering::Render;
 use crate::ai_agents::handlers::AgentsService;
 use crate::ai_agents::models::{DeployAgentReq, DeployAgentResp, DeploySignedAgentReq};
 use crate::common::prepare_for_signing;
+use crate::common::prepared_contracts::SendEndpoint;
 use crate::common::tracing::record_trace;
 
 #[derive(Debug, Clone, Render)]
@@ -28,10 +29,11 @@ impl AgentsService {
     pub async fn prepare_deploy_agent_contract(
         &self,
         request: DeployAgentReq,
+        phlo_price: u64,
     ) -> anyhow::Result<DeployAgentResp> {
         record_trace!(request);
 
-        let valid_after = self.write_client.clone().get_head_block_index().await?;
+        let valid_after = self.write_client.get_head_block_index().await?;
         let (code, phlo_limit, system) = match request {
             DeployAgentReq::Agent {
                 id,
@@ -59,8 +61,12 @@ impl AgentsService {
                     phlo_limit,
                     Some(
                         prepare_for_signing()
+                            .prepared_contracts(&self.prepared_contracts)
+                            .endpoint(SendEndpoint::DeployAgent)
                             .code(system_code)
                             .valid_after_block_number(valid_after)
+                            .phlo_price(phlo_price)
+                            .shard_id(self.deploy_config.shard_id.clone())
                             .call(),
                     ),
                 )
@@ -70,8 +76,12 @@ impl AgentsService {
 
         Ok(DeployAgentResp {
             contract: prepare_for_signing()
+                .prepared_contracts(&self.prepared_contracts)
+                .endpoint(SendEndpoint::DeployAgent)
                 .code(code)
                 .valid_after_block_number(valid_after)
+                .phlo_price(phlo_price)
+                .shard_id(self.deploy_config.shard_id.clone())
                 .phlo_limit(phlo_limit)
                 .call(),
             system,
@@ -91,7 +101,7 @@ impl AgentsService {
     ) -> anyhow::Result<DeployId> {
         record_trace!(request);
 
-        let mut write_client = self.write_client.clone();
+        let write_client = &self.write_client;
 
         let deploy_id = write_client
             .deploy_signed_contract(request.contract)
@@ -101,7 +111,7 @@ impl AgentsService {
             write_client.deploy_signed_contract(system).await?;
         }
 
-        write_client.propose().await?;
+        self.propose_scheduler.enqueue(deploy_id.clone()).await?;
         Ok(deploy_id)
     }
 }
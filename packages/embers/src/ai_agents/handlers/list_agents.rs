@@ -1,4 +1,4 @@
-use firefly_client::models::{Uri, WalletAddress};
+use firefly_client::models::{BlockId, Uri, WalletAddress};
 use firefly_client::rendering::Render;
 
 use crate::ai_agents::blockchain::dtos;
@@ -21,7 +21,11 @@ impl AgentsService {
         err(Debug),
         ret(Debug, level = "trace")
     )]
-    pub async fn list_agents(&self, address: WalletAddress) -> anyhow::Result<Agents> {
+    pub async fn list_agents(
+        &self,
+        address: WalletAddress,
+        block_hash: Option<BlockId>,
+    ) -> anyhow::Result<Agents> {
         record_trace!(address);
 
         let code = ListAgents {
@@ -30,7 +34,7 @@ impl AgentsService {
         }
         .render()?;
         self.read_client
-            .get_data(code)
+            .get_data_at(code, block_hash.as_ref())
             .await
             .map(|agents: Vec<dtos::AgentHeader>| Agents {
                 agents: agents.into_iter().map(Into::into).collect(),
@@ -6,6 +6,7 @@ use uuid::Uuid;
 use crate::ai_agents::handlers::AgentsService;
 use crate::ai_agents::models::{SaveAgentReq, SaveAgentResp};
 use crate::common::prepare_for_signing;
+use crate::common::prepared_contracts::SendEndpoint;
 use crate::common::tracing::record_trace;
 
 #[derive(Debug, Clone, Render)]
@@ -34,6 +35,7 @@ impl AgentsService {
         &self,
         id: String,
         request: SaveAgentReq,
+        phlo_price: u64,
     ) -> anyhow::Result<SaveAgentResp> {
         record_trace!(id, request);
 
@@ -52,12 +54,16 @@ impl AgentsService {
         }
         .render()?;
 
-        let valid_after = self.write_client.clone().get_head_block_index().await?;
+        let valid_after = self.write_client.get_head_block_index().await?;
         Ok(SaveAgentResp {
             version: version.into(),
             contract: prepare_for_signing()
+                .prepared_contracts(&self.prepared_contracts)
+                .endpoint(SendEndpoint::SaveAgent)
                 .code(contract)
                 .valid_after_block_number(valid_after)
+                .phlo_price(phlo_price)
+                .shard_id(self.deploy_config.shard_id.clone())
                 .call(),
         })
     }
@@ -72,10 +78,8 @@ impl AgentsService {
     pub async fn deploy_signed_save_agent(&self, contract: SignedCode) -> anyhow::Result<DeployId> {
         record_trace!(contract);
 
-        let mut write_client = self.write_client.clone();
-
-        let deploy_id = write_client.deploy_signed_contract(contract).await?;
-        write_client.propose().await?;
+        let deploy_id = self.write_client.deploy_signed_contract(contract).await?;
+        self.propose_scheduler.enqueue(deploy_id.clone()).await?;
         Ok(deploy_id)
     }
 }
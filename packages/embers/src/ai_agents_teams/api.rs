@@ -1,7 +1,7 @@
 use firefly_client::models::WalletAddress;
 use poem::web::Data;
 use poem_openapi::OpenApi;
-use poem_openapi::param::Path;
+use poem_openapi::param::{Path, Query};
 use poem_openapi::payload::Json;
 
 use crate::ai_agents_teams::api::dtos::{
@@ -21,7 +21,9 @@ use crate::ai_agents_teams::api::dtos::{
     SaveAgentsTeamResp,
 };
 use crate::ai_agents_teams::handlers::AgentsTeamsService;
+use crate::ai_agents_teams::models;
 use crate::common::api::dtos::{ApiTags, MaybeNotFound, SendResp, SignedContract, Stringified};
+use crate::common::prepared_contracts::SendEndpoint;
 
 mod dtos;
 
@@ -71,10 +73,12 @@ impl AIAgentsTeams {
     async fn prepare_create(
         &self,
         Json(body): Json<CreateAgentsTeamReq>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(agents_teams): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<CreateAgentsTeamResp>> {
+        let phlo_price = agents_teams.deploy_config.phlo_price(phlo_price)?;
         let contract = agents_teams
-            .prepare_create_agents_team_contract(body.into())
+            .prepare_create_agents_team_contract(body.into(), phlo_price)
             .await?;
         Ok(Json(contract.into()))
     }
@@ -85,9 +89,16 @@ impl AIAgentsTeams {
         Json(body): Json<SignedContract>,
         Data(agents_teams): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<SendResp>> {
+        let contract = body.into();
+        let sending = agents_teams.prepared_contracts.verify(
+            [&contract],
+            SendEndpoint::CreateAgentsTeam,
+            None,
+        )?;
         let deploy_id = agents_teams
-            .deploy_signed_create_agents_team(body.into())
+            .deploy_signed_create_agents_team(contract)
             .await?;
+        sending.sent();
         Ok(Json(deploy_id.into()))
     }
 
@@ -95,10 +106,15 @@ impl AIAgentsTeams {
     async fn prepare_deploy_agents_team(
         &self,
         Json(body): Json<DeployAgentsTeamReq>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(agents_teams): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<DeployAgentsTeamResp>> {
+        let phlo_price = agents_teams.deploy_config.phlo_price(phlo_price)?;
+        if matches!(body, DeployAgentsTeamReq::Graph(_)) {
+            agents_teams.deploy_config.check_arbitrary_code()?;
+        }
         let contract = agents_teams
-            .prepare_deploy_agents_team_contract(body.into())
+            .prepare_deploy_agents_team_contract(body.into(), phlo_price)
             .await?;
         Ok(Json(contract.into()))
     }
@@ -109,9 +125,16 @@ impl AIAgentsTeams {
         Json(body): Json<DeploySignedAgentsTeamtReq>,
         Data(agents_teams): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<SendResp>> {
+        let request: models::DeploySignedAgentsTeamtReq = body.into();
+        let sending = agents_teams.prepared_contracts.verify(
+            std::iter::once(&request.contract).chain(&request.system),
+            SendEndpoint::DeployAgentsTeam,
+            None,
+        )?;
         let deploy_id = agents_teams
-            .deploy_signed_deploy_agents_team(body.into())
+            .deploy_signed_deploy_agents_team(request)
             .await?;
+        sending.sent();
         Ok(Json(deploy_id.into()))
     }
 
@@ -119,10 +142,12 @@ impl AIAgentsTeams {
     async fn prepare_run_agents_team(
         &self,
         Json(body): Json<RunAgentsTeamReq>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(agents_teams): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<RunAgentsTeamResp>> {
+        let phlo_price = agents_teams.deploy_config.phlo_price(phlo_price)?;
         let contract = agents_teams
-            .prepare_run_agents_team_contract(body.into())
+            .prepare_run_agents_team_contract(body.into(), phlo_price)
             .await?;
         Ok(Json(contract.into()))
     }
@@ -133,11 +158,15 @@ impl AIAgentsTeams {
         Json(body): Json<SignedContract>,
         Data(agents_teams): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<serde_json::Value>> {
-        agents_teams
-            .deploy_signed_run_agents_team(body.into())
-            .await
-            .map(Json)
-            .map_err(Into::into)
+        let contract = body.into();
+        let sending = agents_teams.prepared_contracts.verify(
+            [&contract],
+            SendEndpoint::RunAgentsTeam,
+            None,
+        )?;
+        let result = agents_teams.deploy_signed_run_agents_team(contract).await?;
+        sending.sent();
+        Ok(Json(result))
     }
 
     #[oai(path = "/:id/save/prepare", method = "post")]
@@ -145,10 +174,12 @@ impl AIAgentsTeams {
         &self,
         Path(id): Path<String>,
         Json(body): Json<SaveAgentsTeamReq>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(agents_teams): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<SaveAgentsTeamResp>> {
+        let phlo_price = agents_teams.deploy_config.phlo_price(phlo_price)?;
         let contract = agents_teams
-            .prepare_save_agents_team_contract(id, body.into())
+            .prepare_save_agents_team_contract(id, body.into(), phlo_price)
             .await?;
         Ok(Json(contract.into()))
     }
@@ -160,9 +191,16 @@ impl AIAgentsTeams {
         Json(body): Json<SignedContract>,
         Data(agents_teams): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<SendResp>> {
+        let contract = body.into();
+        let sending = agents_teams.prepared_contracts.verify(
+            [&contract],
+            SendEndpoint::SaveAgentsTeam,
+            None,
+        )?;
         let deploy_id = agents_teams
-            .deploy_signed_save_agents_team(body.into())
+            .deploy_signed_save_agents_team(contract)
             .await?;
+        sending.sent();
         Ok(Json(deploy_id.into()))
     }
 
@@ -170,9 +208,13 @@ impl AIAgentsTeams {
     async fn prepare_delete(
         &self,
         Path(id): Path<String>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(agents): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<DeleteAgentsTeamResp>> {
-        let contract = agents.prepare_delete_agents_team_contract(id).await?;
+        let phlo_price = agents.deploy_config.phlo_price(phlo_price)?;
+        let contract = agents
+            .prepare_delete_agents_team_contract(id, phlo_price)
+            .await?;
         Ok(Json(contract.into()))
     }
 
@@ -183,7 +225,13 @@ impl AIAgentsTeams {
         Json(body): Json<SignedContract>,
         Data(agents): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<SendResp>> {
-        let deploy_id = agents.deploy_signed_delete_agents_team(body.into()).await?;
+        let contract = body.into();
+        let sending =
+            agents
+                .prepared_contracts
+                .verify([&contract], SendEndpoint::DeleteAgentsTeam, None)?;
+        let deploy_id = agents.deploy_signed_delete_agents_team(contract).await?;
+        sending.sent();
         Ok(Json(deploy_id.into()))
     }
 
@@ -193,10 +241,17 @@ impl AIAgentsTeams {
         Path(address): Path<Stringified<WalletAddress>>,
         Path(id): Path<String>,
         Json(body): Json<PublishAgentsTeamToFireskyReq>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(agents_teams): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<PublishAgentsTeamToFireskyResp>> {
+        let phlo_price = agents_teams.deploy_config.phlo_price(phlo_price)?;
         let contract = agents_teams
-            .prepare_publish_agents_team_to_firesky_contract(address.into(), id, body.into())
+            .prepare_publish_agents_team_to_firesky_contract(
+                address.into(),
+                id,
+                body.into(),
+                phlo_price,
+            )
             .await?;
         Ok(Json(contract.into()))
     }
@@ -204,14 +259,21 @@ impl AIAgentsTeams {
     #[oai(path = "/:address/:id/publish-to-firesky/send", method = "post")]
     async fn publish_agents_team_to_firesky(
         &self,
-        #[allow(unused_variables)] Path(address): Path<Stringified<WalletAddress>>,
+        Path(address): Path<Stringified<WalletAddress>>,
         #[allow(unused_variables)] Path(id): Path<String>,
         Json(body): Json<SignedContract>,
         Data(agents_teams): Data<&AgentsTeamsService>,
     ) -> poem::Result<Json<SendResp>> {
+        let contract = body.into();
+        let sending = agents_teams.prepared_contracts.verify(
+            [&contract],
+            SendEndpoint::PublishAgentsTeamToFiresky,
+            Some(&address.0),
+        )?;
         let deploy_id = agents_teams
-            .deploy_signed_publish_agents_team_to_firesky(body.into())
+            .deploy_signed_publish_agents_team_to_firesky(contract)
             .await?;
+        sending.sent();
         Ok(Json(deploy_id.into()))
     }
 }
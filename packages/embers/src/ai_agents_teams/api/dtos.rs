@@ -1,10 +1,16 @@
 use chrono::{DateTime, Utc};
 use firefly_client::models::{Uri, WalletAddress};
 use poem_openapi::{Object, Union};
+use secp256k1::PublicKey;
 use structural_convert::StructuralConvert;
 
 use crate::ai_agents_teams::models;
-use crate::common::api::dtos::{PreparedContract, RegistryDeploy, SignedContract, Stringified};
+use crate::common::api::dtos::{
+    PreparedContract,
+    RegistryDeploy,
+    SignedContract,
+    Stringified,
+};
 use crate::common::models::PositiveNonZero;
 
 #[derive(Debug, Clone, StructuralConvert, Object)]
@@ -136,12 +142,16 @@ pub struct RunAgentsTeamReq {
     pub prompt: String,
     pub phlo_limit: Stringified<PositiveNonZero<i64>>,
     pub agents_team: Stringified<Uri>,
+    /// Public key that will sign the contract, needed to return the result channel.
+    pub deployer: Option<Stringified<PublicKey>>,
 }
 
 #[derive(Debug, Clone, StructuralConvert, Object)]
 #[convert(from(models::RunAgentsTeamResp))]
 pub struct RunAgentsTeamResp {
     pub contract: PreparedContract,
+    /// Hex id of the unforgeable name the result is sent to, present when a deployer is given.
+    pub result_channel: Option<String>,
 }
 
 #[derive(Debug, Clone, StructuralConvert, Object)]
@@ -1,145 +1,142 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use derive_more::Display;
 use firefly_client::models::Uri;
-use firefly_client::rendering::{Inline, Render};
+use firefly_client::rendering::{Bind, Name, NewName, Render, Term};
 
 use crate::ai_agents_teams::compilation::graphl_parsing::Vertex;
 use crate::ai_agents_teams::compilation::{Code, Node};
 use crate::common::models::RegistryDeploy;
 use crate::common::tracing::record_trace;
 
-#[derive(Debug, Clone, Display)]
-enum From<'a> {
-    #[display("input")]
-    Input,
-    Channel(&'a str),
-}
-
-#[derive(Debug, Clone, Display)]
-enum Output<'a> {
-    Channel(&'a str),
-}
-
-#[derive(Debug, Clone, Render)]
-enum NodesTemplate<'a> {
-    #[template(path = "ai_agents_teams/nodes/compress.rho")]
-    Compress {
-        #[template(direct)]
-        from: Vec<From<'a>>,
-
-        #[template(direct)]
-        output: Output<'a>,
-
-        body: BTreeMap<String, Inline>,
-    },
-
-    #[template(path = "ai_agents_teams/nodes/text_model.rho")]
-    TextModel {
-        #[template(direct)]
-        from: From<'a>,
-
-        #[template(direct)]
-        output: Output<'a>,
-    },
-
-    #[template(path = "ai_agents_teams/nodes/tti_model.rho")]
-    TTIModel {
-        #[template(direct)]
-        from: From<'a>,
-
-        #[template(direct)]
-        output: Output<'a>,
-    },
-
-    #[template(path = "ai_agents_teams/nodes/tts_model.rho")]
-    TTSModel {
-        #[template(direct)]
-        from: From<'a>,
-
-        #[template(direct)]
-        output: Output<'a>,
-    },
-
-    #[template(path = "ai_agents_teams/nodes/output.rho")]
-    Output {
-        #[template(direct)]
-        from: From<'a>,
-    },
-}
-
 #[derive(Debug, Clone, Render)]
 #[template(path = "ai_agents_teams/deploy_agents_team.rho")]
-struct DeployAgentTeamTemplate<'a> {
+struct DeployAgentTeamTemplate {
     env_uri: Uri,
     version: i64,
     public_key: Vec<u8>,
     sig: Vec<u8>,
 
     #[template(direct)]
-    system_channels: Vec<&'static str>,
+    system_channels: Vec<NewName>,
 
-    #[template(direct)]
-    output_channels: Vec<&'a str>,
-
-    #[template(direct)]
-    nodes: Vec<String>,
+    body: Term,
 
     #[template(direct)]
     output: bool,
 }
 
-fn filter_channels<'a>(from: &[From<'a>]) -> Vec<&'a str> {
-    from.iter()
-        .filter_map(|f| match f {
-            From::Channel(c) => Some(*c),
-            From::Input => None,
-        })
-        .collect()
+#[derive(Debug, Clone)]
+enum From<'a> {
+    Input,
+    Channel(&'a Name),
+}
+
+fn name(name: impl Into<String>) -> anyhow::Result<Name> {
+    Name::new(name).map_err(Into::into)
 }
 
-fn get_all_system_channels<'a>(nodes: &BTreeMap<Vertex<'a>, Node<'a>>) -> Vec<&'static str> {
+const fn system_channel(node: &Node<'_>) -> Option<(&'static str, &'static str)> {
+    match node {
+        Node::Compress { .. } => None,
+        Node::TextModel { .. } => Some(("gpt4", "rho:ai:gpt4")),
+        Node::TTIModel { .. } => Some(("dalle3", "rho:ai:dalle3")),
+        Node::TTSModel { .. } => Some(("textToAudio", "rho:ai:textToAudio")),
+    }
+}
+
+fn get_all_system_channels(nodes: &BTreeMap<Vertex<'_>, Node<'_>>) -> anyhow::Result<Vec<NewName>> {
     nodes
         .values()
-        .fold(BTreeSet::default(), |mut acc, node| {
-            match node {
-                Node::Compress { .. } => {}
-                Node::TextModel { .. } => {
-                    acc.insert("gpt4(`rho:ai:gpt4`)");
-                }
-                Node::TTIModel { .. } => {
-                    acc.insert("dalle3(`rho:ai:dalle3`)");
-                }
-                Node::TTSModel { .. } => {
-                    acc.insert("textToAudio(`rho:ai:textToAudio`)");
-                }
-            }
-            acc
-        })
+        .filter_map(system_channel)
+        .collect::<BTreeSet<_>>()
         .into_iter()
+        .map(|(channel, uri)| Ok(NewName::system(name(channel)?, uri)))
         .collect()
 }
 
-fn node_output_channel(index: usize) -> String {
-    format!("channel{index}Output")
+fn get_input_for_vertex<'a, 'v>(
+    outputs: &'a BTreeMap<&Vertex<'v>, Name>,
+    vertex: &Vertex<'v>,
+) -> From<'a> {
+    outputs.get(vertex).map_or(From::Input, From::Channel)
 }
 
-fn get_input_for_vertex<'a, 'b>(
-    outputs: &'b BTreeMap<&Vertex<'a>, String>,
-    vertex: &Vertex<'a>,
-) -> From<'b> {
+fn get_output_for_vertex<'v>(
+    outputs: &BTreeMap<&Vertex<'v>, Name>,
+    vertex: &Vertex<'v>,
+) -> anyhow::Result<Name> {
     outputs
         .get(vertex)
-        .map_or(From::Input, |s| From::Channel(s))
+        .cloned()
+        .map_or_else(|| name("devNull"), Ok)
 }
 
-fn get_output_for_vertex<'a, 'b>(
-    outputs: &'b BTreeMap<&Vertex<'a>, String>,
-    vertex: &'b Vertex<'a>,
-) -> Output<'b> {
-    outputs
-        .get(&vertex)
-        .map_or(Output::Channel("devNull"), |s| Output::Channel(s))
+/// Reads a value from each channel and puts it back for the other readers before running `body`.
+fn peek(channels: &[(Name, &Name)], body: Term) -> Term {
+    if channels.is_empty() {
+        return body;
+    }
+
+    let binds = channels
+        .iter()
+        .map(|(value, channel)| Bind::process(value.clone(), (*channel).clone()));
+    let put_back = channels
+        .iter()
+        .map(|(value, channel)| Term::send((*channel).clone(), [Term::var(value.clone())]));
+
+    Term::receive(binds, Term::par(put_back.chain([body])))
+}
+
+/// Sends the value from `from` to `model`, with `output` as the reply channel.
+fn model_node(model: &str, from: &From<'_>, output: Name) -> anyhow::Result<Term> {
+    let model = name(model)?;
+    let (request, channels) = match from {
+        From::Input => (name("input")?, vec![]),
+        From::Channel(channel) => {
+            let request = name(format!("{model}Request"))?;
+            (request.clone(), vec![(request, *channel)])
+        }
+    };
+
+    Ok(peek(
+        &channels,
+        Term::send(model, [Term::var(request), Term::eval(output)]),
+    ))
+}
+
+/// Sends a map of the values from `from`, keyed by vertex, to `output`.
+fn compress_node(from: Vec<(&Vertex<'_>, From<'_>)>, output: Name) -> anyhow::Result<Term> {
+    let mut channels = Vec::new();
+    let mut body = BTreeMap::new();
+
+    for (vertex, from) in from {
+        let value = match from {
+            From::Input => name("input")?,
+            From::Channel(channel) => {
+                let value = name(format!("{channel}Value"))?;
+                channels.push((value.clone(), channel));
+                value
+            }
+        };
+        body.insert((*vertex.as_ref()).to_owned(), Term::var(value));
+    }
+
+    Ok(peek(&channels, Term::send(output, [Term::value(body)])))
+}
+
+fn output_node(from: &From<'_>) -> anyhow::Result<Term> {
+    let (value, channels) = match from {
+        From::Input => (name("input")?, vec![]),
+        From::Channel(channel) => {
+            let value = name("returnValue")?;
+            (value.clone(), vec![(value, *channel)])
+        }
+    };
+
+    Ok(peek(
+        &channels,
+        Term::send(name("return")?, [Term::var(value)]),
+    ))
 }
 
 #[tracing::instrument(
@@ -157,65 +154,57 @@ pub fn render_agent_team(code: Code<'_>, deploy: RegistryDeploy) -> anyhow::Resu
         .iter()
         .filter_map(|(vertex, node)| node.output().then_some(vertex))
         .enumerate()
-        .map(|(index, v)| (v, node_output_channel(index)))
-        .collect();
+        .map(|(index, v)| Ok((v, name(format!("channel{index}Output"))?)))
+        .collect::<anyhow::Result<_>>()?;
 
     let output = code
         .output
         .as_ref()
-        .map(|v| get_input_for_vertex(&vertex_outputs, &v.from))
-        .map(|from| NodesTemplate::Output { from });
+        .map(|v| output_node(&get_input_for_vertex(&vertex_outputs, &v.from)));
 
     let nodes = code
         .nodes
         .iter()
-        .map(|(vertex, node)| match node {
-            Node::Compress { from, .. } => NodesTemplate::Compress {
-                from: from
-                    .iter()
-                    .map(|from| get_input_for_vertex(&vertex_outputs, from))
-                    .collect(),
-                output: get_output_for_vertex(&vertex_outputs, vertex),
-                body: from
-                    .iter()
-                    .map(|from| {
-                        (
-                            (*from.as_ref()).to_owned(),
-                            Inline::from(match get_input_for_vertex(&vertex_outputs, from) {
-                                From::Input => From::Input.to_string(),
-                                channel @ From::Channel(_) => format!("{channel}Value"),
-                            }),
-                        )
-                    })
-                    .collect(),
-            },
-            Node::TextModel { from, .. } => NodesTemplate::TextModel {
-                from: get_input_for_vertex(&vertex_outputs, from),
-                output: get_output_for_vertex(&vertex_outputs, vertex),
-            },
-            Node::TTIModel { from, .. } => NodesTemplate::TTIModel {
-                from: get_input_for_vertex(&vertex_outputs, from),
-                output: get_output_for_vertex(&vertex_outputs, vertex),
-            },
-            Node::TTSModel { from, .. } => NodesTemplate::TTSModel {
-                from: get_input_for_vertex(&vertex_outputs, from),
-                output: get_output_for_vertex(&vertex_outputs, vertex),
-            },
+        .map(|(vertex, node)| {
+            let output = get_output_for_vertex(&vertex_outputs, vertex)?;
+            match node {
+                Node::Compress { from, .. } => compress_node(
+                    from.iter()
+                        .map(|from| (from, get_input_for_vertex(&vertex_outputs, from)))
+                        .collect(),
+                    output,
+                ),
+                Node::TextModel { from, .. } => {
+                    model_node("gpt4", &get_input_for_vertex(&vertex_outputs, from), output)
+                }
+                Node::TTIModel { from, .. } => model_node(
+                    "dalle3",
+                    &get_input_for_vertex(&vertex_outputs, from),
+                    output,
+                ),
+                Node::TTSModel { from, .. } => model_node(
+                    "textToAudio",
+                    &get_input_for_vertex(&vertex_outputs, from),
+                    output,
+                ),
+            }
         })
         .chain(output)
-        .map(Render::render)
-        .collect::<Result<_, _>>()?;
+        .collect::<anyhow::Result<Vec<_>>>()?;
 
-    let output_channels = vertex_outputs.values().map(AsRef::as_ref).collect();
+    let body = if vertex_outputs.is_empty() {
+        Term::par(nodes)
+    } else {
+        Term::new(vertex_outputs.into_values(), Term::par(nodes))
+    };
 
     DeployAgentTeamTemplate {
         env_uri: deploy.uri_pub_key.into(),
         version: deploy.version,
         public_key: deploy.uri_pub_key.serialize_uncompressed().into(),
         sig: deploy.signature,
-        system_channels: get_all_system_channels(&code.nodes),
-        output_channels,
-        nodes,
+        system_channels: get_all_system_channels(&code.nodes)?,
+        body,
         output: code.output.is_some(),
     }
     .render()
@@ -1,6 +1,11 @@
+use std::sync::Arc;
+
 use aes_gcm::{Aes256Gcm, Key};
 use firefly_client::models::Uri;
-use firefly_client::{NodeEvents, ReadNodeClient, WriteNodeClient};
+use firefly_client::{DeployTransport, EventSource, Proposer, ReadNodeClient};
+
+use crate::common::models::DeployConfig;
+use crate::common::prepared_contracts::PreparedContracts;
 
 mod create_agents_team;
 mod delete_agents_team;
@@ -15,8 +20,11 @@ mod save_agents_team;
 #[derive(Clone)]
 pub struct AgentsTeamsService {
     pub uri: Uri,
-    pub write_client: WriteNodeClient,
+    pub write_client: Arc<dyn DeployTransport>,
     pub read_client: ReadNodeClient,
-    pub observer_node_events: NodeEvents,
+    pub propose_scheduler: Arc<dyn Proposer>,
+    pub observer_node_events: Arc<dyn EventSource>,
     pub aes_encryption_key: Key<Aes256Gcm>,
+    pub deploy_config: DeployConfig,
+    pub prepared_contracts: PreparedContracts,
 }
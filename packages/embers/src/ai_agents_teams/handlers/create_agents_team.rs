@@ -6,6 +6,7 @@ use uuid::Uuid;
 use crate::ai_agents_teams::handlers::AgentsTeamsService;
 use crate::ai_agents_teams::models::{CreateAgentsTeamReq, CreateAgentsTeamResp, Graph};
 use crate::common::prepare_for_signing;
+use crate::common::prepared_contracts::SendEndpoint;
 use crate::common::tracing::record_trace;
 
 #[derive(Debug, Clone, Render)]
@@ -33,6 +34,7 @@ impl AgentsTeamsService {
     pub async fn prepare_create_agents_team_contract(
         &self,
         request: CreateAgentsTeamReq,
+        phlo_price: u64,
     ) -> anyhow::Result<CreateAgentsTeamResp> {
         record_trace!(request);
 
@@ -52,13 +54,17 @@ impl AgentsTeamsService {
         }
         .render()?;
 
-        let valid_after = self.write_client.clone().get_head_block_index().await?;
+        let valid_after = self.write_client.get_head_block_index().await?;
         Ok(CreateAgentsTeamResp {
             id: id.into(),
             version: version.into(),
             contract: prepare_for_signing()
+                .prepared_contracts(&self.prepared_contracts)
+                .endpoint(SendEndpoint::CreateAgentsTeam)
                 .code(contract)
                 .valid_after_block_number(valid_after)
+                .phlo_price(phlo_price)
+                .shard_id(self.deploy_config.shard_id.clone())
                 .call(),
         })
     }
@@ -76,10 +82,8 @@ impl AgentsTeamsService {
     ) -> anyhow::Result<DeployId> {
         record_trace!(contract);
 
-        let mut write_client = self.write_client.clone();
-
-        let deploy_id = write_client.deploy_signed_contract(contract).await?;
-        write_client.propose().await?;
+        let deploy_id = self.write_client.deploy_signed_contract(contract).await?;
+        self.propose_scheduler.enqueue(deploy_id.clone()).await?;
         Ok(deploy_id)
     }
 }
@@ -4,6 +4,7 @@ use firefly_client::rendering::Render;
 use crate::ai_agents_teams::handlers::AgentsTeamsService;
 use crate::ai_agents_teams::models::DeleteAgentsTeamResp;
 use crate::common::prepare_for_signing;
+use crate::common::prepared_contracts::SendEndpoint;
 use crate::common::tracing::record_trace;
 
 #[derive(Debug, Clone, Render)]
@@ -18,6 +19,7 @@ impl AgentsTeamsService {
     pub async fn prepare_delete_agents_team_contract(
         &self,
         id: String,
+        phlo_price: u64,
     ) -> anyhow::Result<DeleteAgentsTeamResp> {
         let contract = DeleteAgentsTeam {
             env_uri: self.uri.clone(),
@@ -25,11 +27,15 @@ impl AgentsTeamsService {
         }
         .render()?;
 
-        let valid_after = self.write_client.clone().get_head_block_index().await?;
+        let valid_after = self.write_client.get_head_block_index().await?;
         Ok(DeleteAgentsTeamResp {
             contract: prepare_for_signing()
+                .prepared_contracts(&self.prepared_contracts)
+                .endpoint(SendEndpoint::DeleteAgentsTeam)
                 .code(contract)
                 .valid_after_block_number(valid_after)
+                .phlo_price(phlo_price)
+                .shard_id(self.deploy_config.shard_id.clone())
                 .call(),
         })
     }
@@ -47,10 +53,8 @@ impl AgentsTeamsService {
     ) -> anyhow::Result<DeployId> {
         record_trace!(contract);
 
-        let mut write_client = self.write_client.clone();
-
-        let deploy_id = write_client.deploy_signed_contract(contract).await?;
-        write_client.propose().await?;
+        let deploy_id = self.write_client.deploy_signed_contract(contract).await?;
+        self.propose_scheduler.enqueue(deploy_id.clone()).await?;
         Ok(deploy_id)
     }
 }
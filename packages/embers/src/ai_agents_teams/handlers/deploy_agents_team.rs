@@ -11,6 +11,7 @@ use crate::ai_agents_teams::models::{
     DeploySignedAgentsTeamtReq,
 };
 use crate::common::prepare_for_signing;
+use crate::common::prepared_contracts::SendEndpoint;
 use crate::common::tracing::record_trace;
 
 #[derive(Debug, Clone, Render)]
@@ -33,10 +34,11 @@ impl AgentsTeamsService {
     pub async fn prepare_deploy_agents_team_contract(
         &self,
         request: DeployAgentsTeamReq,
+        phlo_price: u64,
     ) -> anyhow::Result<DeployAgentsTeamResp> {
         record_trace!(request);
 
-        let valid_after = self.write_client.clone().get_head_block_index().await?;
+        let valid_after = self.write_client.get_head_block_index().await?;
         let (graph, phlo_limit, deploy, system) = match request {
             DeployAgentsTeamReq::AgentsTeam {
                 id,
@@ -66,8 +68,12 @@ impl AgentsTeamsService {
                     deploy,
                     Some(
                         prepare_for_signing()
+                            .prepared_contracts(&self.prepared_contracts)
+                            .endpoint(SendEndpoint::DeployAgentsTeam)
                             .code(system_code)
                             .valid_after_block_number(valid_after)
+                            .phlo_price(phlo_price)
+                            .shard_id(self.deploy_config.shard_id.clone())
                             .call(),
                     ),
                 )
@@ -86,8 +92,12 @@ impl AgentsTeamsService {
 
         Ok(DeployAgentsTeamResp {
             contract: prepare_for_signing()
+                .prepared_contracts(&self.prepared_contracts)
+                .endpoint(SendEndpoint::DeployAgentsTeam)
                 .code(code)
                 .valid_after_block_number(valid_after)
+                .phlo_price(phlo_price)
+                .shard_id(self.deploy_config.shard_id.clone())
                 .timestamp(timestamp)
                 .phlo_limit(phlo_limit)
                 .call(),
@@ -108,7 +118,7 @@ impl AgentsTeamsService {
     ) -> anyhow::Result<DeployId> {
         record_trace!(request);
 
-        let mut write_client = self.write_client.clone();
+        let write_client = &self.write_client;
 
         let deploy_id = write_client
             .deploy_signed_contract(request.contract)
@@ -118,7 +128,7 @@ impl AgentsTeamsService {
             write_client.deploy_signed_contract(system).await?;
         }
 
-        write_client.propose().await?;
+        self.propose_scheduler.enqueue(deploy_id.clone()).await?;
         Ok(deploy_id)
     }
 }
@@ -16,6 +16,7 @@ use crate::ai_agents_teams::models::{
     PublishAgentsTeamToFireskyResp,
 };
 use crate::common::prepare_for_signing;
+use crate::common::prepared_contracts::SendEndpoint;
 use crate::common::tracing::record_trace;
 
 #[derive(Debug, Clone, Render)]
@@ -34,9 +35,10 @@ impl AgentsTeamsService {
         address: WalletAddress,
         id: String,
         request: PublishAgentsTeamToFireskyReq,
+        phlo_price: u64,
     ) -> anyhow::Result<PublishAgentsTeamToFireskyResp> {
         let agent_team = self
-            .get_agents_team(address, id, "latest".into())
+            .get_agents_team(address.clone(), id, "latest".into())
             .await?
             .ok_or_else(|| anyhow::anyhow!("agents team not found"))?;
 
@@ -98,11 +100,16 @@ impl AgentsTeamsService {
         }
         .render()?;
 
-        let valid_after = self.write_client.clone().get_head_block_index().await?;
+        let valid_after = self.write_client.get_head_block_index().await?;
         Ok(PublishAgentsTeamToFireskyResp {
             contract: prepare_for_signing()
+                .prepared_contracts(&self.prepared_contracts)
+                .endpoint(SendEndpoint::PublishAgentsTeamToFiresky)
+                .deployer(&address)
                 .code(contract)
                 .valid_after_block_number(valid_after)
+                .phlo_price(phlo_price)
+                .shard_id(self.deploy_config.shard_id.clone())
                 .call(),
         })
     }
@@ -120,10 +127,8 @@ impl AgentsTeamsService {
     ) -> anyhow::Result<DeployId> {
         record_trace!(contract);
 
-        let mut write_client = self.write_client.clone();
-
-        let deploy_id = write_client.deploy_signed_contract(contract).await?;
-        write_client.propose().await?;
+        let deploy_id = self.write_client.deploy_signed_contract(contract).await?;
+        self.propose_scheduler.enqueue(deploy_id.clone()).await?;
         Ok(deploy_id)
     }
 }
@@ -1,12 +1,15 @@
 use std::time::Duration;
 
 use anyhow::anyhow;
-use firefly_client::models::{DeployId, SignedCode, Uri};
+use chrono::Utc;
+use firefly_client::models::{DeployId, SignedCode, UnforgeableName, Uri};
 use firefly_client::rendering::Render;
+use firefly_client::{DeployOutcome, DeployTracker};
 
 use crate::ai_agents_teams::handlers::AgentsTeamsService;
 use crate::ai_agents_teams::models::{RunAgentsTeamReq, RunAgentsTeamResp};
 use crate::common::prepare_for_signing;
+use crate::common::prepared_contracts::SendEndpoint;
 use crate::common::tracing::record_trace;
 
 #[derive(Debug, Clone, Render)]
@@ -33,6 +36,7 @@ impl AgentsTeamsService {
     pub async fn prepare_run_agents_team_contract(
         &self,
         request: RunAgentsTeamReq,
+        phlo_price: u64,
     ) -> anyhow::Result<RunAgentsTeamResp> {
         record_trace!(request);
 
@@ -42,13 +46,36 @@ impl AgentsTeamsService {
         }
         .render()?;
 
-        let valid_after = self.write_client.clone().get_head_block_index().await?;
+        let write_client = &self.write_client;
+        let valid_after = write_client.get_head_block_index().await?;
+
+        let timestamp = Utc::now();
+        let result_channel = match &request.deployer {
+            Some(deployer) => {
+                let names = write_client
+                    .preview_private_names(deployer, timestamp, 1)
+                    .await?;
+                match names.into_iter().next() {
+                    Some(UnforgeableName::Private(id)) => Some(id),
+                    _ => return Err(anyhow!("node did not preview the result channel")),
+                }
+            }
+            None => None,
+        };
+
         Ok(RunAgentsTeamResp {
             contract: prepare_for_signing()
+                .prepared_contracts(&self.prepared_contracts)
+                .endpoint(SendEndpoint::RunAgentsTeam)
+                .maybe_deployer(request.deployer.map(Into::into).as_ref())
+                .timestamp(timestamp)
                 .code(contract)
                 .phlo_limit(request.phlo_limit)
                 .valid_after_block_number(valid_after)
+                .phlo_price(phlo_price)
+                .shard_id(self.deploy_config.shard_id.clone())
                 .call(),
+            result_channel,
         })
     }
 
@@ -65,18 +92,24 @@ impl AgentsTeamsService {
     ) -> anyhow::Result<serde_json::Value> {
         record_trace!(contract);
 
-        let mut write_client = self.write_client.clone();
+        let write_client = &self.write_client;
 
+        let valid_after = Some(contract.deploy_data()?.valid_after_block_number as _);
         let deploy_id = write_client.deploy_signed_contract(contract).await?;
 
-        let deploy_waiter = self
-            .observer_node_events
-            .wait_for_deploy(&deploy_id, Duration::from_secs(60));
-        let (_, finalized) =
-            tokio::try_join!(write_client.propose(), async { Ok(deploy_waiter.await) })?;
+        let deploy_tracker = DeployTracker::builder()
+            .write_client(self.write_client.clone())
+            .node_events(self.observer_node_events.clone())
+            .build();
+        let (_, outcome) =
+            tokio::try_join!(self.propose_scheduler.enqueue(deploy_id.clone()), async {
+                Ok(deploy_tracker
+                    .track(&deploy_id, valid_after, Duration::from_secs(60))
+                    .await)
+            })?;
 
-        if !finalized {
-            return Err(anyhow!("block is not finalized"));
+        if !matches!(outcome, DeployOutcome::Finalized { .. }) {
+            return Err(anyhow!("block is not finalized: {outcome:?}"));
         }
 
         let code = GetAgentsTeamResult { deploy_id }.render()?;
@@ -2,6 +2,7 @@ use std::convert::Infallible;
 
 use chrono::{DateTime, Utc};
 use firefly_client::models::{SignedCode, Uri, WalletAddress};
+use secp256k1::PublicKey;
 
 use crate::common::models::{PositiveNonZero, PreparedContract, RegistryDeploy};
 
@@ -125,11 +126,13 @@ pub struct RunAgentsTeamReq {
     pub prompt: String,
     pub phlo_limit: PositiveNonZero<i64>,
     pub agents_team: Uri,
+    pub deployer: Option<PublicKey>,
 }
 
 #[derive(Debug, Clone)]
 pub struct RunAgentsTeamResp {
     pub contract: PreparedContract,
+    pub result_channel: Option<String>,
 }
 
 #[derive(Debug, Clone)]
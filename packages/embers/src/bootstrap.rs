@@ -1,15 +1,25 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::Context;
 use firefly_client::helpers::insert_signed_signature;
 use firefly_client::models::{DeployData, Uri};
 use firefly_client::rendering::Render;
-use firefly_client::{NodeEvents, ReadNodeClient, WriteNodeClient};
-use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use firefly_client::signer::Signer;
+use firefly_client::{DeployTransport, EventSource, Proposer, ReadNodeClient, never_sent};
+use tracing::Instrument;
 
 use crate::ai_agents::handlers::AgentsService;
 use crate::ai_agents_teams::handlers::AgentsTeamsService;
+use crate::common::models::DeployConfig;
+use crate::common::prepared_contracts::PreparedContracts;
 use crate::testnet::handlers::TestnetService;
 use crate::wallets::handlers::WalletsService;
 
+const ENV_DEPLOY_INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const ENV_DEPLOY_MAX_BACKOFF: Duration = Duration::from_secs(64);
+const ENV_DEPLOY_MAX_ATTEMPTS: u32 = 10;
+
 #[derive(Debug, Clone, Render)]
 #[template(path = "ai_agents/init.rho")]
 struct InitAgentsEnv {
@@ -22,18 +32,21 @@ struct InitAgentsEnv {
 impl AgentsService {
     #[tracing::instrument(level = "info", skip_all, err(Debug))]
     pub async fn bootstrap(
-        mut write_client: WriteNodeClient,
+        write_client: Arc<dyn DeployTransport>,
         read_client: ReadNodeClient,
-        deployer_key: &SecretKey,
-        env_key: &SecretKey,
+        propose_scheduler: Arc<dyn Proposer>,
+        deployer_signer: Arc<dyn Signer>,
+        env_signer: &dyn Signer,
+        deploy_config: DeployConfig,
+        prepared_contracts: PreparedContracts,
     ) -> anyhow::Result<Self> {
-        let secp = Secp256k1::new();
-        let env_public_key = PublicKey::from_secret_key(&secp, env_key);
-        let deployer_public_key = PublicKey::from_secret_key(&secp, deployer_key);
+        let env_public_key = env_signer.public_key();
+        let deployer_public_key = deployer_signer.public_key();
 
         let timestamp = chrono::Utc::now();
         let version = 0;
-        let sig = insert_signed_signature(env_key, timestamp, &deployer_public_key, version);
+        let sig =
+            insert_signed_signature(env_signer, timestamp, &deployer_public_key, version).await?;
         let env_uri: Uri = env_public_key.into();
 
         let code = InitAgentsEnv {
@@ -46,19 +59,30 @@ impl AgentsService {
 
         tracing::debug!("code = {code}");
 
-        let deploy_data = DeployData::builder(code).timestamp(timestamp).build();
+        let deploy_data = DeployData::builder(code)
+            .timestamp(timestamp)
+            .phlo_price(deploy_config.phlo_price)
+            .shard_id(deploy_config.shard_id.clone())
+            .build();
 
         tracing::info!("AgentsService: Starting deploy...");
-        write_client
-            .deploy(deployer_key, deploy_data)
-            .await
-            .context("failed to deploy agents env")?;
-        tracing::info!("AgentsService: Deploy succeeded!");
+        deploy_env(
+            write_client.clone(),
+            propose_scheduler.clone(),
+            deployer_signer,
+            deploy_data,
+            "AgentsService",
+        )
+        .await
+        .context("failed to deploy agents env")?;
 
         Ok(Self {
             uri: env_uri,
             write_client,
             read_client,
+            propose_scheduler,
+            prepared_contracts,
+            deploy_config,
         })
     }
 }
@@ -73,22 +97,26 @@ struct InitAgentsTeamsEnv {
 }
 
 impl AgentsTeamsService {
+    #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(level = "info", skip_all, err(Debug))]
     pub async fn bootstrap(
-        mut write_client: WriteNodeClient,
+        write_client: Arc<dyn DeployTransport>,
         read_client: ReadNodeClient,
-        observer_node_events: NodeEvents,
-        deployer_key: &SecretKey,
-        env_key: &SecretKey,
+        propose_scheduler: Arc<dyn Proposer>,
+        observer_node_events: Arc<dyn EventSource>,
+        deployer_signer: Arc<dyn Signer>,
+        env_signer: &dyn Signer,
         aes_encryption_key: [u8; 32],
+        deploy_config: DeployConfig,
+        prepared_contracts: PreparedContracts,
     ) -> anyhow::Result<Self> {
-        let secp = Secp256k1::new();
-        let env_public_key = PublicKey::from_secret_key(&secp, env_key);
-        let deployer_public_key = PublicKey::from_secret_key(&secp, deployer_key);
+        let env_public_key = env_signer.public_key();
+        let deployer_public_key = deployer_signer.public_key();
 
         let timestamp = chrono::Utc::now();
         let version = 0;
-        let sig = insert_signed_signature(env_key, timestamp, &deployer_public_key, version);
+        let sig =
+            insert_signed_signature(env_signer, timestamp, &deployer_public_key, version).await?;
         let env_uri: Uri = env_public_key.into();
 
         let code = InitAgentsTeamsEnv {
@@ -101,21 +129,32 @@ impl AgentsTeamsService {
 
         tracing::debug!("code = {code}");
 
-        let deploy_data = DeployData::builder(code).timestamp(timestamp).build();
+        let deploy_data = DeployData::builder(code)
+            .timestamp(timestamp)
+            .phlo_price(deploy_config.phlo_price)
+            .shard_id(deploy_config.shard_id.clone())
+            .build();
 
         tracing::info!("AgentsTeamsService: Starting deploy...");
-        write_client
-            .deploy(deployer_key, deploy_data)
-            .await
-            .context("failed to deploy agents teams env")?;
-        tracing::info!("AgentsTeamsService: Deploy succeeded!");
+        deploy_env(
+            write_client.clone(),
+            propose_scheduler.clone(),
+            deployer_signer,
+            deploy_data,
+            "AgentsTeamsService",
+        )
+        .await
+        .context("failed to deploy agents teams env")?;
 
         Ok(Self {
             uri: env_uri,
             write_client,
             read_client,
+            propose_scheduler,
             observer_node_events,
             aes_encryption_key: aes_encryption_key.into(),
+            prepared_contracts,
+            deploy_config,
         })
     }
 }
@@ -130,22 +169,26 @@ struct InitWalletsEnv {
 }
 
 impl WalletsService {
+    #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(level = "info", skip_all, err(Debug))]
     pub async fn bootstrap(
-        mut write_client: WriteNodeClient,
+        write_client: Arc<dyn DeployTransport>,
         read_client: ReadNodeClient,
-        validator_node_events: NodeEvents,
-        observer_node_events: NodeEvents,
-        deployer_key: &SecretKey,
-        env_key: &SecretKey,
+        propose_scheduler: Arc<dyn Proposer>,
+        validator_node_events: Arc<dyn EventSource>,
+        observer_node_events: Arc<dyn EventSource>,
+        deployer_signer: Arc<dyn Signer>,
+        env_signer: &dyn Signer,
+        deploy_config: DeployConfig,
+        prepared_contracts: PreparedContracts,
     ) -> anyhow::Result<Self> {
-        let secp = Secp256k1::new();
-        let env_public_key = PublicKey::from_secret_key(&secp, env_key);
-        let deployer_public_key = PublicKey::from_secret_key(&secp, deployer_key);
+        let env_public_key = env_signer.public_key();
+        let deployer_public_key = deployer_signer.public_key();
 
         let timestamp = chrono::Utc::now();
         let version = 0;
-        let sig = insert_signed_signature(env_key, timestamp, &deployer_public_key, version);
+        let sig =
+            insert_signed_signature(env_signer, timestamp, &deployer_public_key, version).await?;
         let env_uri: Uri = env_public_key.into();
 
         let code = InitWalletsEnv {
@@ -158,21 +201,32 @@ impl WalletsService {
 
         tracing::debug!("code = {code}");
 
-        let deploy_data = DeployData::builder(code).timestamp(timestamp).build();
+        let deploy_data = DeployData::builder(code)
+            .timestamp(timestamp)
+            .phlo_price(deploy_config.phlo_price)
+            .shard_id(deploy_config.shard_id.clone())
+            .build();
 
         tracing::info!("WalletsService: Starting deploy...");
-        write_client
-            .deploy(deployer_key, deploy_data)
-            .await
-            .context("failed to deploy wallets env")?;
-        tracing::info!("WalletsService: Deploy succeeded!");
+        deploy_env(
+            write_client.clone(),
+            propose_scheduler.clone(),
+            deployer_signer,
+            deploy_data,
+            "WalletsService",
+        )
+        .await
+        .context("failed to deploy wallets env")?;
 
         Ok(Self {
             uri: env_uri,
             write_client,
             read_client,
+            propose_scheduler,
             validator_node_events,
             observer_node_events,
+            prepared_contracts,
+            deploy_config,
         })
     }
 }
@@ -187,21 +241,25 @@ struct InitTestnetEnv {
 }
 
 impl TestnetService {
+    #[allow(clippy::too_many_arguments)]
     #[tracing::instrument(level = "info", skip_all, err(Debug))]
     pub async fn bootstrap(
-        mut write_client: WriteNodeClient,
+        write_client: Arc<dyn DeployTransport>,
         read_client: ReadNodeClient,
-        observer_node_events: NodeEvents,
-        deployer_key: SecretKey,
-        env_key: &SecretKey,
+        propose_scheduler: Arc<dyn Proposer>,
+        observer_node_events: Arc<dyn EventSource>,
+        deployer_signer: Arc<dyn Signer>,
+        env_signer: &dyn Signer,
+        deploy_config: DeployConfig,
+        prepared_contracts: PreparedContracts,
     ) -> anyhow::Result<Self> {
-        let secp = Secp256k1::new();
-        let env_public_key = PublicKey::from_secret_key(&secp, env_key);
-        let deployer_public_key = PublicKey::from_secret_key(&secp, &deployer_key);
+        let env_public_key = env_signer.public_key();
+        let deployer_public_key = deployer_signer.public_key();
 
         let timestamp = chrono::Utc::now();
         let version = 0;
-        let sig = insert_signed_signature(env_key, timestamp, &deployer_public_key, version);
+        let sig =
+            insert_signed_signature(env_signer, timestamp, &deployer_public_key, version).await?;
         let env_uri: Uri = env_public_key.into();
 
         let code = InitTestnetEnv {
@@ -214,21 +272,159 @@ impl TestnetService {
 
         tracing::debug!("code = {code}");
 
-        let deploy_data = DeployData::builder(code).timestamp(timestamp).build();
+        let deploy_data = DeployData::builder(code)
+            .timestamp(timestamp)
+            .phlo_price(deploy_config.phlo_price)
+            .shard_id(deploy_config.shard_id.clone())
+            .build();
 
         tracing::info!("TestnetService: Starting deploy...");
-        write_client
-            .deploy(&deployer_key, deploy_data)
-            .await
-            .context("failed to deploy testnet env")?;
-        tracing::info!("TestnetService: Deploy succeeded!");
+        deploy_env(
+            write_client.clone(),
+            propose_scheduler.clone(),
+            deployer_signer.clone(),
+            deploy_data,
+            "TestnetService",
+        )
+        .await
+        .context("failed to deploy testnet env")?;
 
         Ok(Self {
             uri: env_uri,
-            service_key: deployer_key,
+            service_signer: deployer_signer,
             write_client,
             read_client,
+            propose_scheduler,
             observer_node_events,
+            prepared_contracts,
+            deploy_config,
         })
     }
 }
+
+/// Deploys an env contract. While no validator is reachable the deploy is retried in the
+/// background, so that embers still starts in a degraded mode and the env shows up once a validator
+/// does.
+async fn deploy_env(
+    write_client: Arc<dyn DeployTransport>,
+    propose_scheduler: Arc<dyn Proposer>,
+    deployer_signer: Arc<dyn Signer>,
+    deploy_data: DeployData,
+    service: &'static str,
+) -> anyhow::Result<()> {
+    match write_client
+        .deploy(&*deployer_signer, deploy_data.clone())
+        .await
+    {
+        Ok(_) => {
+            tracing::info!("{service}: Deploy succeeded!");
+            Ok(())
+        }
+        Err(err) if !write_client.is_healthy() && never_sent(err.as_ref()) => {
+            tracing::error!(error = ?err, "{service}: no reachable validator, retrying env deploy in {ENV_DEPLOY_INITIAL_BACKOFF:?}");
+            tokio::spawn(
+                async move {
+                    let mut backoff = ENV_DEPLOY_INITIAL_BACKOFF;
+                    for attempt in 1..=ENV_DEPLOY_MAX_ATTEMPTS {
+                        tokio::time::sleep(backoff).await;
+                        match write_client
+                            .deploy(&*deployer_signer, deploy_data.clone())
+                            .await
+                        {
+                            Ok(_) => {
+                                tracing::info!("{service}: Deploy succeeded!");
+                                propose_scheduler.request_propose();
+                                return;
+                            }
+                            Err(err)
+                                if never_sent(err.as_ref()) && attempt < ENV_DEPLOY_MAX_ATTEMPTS =>
+                            {
+                                backoff = (backoff * 2).min(ENV_DEPLOY_MAX_BACKOFF);
+                                tracing::warn!(error = ?err, "{service}: env deploy failed, retrying in {backoff:?}");
+                            }
+                            Err(err) => {
+                                tracing::error!(error = ?err, "{service}: env deploy failed after {attempt} attempts");
+                                return;
+                            }
+                        }
+                    }
+                }
+                .in_current_span(),
+            );
+            Ok(())
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use firefly_client::{ProposeScheduler, ValidatorEndpoint, WriteNodeClient};
+    use firefly_mock_node::MockNode;
+    use secp256k1::SecretKey;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    const KEY: &str = "f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc";
+
+    async fn deploy_nil(write_client: &WriteNodeClient) -> anyhow::Result<()> {
+        let propose_scheduler = ProposeScheduler::builder()
+            .write_client(write_client.clone())
+            .debounce(Duration::from_millis(10))
+            .build();
+
+        deploy_env(
+            Arc::new(write_client.clone()),
+            Arc::new(propose_scheduler),
+            Arc::new(SecretKey::from_str(KEY).unwrap()),
+            DeployData::builder("Nil".to_owned()).build(),
+            "TestService",
+        )
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_deploy_env_retries_in_the_background() {
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let url = format!("http://{addr}");
+        let write_client = WriteNodeClient::builder()
+            .endpoints(vec![ValidatorEndpoint {
+                deploy_service_url: url.clone(),
+                propose_service_url: url,
+            }])
+            .connect_deadline(Duration::ZERO)
+            .connect()
+            .await
+            .unwrap();
+
+        deploy_nil(&write_client).await.unwrap();
+
+        let node = MockNode::start_on(addr).await.unwrap();
+        tokio::time::timeout(Duration::from_secs(10), async {
+            while write_client.get_head_block_index().await.unwrap_or_default() == 0 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(node.deploys().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deploy_env_fails_once_sent() {
+        let node = MockNode::start().await.unwrap();
+        let write_client = WriteNodeClient::new(node.grpc_url(), node.grpc_url())
+            .await
+            .unwrap();
+        node.set_unavailable(true);
+
+        assert!(deploy_nil(&write_client).await.is_err());
+    }
+}
@@ -1,34 +1,113 @@
 use chrono::{DateTime, Utc};
+use firefly_client::models::WalletAddress;
 use firefly_client::models::casper::DeployDataProto;
 use prost::Message;
 
 use crate::common::models::{PositiveNonZero, PreparedContract};
+use crate::common::prepared_contracts::{PreparedContracts, SendEndpoint};
 
 pub mod api;
 pub mod blockchain;
+pub mod health;
 pub mod models;
+pub mod prepared_contracts;
 pub mod tracing;
 
+const DEFAULT_PHLO_LIMIT: i64 = 5_000_000;
+
+/// Encodes a deploy for the client to sign, tagged for `endpoint` and optionally bound to the
+/// `deployer` wallet.
 #[bon::builder]
 pub fn prepare_for_signing(
+    prepared_contracts: &PreparedContracts,
+    endpoint: SendEndpoint,
+    deployer: Option<&WalletAddress>,
     code: String,
     valid_after_block_number: u64,
+    phlo_price: u64,
+    #[builder(into)] shard_id: String,
     phlo_limit: Option<PositiveNonZero<i64>>,
     timestamp: Option<DateTime<Utc>>,
 ) -> PreparedContract {
     let timestamp = timestamp
         .unwrap_or_else(chrono::Utc::now)
         .timestamp_millis();
-    let contract = DeployDataProto {
+    let mut contract = DeployDataProto {
         term: code,
         timestamp,
-        phlo_price: 1,
-        phlo_limit: phlo_limit.map_or(5_000_000, |v| v.0),
+        phlo_price: phlo_price as _,
+        phlo_limit: phlo_limit.map_or(DEFAULT_PHLO_LIMIT, |v| v.0),
         valid_after_block_number: valid_after_block_number as _,
-        shard_id: "root".into(),
+        shard_id,
         ..Default::default()
+    };
+    prepared_contracts.tag(&mut contract, endpoint, deployer);
+
+    PreparedContract(contract.encode_to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use firefly_client::DeployPreview;
+
+    use super::*;
+    use crate::common::models::DeployConfig;
+    use crate::common::prepared_contracts::untagged;
+
+    fn prepared_contracts(config: &DeployConfig) -> PreparedContracts {
+        PreparedContracts::new(config, &[0; 32])
+    }
+
+    fn prepare(
+        prepared_contracts: &PreparedContracts,
+        phlo_limit: Option<PositiveNonZero<i64>>,
+    ) -> PreparedContract {
+        prepare_for_signing()
+            .prepared_contracts(prepared_contracts)
+            .endpoint(SendEndpoint::Transfer)
+            .code("Nil".to_owned())
+            .valid_after_block_number(42)
+            .phlo_price(3)
+            .shard_id("root")
+            .maybe_phlo_limit(phlo_limit)
+            .timestamp(DateTime::from_timestamp_millis(1_700_000_000_000).unwrap())
+            .call()
     }
-    .encode_to_vec();
 
-    PreparedContract(contract)
+    #[tokio::test]
+    async fn test_prepare_for_signing_encodes_deploy() {
+        let prepared_contracts = prepared_contracts(&DeployConfig::default());
+        let contract = prepare(&prepared_contracts, Some(PositiveNonZero(50_000)));
+
+        let preview = DeployPreview::decode(&contract.0).unwrap();
+        assert_eq!(untagged(&preview.term), "Nil");
+        assert_eq!(preview.timestamp.timestamp_millis(), 1_700_000_000_000);
+        assert_eq!(preview.phlo_limit, 50_000);
+        assert_eq!(preview.phlo_price, 3);
+        assert_eq!(preview.shard_id, "root");
+        assert_eq!(preview.valid_after_block_number, 42);
+        assert!(preview.deployer.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_prepare_for_signing_defaults_phlo_limit() {
+        let prepared_contracts = prepared_contracts(&DeployConfig::default());
+        let contract = prepare(&prepared_contracts, None);
+
+        let preview = DeployPreview::decode(&contract.0).unwrap();
+        assert_eq!(preview.phlo_limit, 5_000_000);
+    }
+
+    #[test]
+    fn test_check_arbitrary_code() {
+        assert!(DeployConfig::default().check_arbitrary_code().is_err());
+        assert!(
+            DeployConfig {
+                allow_arbitrary_code: true,
+                ..Default::default()
+            }
+            .check_arbitrary_code()
+            .is_ok()
+        );
+    }
 }
@@ -1,6 +1,8 @@
+use poem::web::Data;
 use poem_openapi::OpenApi;
 
-use crate::common::api::dtos::ApiTags;
+use crate::common::api::dtos::{ApiTags, ReadinessResponse};
+use crate::common::health::HealthService;
 
 pub mod dtos;
 
@@ -10,8 +12,13 @@ pub struct Service;
 #[allow(clippy::unused_async)]
 #[OpenApi(prefix_path = "/service", tag = ApiTags::Service)]
 impl Service {
-    #[oai(path = "/ready", method = "get")]
-    async fn ready(&self) -> poem::Result<()> {
+    #[oai(path = "/live", method = "get")]
+    async fn live(&self) -> poem::Result<()> {
         Ok(())
     }
+
+    #[oai(path = "/ready", method = "get")]
+    async fn ready(&self, Data(health): Data<&HealthService>) -> ReadinessResponse {
+        health.readiness().await.into()
+    }
 }
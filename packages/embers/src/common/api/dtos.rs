@@ -5,6 +5,7 @@ use chrono::{DateTime, Utc};
 use derive_more::From;
 use firefly_client::helpers::ShortHex;
 use firefly_client::models::{DeployId, Uri, WalletAddress};
+use poem::IntoResponse;
 use poem_openapi::payload::Json;
 use poem_openapi::registry::{MetaSchema, MetaSchemaRef, Registry};
 use poem_openapi::types::{
@@ -16,11 +17,13 @@ use poem_openapi::types::{
     ToJSON,
     Type,
 };
-use poem_openapi::{ApiResponse, NewType, Object, Tags};
+use poem_openapi::{ApiResponse, Enum, NewType, Object, Tags};
 use secp256k1::PublicKey;
+use structural_convert::StructuralConvert;
 
 use crate::ai_agents_teams::models::Graph;
 use crate::common::models;
+use crate::common::prepared_contracts::SignedContractError;
 
 impl<T> Type for models::PositiveNonZero<T>
 where
@@ -354,6 +357,45 @@ impl From<anyhow::Error> for InternalError {
     }
 }
 
+impl poem::error::ResponseError for models::PhloPriceOutOfBounds {
+    fn status(&self) -> poem::http::StatusCode {
+        poem::http::StatusCode::BAD_REQUEST
+    }
+}
+
+impl poem::error::ResponseError for models::ArbitraryCodeDisabled {
+    fn status(&self) -> poem::http::StatusCode {
+        poem::http::StatusCode::FORBIDDEN
+    }
+}
+
+impl poem::error::ResponseError for SignedContractError {
+    fn status(&self) -> poem::http::StatusCode {
+        match self {
+            Self::NotPrepared | Self::WrongEndpoint { .. } | Self::Invalid(_) => {
+                poem::http::StatusCode::BAD_REQUEST
+            }
+            Self::Expired => poem::http::StatusCode::GONE,
+            Self::AlreadySent => poem::http::StatusCode::CONFLICT,
+            Self::TooManySent => poem::http::StatusCode::SERVICE_UNAVAILABLE,
+            Self::InvalidSignature | Self::DeployerMismatch { .. } => {
+                poem::http::StatusCode::FORBIDDEN
+            }
+        }
+    }
+
+    fn as_response(&self) -> poem::Response {
+        let body = serde_json::json!({
+            "code": self.code(),
+            "description": self.to_string(),
+        });
+
+        let mut resp = poem::web::Json(body).into_response();
+        resp.set_status(self.status());
+        resp
+    }
+}
+
 #[derive(Debug, Clone, ApiResponse)]
 pub enum MaybeNotFound<T>
 where
@@ -452,3 +494,91 @@ impl From<DeployId> for SendResp {
         }
     }
 }
+
+#[derive(Debug, Clone, Eq, PartialEq, StructuralConvert, Enum)]
+#[convert(from(models::ReadinessStatus))]
+#[oai(rename_all = "lowercase")]
+pub enum ReadinessStatus {
+    Ready,
+    Degraded,
+    Unavailable,
+}
+
+#[derive(Debug, Clone, Object)]
+pub struct Probe {
+    pub ok: bool,
+    pub error: Option<String>,
+}
+
+impl From<models::Probe> for Probe {
+    fn from(value: models::Probe) -> Self {
+        Self {
+            ok: value.is_ok(),
+            error: value.error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Object, StructuralConvert)]
+#[convert(from(models::ValidatorReadiness))]
+pub struct ValidatorReadiness {
+    pub deploy_service_url: String,
+    pub propose_service_url: String,
+    pub active: bool,
+    pub deploy: Probe,
+    pub propose: Probe,
+}
+
+#[derive(Debug, Clone, Object, StructuralConvert)]
+#[convert(from(models::NodeEventsReadiness))]
+pub struct NodeEventsReadiness {
+    pub name: String,
+    pub connected: bool,
+    pub secs_since_last_event: Option<u64>,
+}
+
+#[derive(Debug, Clone, Object, StructuralConvert)]
+#[convert(from(models::EnvContractReadiness))]
+pub struct EnvContractReadiness {
+    pub name: String,
+    pub uri: Stringified<Uri>,
+    pub registered: Probe,
+}
+
+#[derive(Debug, Clone, Object, StructuralConvert)]
+#[convert(from(models::NetworkReadiness))]
+pub struct NetworkReadiness {
+    pub status: ReadinessStatus,
+    pub validators: Vec<ValidatorReadiness>,
+    pub observer: Probe,
+    pub node_events: Vec<NodeEventsReadiness>,
+    pub env_contracts: Vec<EnvContractReadiness>,
+}
+
+#[derive(Debug, Clone, Object, StructuralConvert)]
+#[convert(from(models::Readiness))]
+pub struct Readiness {
+    pub status: ReadinessStatus,
+    pub mainnet: NetworkReadiness,
+    pub testnet: NetworkReadiness,
+}
+
+#[derive(Debug, Clone, ApiResponse)]
+pub enum ReadinessResponse {
+    /// Requests can be served, possibly with some nodes or contracts unreachable.
+    #[oai(status = 200)]
+    Ready(Json<Readiness>),
+    #[oai(status = 503)]
+    Unavailable(Json<Readiness>),
+}
+
+impl From<models::Readiness> for ReadinessResponse {
+    fn from(value: models::Readiness) -> Self {
+        match value.status {
+            models::ReadinessStatus::Ready | models::ReadinessStatus::Degraded => {
+                Self::Ready(Json(value.into()))
+            }
+            models::ReadinessStatus::Unavailable => Self::Unavailable(Json(value.into())),
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use std::fmt::Display;
+use std::sync::Arc;
+use std::time::Duration;
+
+use firefly_client::models::Uri;
+use firefly_client::rendering::Render;
+use firefly_client::{DeployTransport, EventSource, ReadNodeClient};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+
+use crate::common::models::{
+    EnvContractReadiness,
+    NetworkReadiness,
+    NodeEventsReadiness,
+    Probe,
+    Readiness,
+    ReadinessStatus,
+    ValidatorReadiness,
+};
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const REGISTRY_CHECK_TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Render)]
+#[template(path = "common/lookup_env.rho")]
+struct LookupEnv {
+    env_uri: Uri,
+}
+
+/// Clients of a network and the env contracts deployed to it.
+#[derive(Clone)]
+pub struct NetworkClients {
+    pub write_client: Arc<dyn DeployTransport>,
+    pub read_client: ReadNodeClient,
+    pub node_events: Vec<(&'static str, Arc<dyn EventSource>)>,
+    pub env_contracts: Vec<(&'static str, Uri)>,
+    pub registry_checks: RegistryChecks,
+}
+
+/// Last registry lookup of the env contracts. Each lookup is an exploratory deploy, so the result
+/// is reused for [`REGISTRY_CHECK_TTL`] instead of being repeated on every probe.
+#[derive(Clone, Default)]
+pub struct RegistryChecks(Arc<Mutex<Option<RegistryCheck>>>);
+
+struct RegistryCheck {
+    checked_at: Instant,
+    env_contracts: Vec<EnvContractReadiness>,
+}
+
+#[derive(Clone)]
+pub struct HealthService {
+    pub mainnet: NetworkClients,
+    pub testnet: NetworkClients,
+}
+
+impl HealthService {
+    /// Checks both networks. Only mainnet can make Embers unavailable, an outage of testnet merely
+    /// degrades it.
+    #[tracing::instrument(level = "info", skip_all, ret(Debug, level = "trace"))]
+    pub async fn readiness(&self) -> Readiness {
+        let (mainnet, testnet) = tokio::join!(self.mainnet.readiness(), self.testnet.readiness());
+
+        Readiness {
+            status: mainnet
+                .status
+                .max(testnet.status.min(ReadinessStatus::Degraded)),
+            mainnet,
+            testnet,
+        }
+    }
+}
+
+impl NetworkClients {
+    async fn readiness(&self) -> NetworkReadiness {
+        let (validators, observer, env_contracts) = tokio::join!(
+            self.write_client.probe(PROBE_TIMEOUT),
+            with_timeout(self.read_client.status()),
+            self.env_contracts_readiness(),
+        );
+
+        let validators: Vec<_> = validators
+            .into_iter()
+            .map(|probe| ValidatorReadiness {
+                deploy_service_url: probe.endpoint.deploy_service_url,
+                propose_service_url: probe.endpoint.propose_service_url,
+                active: probe.active,
+                deploy: probe.deploy.into(),
+                propose: probe.propose.into(),
+            })
+            .collect();
+        let node_events: Vec<_> = self
+            .node_events
+            .iter()
+            .map(|(name, node_events)| {
+                let status = node_events.status();
+                NodeEventsReadiness {
+                    name,
+                    connected: status.connected,
+                    secs_since_last_event: status.since_last_event.map(|since| since.as_secs()),
+                }
+            })
+            .collect();
+
+        let answering = validators
+            .iter()
+            .filter(|validator| validator.deploy.is_ok() && validator.propose.is_ok())
+            .count();
+        let status = if answering == 0 || !observer.is_ok() {
+            ReadinessStatus::Unavailable
+        } else if answering == validators.len()
+            && node_events.iter().all(|node_events| node_events.connected)
+            && env_contracts
+                .iter()
+                .all(|env_contract| env_contract.registered.is_ok())
+        {
+            ReadinessStatus::Ready
+        } else {
+            ReadinessStatus::Degraded
+        };
+
+        NetworkReadiness {
+            status,
+            validators,
+            observer,
+            node_events,
+            env_contracts,
+        }
+    }
+
+    async fn env_contracts_readiness(&self) -> Vec<EnvContractReadiness> {
+        // held during the lookup, so that concurrent probes wait for it instead of repeating it
+        let mut last_check = self.registry_checks.0.lock().await;
+        if let Some(last_check) = &*last_check
+            && last_check.checked_at.elapsed() < REGISTRY_CHECK_TTL
+        {
+            return last_check.env_contracts.clone();
+        }
+
+        let env_contracts =
+            futures::future::join_all(self.env_contracts.iter().map(|(name, uri)| async {
+                EnvContractReadiness {
+                    name,
+                    uri: uri.clone(),
+                    registered: with_timeout(is_registered(&self.read_client, uri.clone())).await,
+                }
+            }))
+            .await;
+        *last_check = Some(RegistryCheck {
+            checked_at: Instant::now(),
+            env_contracts: env_contracts.clone(),
+        });
+        env_contracts
+    }
+}
+
+async fn with_timeout<E: Display>(check: impl Future<Output = Result<(), E>>) -> Probe {
+    tokio::time::timeout(PROBE_TIMEOUT, check)
+        .await
+        .map_or_else(
+            |_| Probe {
+                error: Some(format!("no answer within {PROBE_TIMEOUT:?}")),
+            },
+            Into::into,
+        )
+}
+
+async fn is_registered(read_client: &ReadNodeClient, env_uri: Uri) -> anyhow::Result<()> {
+    let code = LookupEnv { env_uri }.render()?;
+    let registered: bool = read_client.get_data(code).await?;
+    anyhow::ensure!(registered, "not found in the registry");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use firefly_client::WriteNodeClient;
+    use firefly_mock_node::MockNode;
+
+    use super::*;
+    use crate::common::api::dtos::ReadinessResponse;
+
+    async fn network(node: &MockNode) -> NetworkClients {
+        let write_client = WriteNodeClient::new(node.grpc_url(), node.grpc_url())
+            .await
+            .unwrap();
+
+        NetworkClients {
+            write_client: Arc::new(write_client),
+            read_client: ReadNodeClient::new(node.http_url()),
+            node_events: Vec::new(),
+            env_contracts: Vec::new(),
+            registry_checks: RegistryChecks::default(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_readiness_is_unavailable_only_without_mainnet() {
+        let mainnet = MockNode::start().await.unwrap();
+        let testnet = MockNode::start().await.unwrap();
+        let health = HealthService {
+            mainnet: network(&mainnet).await,
+            testnet: network(&testnet).await,
+        };
+
+        assert_eq!(health.readiness().await.status, ReadinessStatus::Ready);
+
+        testnet.set_unavailable(true);
+        let readiness = health.readiness().await;
+        assert_eq!(readiness.mainnet.status, ReadinessStatus::Ready);
+        assert_eq!(readiness.testnet.status, ReadinessStatus::Unavailable);
+        assert_eq!(readiness.status, ReadinessStatus::Degraded);
+        assert!(matches!(
+            ReadinessResponse::from(readiness),
+            ReadinessResponse::Ready(_)
+        ));
+
+        testnet.set_unavailable(false);
+        mainnet.set_unavailable(true);
+        let readiness = health.readiness().await;
+        assert_eq!(readiness.mainnet.status, ReadinessStatus::Unavailable);
+        assert_eq!(readiness.testnet.status, ReadinessStatus::Ready);
+        assert_eq!(readiness.status, ReadinessStatus::Unavailable);
+        assert!(matches!(
+            ReadinessResponse::from(readiness),
+            ReadinessResponse::Unavailable(_)
+        ));
+    }
+}
@@ -2,7 +2,9 @@ mod positive_non_zero;
 
 use chrono::{DateTime, Utc};
 use firefly_client::helpers::ShortHex;
+use firefly_client::models::Uri;
 use secp256k1::PublicKey;
+use serde::Deserialize;
 
 pub use self::positive_non_zero::*;
 
@@ -17,3 +19,141 @@ pub struct RegistryDeploy {
     pub uri_pub_key: PublicKey,
     pub signature: Vec<u8>,
 }
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("phlo price {price} is out of bounds [{min}, {max}]")]
+pub struct PhloPriceOutOfBounds {
+    pub price: u64,
+    pub min: u64,
+    pub max: u64,
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("deploying code that is not stored on chain is disabled on this network")]
+pub struct ArbitraryCodeDisabled;
+
+/// Per-network deploy parameters: the shard deploys are bound to and the phlo price clients may
+/// pick from.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct DeployConfig {
+    pub shard_id: String,
+    pub phlo_price: u64,
+    pub min_phlo_price: u64,
+    pub max_phlo_price: u64,
+    /// How long a prepared contract can be sent after it was prepared.
+    pub prepared_contract_ttl_secs: u64,
+    /// How many sent contracts are remembered until they expire, to refuse sending them twice.
+    pub max_sent_contracts: usize,
+    /// Whether clients may prepare code that is not stored on chain: raw agent code, unsaved agents
+    /// team graphs and tests.
+    pub allow_arbitrary_code: bool,
+}
+
+impl Default for DeployConfig {
+    fn default() -> Self {
+        Self {
+            shard_id: "root".to_owned(),
+            phlo_price: 1,
+            min_phlo_price: 1,
+            max_phlo_price: 1_000,
+            prepared_contract_ttl_secs: 600,
+            max_sent_contracts: 100_000,
+            allow_arbitrary_code: false,
+        }
+    }
+}
+
+impl DeployConfig {
+    /// Returns the requested phlo price if it is within bounds, or the network default.
+    pub const fn phlo_price(&self, requested: Option<u64>) -> Result<u64, PhloPriceOutOfBounds> {
+        match requested {
+            Some(price) if price < self.min_phlo_price || price > self.max_phlo_price => {
+                Err(PhloPriceOutOfBounds {
+                    price,
+                    min: self.min_phlo_price,
+                    max: self.max_phlo_price,
+                })
+            }
+            Some(price) => Ok(price),
+            None => Ok(self.phlo_price),
+        }
+    }
+
+    /// Fails unless clients may prepare code that is not stored on chain.
+    pub const fn check_arbitrary_code(&self) -> Result<(), ArbitraryCodeDisabled> {
+        if self.allow_arbitrary_code {
+            Ok(())
+        } else {
+            Err(ArbitraryCodeDisabled)
+        }
+    }
+}
+
+/// Overall state reported by the readiness probe, from best to worst.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ReadinessStatus {
+    Ready,
+    /// Requests are served, but some node or contract is not reachable.
+    Degraded,
+    /// Deploys or reads can't be served on the network, or on mainnet for the overall status.
+    Unavailable,
+}
+
+#[derive(Debug, Clone)]
+pub struct Probe {
+    pub error: Option<String>,
+}
+
+impl Probe {
+    pub const fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl<E: std::fmt::Display> From<Result<(), E>> for Probe {
+    fn from(value: Result<(), E>) -> Self {
+        Self {
+            error: value.err().map(|err| format!("{err:#}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ValidatorReadiness {
+    pub deploy_service_url: String,
+    pub propose_service_url: String,
+    pub active: bool,
+    pub deploy: Probe,
+    pub propose: Probe,
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeEventsReadiness {
+    pub name: &'static str,
+    pub connected: bool,
+    pub secs_since_last_event: Option<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct EnvContractReadiness {
+    pub name: &'static str,
+    pub uri: Uri,
+    pub registered: Probe,
+}
+
+#[derive(Debug, Clone)]
+pub struct NetworkReadiness {
+    pub status: ReadinessStatus,
+    pub validators: Vec<ValidatorReadiness>,
+    pub observer: Probe,
+    pub node_events: Vec<NodeEventsReadiness>,
+    pub env_contracts: Vec<EnvContractReadiness>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Readiness {
+    pub status: ReadinessStatus,
+    pub mainnet: NetworkReadiness,
+    pub testnet: NetworkReadiness,
+}
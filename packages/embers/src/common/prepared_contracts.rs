@@ -0,0 +1,556 @@
+use std::str::FromStr;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use blake2::digest::Mac;
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Blake2bMac, Digest};
+use chrono::Utc;
+use dashmap::DashMap;
+use dashmap::mapref::entry::Entry;
+use firefly_client::models::casper::DeployDataProto;
+use firefly_client::models::{SignedCode, WalletAddress};
+use firefly_client::{DeployPreview, DeployPreviewError};
+use prost::Message;
+
+use crate::common::models::DeployConfig;
+
+/// Starts the comment appended to prepared terms, followed by the endpoint, the deployer (or `-`),
+/// the expiration timestamp and the tag itself.
+const TAG_PREFIX: &str = "\n// prepared by embers: ";
+const NO_DEPLOYER: &str = "-";
+const EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Send endpoint a contract is prepared for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
+pub enum SendEndpoint {
+    #[display("/ai-agents/create/send")]
+    CreateAgent,
+    #[display("/ai-agents/deploy/send")]
+    DeployAgent,
+    #[display("/ai-agents/:id/save/send")]
+    SaveAgent,
+    #[display("/ai-agents/:id/delete/send")]
+    DeleteAgent,
+    #[display("/ai-agents-teams/create/send")]
+    CreateAgentsTeam,
+    #[display("/ai-agents-teams/deploy/send")]
+    DeployAgentsTeam,
+    #[display("/ai-agents-teams/run/send")]
+    RunAgentsTeam,
+    #[display("/ai-agents-teams/:id/save/send")]
+    SaveAgentsTeam,
+    #[display("/ai-agents-teams/:id/delete/send")]
+    DeleteAgentsTeam,
+    #[display("/ai-agents-teams/:address/:id/publish-to-firesky/send")]
+    PublishAgentsTeamToFiresky,
+    #[display("/wallets/transfer/send")]
+    Transfer,
+    #[display("/wallets/boost/send")]
+    Boost,
+    #[display("/testnet/deploy/send")]
+    DeployTest,
+}
+
+impl SendEndpoint {
+    const ALL: [Self; 13] = [
+        Self::CreateAgent,
+        Self::DeployAgent,
+        Self::SaveAgent,
+        Self::DeleteAgent,
+        Self::CreateAgentsTeam,
+        Self::DeployAgentsTeam,
+        Self::RunAgentsTeam,
+        Self::SaveAgentsTeam,
+        Self::DeleteAgentsTeam,
+        Self::PublishAgentsTeamToFiresky,
+        Self::Transfer,
+        Self::Boost,
+        Self::DeployTest,
+    ];
+}
+
+impl FromStr for SendEndpoint {
+    type Err = ();
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|endpoint| endpoint.to_string() == value)
+            .ok_or(())
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum SignedContractError {
+    #[error("contract was not prepared by this service")]
+    NotPrepared,
+
+    #[error("contract was prepared for {expected}, not {actual}")]
+    WrongEndpoint {
+        expected: SendEndpoint,
+        actual: SendEndpoint,
+    },
+
+    #[error("prepared contract has expired")]
+    Expired,
+
+    #[error("prepared contract was already sent")]
+    AlreadySent,
+
+    #[error("too many contracts are being sent, try again later")]
+    TooManySent,
+
+    #[error("invalid signed contract: {0}")]
+    Invalid(#[from] DeployPreviewError),
+
+    #[error("signature does not match the contract and deployer")]
+    InvalidSignature,
+
+    #[error("contract is signed by {}, not by {}", actual.as_ref(), expected.as_ref())]
+    DeployerMismatch {
+        expected: WalletAddress,
+        actual: WalletAddress,
+    },
+}
+
+impl SignedContractError {
+    /// Stable identifier of the error for API clients.
+    pub const fn code(&self) -> &'static str {
+        match self {
+            Self::NotPrepared => "contract_not_prepared",
+            Self::WrongEndpoint { .. } => "wrong_endpoint",
+            Self::Expired => "contract_expired",
+            Self::AlreadySent => "contract_already_sent",
+            Self::TooManySent => "too_many_contracts",
+            Self::Invalid(_) => "invalid_contract",
+            Self::InvalidSignature => "invalid_signature",
+            Self::DeployerMismatch { .. } => "deployer_mismatch",
+        }
+    }
+}
+
+/// What a prepared term was tagged with.
+#[derive(Debug, Clone)]
+struct Prepared {
+    endpoint: SendEndpoint,
+    deployer: Option<WalletAddress>,
+    expires_at: i64,
+}
+
+impl Prepared {
+    /// Reads the fields of the tag comment at the end of `term`.
+    fn parse(term: &str) -> Option<Self> {
+        let (_, fields) = term.rsplit_once(TAG_PREFIX)?;
+        let [endpoint, deployer, expires_at] =
+            fields.split(' ').collect::<Vec<_>>().try_into().ok()?;
+
+        Some(Self {
+            endpoint: endpoint.parse().ok()?,
+            deployer: match deployer {
+                NO_DEPLOYER => None,
+                deployer => Some(deployer.to_owned().try_into().ok()?),
+            },
+            expires_at: expires_at.parse().ok()?,
+        })
+    }
+}
+
+/// Authenticates the contracts handed out by the prepare endpoints, so that the send endpoints only
+/// relay what this service prepared.
+///
+/// Each prepared term ends with a comment naming the send endpoint, the deployer and the
+/// expiration, followed by a keyed hash of the whole deploy. Any replica sharing the key, or the
+/// same one after a restart, can check it without remembering anything. Only the contracts sent
+/// through this replica are remembered until they expire, to refuse sending them twice.
+#[derive(Debug, Clone)]
+pub struct PreparedContracts {
+    key: [u8; 32],
+    ttl: Duration,
+    max_sent_contracts: usize,
+    sent: Arc<DashMap<[u8; 32], i64>>,
+}
+
+fn contract_hash(contract: &[u8]) -> [u8; 32] {
+    Blake2b::<U32>::new()
+        .chain_update(contract)
+        .finalize()
+        .into()
+}
+
+/// Drops the sent contracts once they expire, until `sent` is dropped.
+async fn evict_expired(sent: Weak<DashMap<[u8; 32], i64>>) {
+    let mut interval = tokio::time::interval(EVICTION_INTERVAL);
+    loop {
+        interval.tick().await;
+        let Some(sent) = sent.upgrade() else {
+            return;
+        };
+        let now = Utc::now().timestamp();
+        sent.retain(|_, expires_at| *expires_at > now);
+    }
+}
+
+/// Contracts accepted by [`PreparedContracts::verify`] while they are deployed. Unless
+/// [`Self::sent`] is called, dropping it forgets them, so that a failed deploy can be retried.
+#[must_use = "the contracts can be sent again once this is dropped"]
+pub struct SendingContracts {
+    sent: Arc<DashMap<[u8; 32], i64>>,
+    hashes: Vec<[u8; 32]>,
+}
+
+impl SendingContracts {
+    /// Keeps refusing the contracts until they expire, once they are deployed.
+    pub fn sent(mut self) {
+        self.hashes.clear();
+    }
+}
+
+impl Drop for SendingContracts {
+    fn drop(&mut self) {
+        for hash in &self.hashes {
+            self.sent.remove(hash);
+        }
+    }
+}
+
+/// Removes the tag comment from a prepared `term`, if any. The comment must be the last line, so
+/// that no code hides after it.
+pub fn untagged(term: &str) -> &str {
+    term.rsplit_once(TAG_PREFIX)
+        .filter(|(_, tag)| !tag.contains('\n'))
+        .map_or(term, |(code, _)| code)
+}
+
+impl PreparedContracts {
+    /// Tags contracts with a key derived from `secret` and the shard, so that contracts prepared for
+    /// one network are refused by the other. Must be called within a tokio runtime, which runs the
+    /// eviction of sent contracts.
+    pub fn new(config: &DeployConfig, secret: &[u8; 32]) -> Self {
+        let key = Blake2bMac::<U32>::new_from_slice(secret)
+            .expect("32 bytes is a valid blake2b key length")
+            .chain_update(b"prepared contracts ")
+            .chain_update(config.shard_id.as_bytes())
+            .finalize()
+            .into_bytes()
+            .into();
+        let sent = Arc::default();
+        tokio::spawn(evict_expired(Arc::downgrade(&sent)));
+
+        Self {
+            key,
+            ttl: Duration::from_secs(config.prepared_contract_ttl_secs),
+            max_sent_contracts: config.max_sent_contracts,
+            sent,
+        }
+    }
+
+    fn mac(&self) -> Blake2bMac<U32> {
+        Blake2bMac::new_from_slice(&self.key).expect("32 bytes is a valid blake2b key length")
+    }
+
+    /// Appends the tag comment to the term of `deploy`, binding it to `endpoint` and, when given,
+    /// to the `deployer` wallet until the ttl runs out.
+    pub fn tag(
+        &self,
+        deploy: &mut DeployDataProto,
+        endpoint: SendEndpoint,
+        deployer: Option<&WalletAddress>,
+    ) {
+        let expires_at = Utc::now().timestamp() + self.ttl.as_secs() as i64;
+        let deployer = deployer.map_or(NO_DEPLOYER, |deployer| deployer.as_ref());
+        deploy.term = format!(
+            "{}{TAG_PREFIX}{endpoint} {deployer} {expires_at}",
+            deploy.term
+        );
+
+        let tag = self.mac().chain_update(deploy.encode_to_vec()).finalize();
+        deploy.term = format!("{} {}", deploy.term, hex::encode(tag.into_bytes()));
+    }
+
+    /// Checks that each of `contracts` was prepared for `endpoint`, has not expired, is validly
+    /// signed by the wallet it was prepared for, and by `wallet` when the send request names one.
+    /// Then refuses them until the returned [`SendingContracts`] is dropped, or until they expire
+    /// once it is marked as sent. Either all of them are accepted or none is.
+    pub fn verify<'a>(
+        &self,
+        contracts: impl IntoIterator<Item = &'a SignedCode>,
+        endpoint: SendEndpoint,
+        wallet: Option<&WalletAddress>,
+    ) -> Result<SendingContracts, SignedContractError> {
+        let verified = contracts
+            .into_iter()
+            .map(|contract| self.verify_one(contract, endpoint, wallet))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if self.sent.len() + verified.len() > self.max_sent_contracts {
+            return Err(SignedContractError::TooManySent);
+        }
+
+        let mut sending = SendingContracts {
+            sent: self.sent.clone(),
+            hashes: Vec::with_capacity(verified.len()),
+        };
+        for (hash, expires_at) in verified {
+            match self.sent.entry(hash) {
+                Entry::Occupied(_) => return Err(SignedContractError::AlreadySent),
+                Entry::Vacant(entry) => {
+                    entry.insert(expires_at);
+                }
+            }
+            sending.hashes.push(hash);
+        }
+
+        Ok(sending)
+    }
+
+    /// Checks a single contract, returning its hash and expiration.
+    fn verify_one(
+        &self,
+        contract: &SignedCode,
+        endpoint: SendEndpoint,
+        wallet: Option<&WalletAddress>,
+    ) -> Result<([u8; 32], i64), SignedContractError> {
+        let mut deploy = contract.deploy_data().map_err(DeployPreviewError::from)?;
+        let (term, tag) = deploy
+            .term
+            .rsplit_once(' ')
+            .ok_or(SignedContractError::NotPrepared)?;
+        let tag = hex::decode(tag).map_err(|_| SignedContractError::NotPrepared)?;
+        let prepared = Prepared::parse(term).ok_or(SignedContractError::NotPrepared)?;
+
+        deploy.term = term.to_owned();
+        self.mac()
+            .chain_update(deploy.encode_to_vec())
+            .verify_slice(&tag)
+            .map_err(|_| SignedContractError::NotPrepared)?;
+
+        if prepared.endpoint != endpoint {
+            return Err(SignedContractError::WrongEndpoint {
+                expected: prepared.endpoint,
+                actual: endpoint,
+            });
+        }
+
+        if prepared.expires_at <= Utc::now().timestamp() {
+            return Err(SignedContractError::Expired);
+        }
+
+        let deployer = DeployPreview::try_from(contract.clone())?
+            .deployer
+            .filter(|deployer| deployer.signature_valid)
+            .ok_or(SignedContractError::InvalidSignature)?;
+
+        if let Some(expected) = prepared
+            .deployer
+            .iter()
+            .chain(wallet)
+            .find(|expected| **expected != deployer.wallet_address)
+        {
+            return Err(SignedContractError::DeployerMismatch {
+                expected: expected.clone(),
+                actual: deployer.wallet_address,
+            });
+        }
+
+        Ok((contract_hash(&contract.contract), prepared.expires_at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prost::Message as _;
+    use secp256k1::{Message, Secp256k1, SecretKey};
+
+    use super::*;
+
+    const KEY: &str = "f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc";
+
+    fn key() -> SecretKey {
+        SecretKey::from_str(KEY).unwrap()
+    }
+
+    fn prepared_contracts(config: &DeployConfig) -> PreparedContracts {
+        PreparedContracts::new(config, &[0; 32])
+    }
+
+    fn prepare(
+        prepared_contracts: &PreparedContracts,
+        deployer: Option<&WalletAddress>,
+        phlo_limit: i64,
+    ) -> DeployDataProto {
+        let mut deploy = DeployDataProto {
+            term: "Nil".to_owned(),
+            timestamp: 1_700_000_000_000,
+            phlo_price: 3,
+            phlo_limit,
+            valid_after_block_number: 42,
+            shard_id: "root".to_owned(),
+            ..Default::default()
+        };
+        prepared_contracts.tag(&mut deploy, SendEndpoint::Transfer, deployer);
+        deploy
+    }
+
+    fn sign(deploy: &DeployDataProto) -> SignedCode {
+        let secp = Secp256k1::new();
+        let contract = deploy.encode_to_vec();
+        let hash = Blake2b::<U32>::new().chain_update(&contract).finalize();
+
+        SignedCode {
+            sig: secp
+                .sign_ecdsa(Message::from_digest(hash.into()), &key())
+                .serialize_der()
+                .to_vec(),
+            sig_algorithm: "secp256k1".to_owned(),
+            deployer: key().public_key(&secp).serialize_uncompressed().to_vec(),
+            contract,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_prepared_contract_is_sent_once() {
+        let prepared_contracts = prepared_contracts(&DeployConfig::default());
+        let signed = sign(&prepare(&prepared_contracts, None, 1));
+
+        assert!(matches!(
+            prepared_contracts.verify([&signed], SendEndpoint::Boost, None),
+            Err(SignedContractError::WrongEndpoint { .. })
+        ));
+        let sending = prepared_contracts
+            .verify([&signed], SendEndpoint::Transfer, None)
+            .unwrap();
+        assert!(matches!(
+            prepared_contracts.verify([&signed], SendEndpoint::Transfer, None),
+            Err(SignedContractError::AlreadySent)
+        ));
+        sending.sent();
+        assert!(matches!(
+            prepared_contracts.verify([&signed], SendEndpoint::Transfer, None),
+            Err(SignedContractError::AlreadySent)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_prepared_contract_is_released_when_not_sent() {
+        let prepared_contracts = prepared_contracts(&DeployConfig::default());
+        let signed = sign(&prepare(&prepared_contracts, None, 1));
+
+        drop(
+            prepared_contracts
+                .verify([&signed], SendEndpoint::Transfer, None)
+                .unwrap(),
+        );
+        prepared_contracts
+            .verify([&signed], SendEndpoint::Transfer, None)
+            .unwrap()
+            .sent();
+    }
+
+    #[tokio::test]
+    async fn test_prepared_contract_is_accepted_by_replicas() {
+        let signed = sign(&prepare(
+            &prepared_contracts(&DeployConfig::default()),
+            None,
+            1,
+        ));
+
+        prepared_contracts(&DeployConfig::default())
+            .verify([&signed], SendEndpoint::Transfer, None)
+            .unwrap()
+            .sent();
+
+        let other = PreparedContracts::new(&DeployConfig::default(), &[1; 32]);
+        assert!(matches!(
+            other.verify([&signed], SendEndpoint::Transfer, None),
+            Err(SignedContractError::NotPrepared)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_prepared_contract_rejects_tampering() {
+        let prepared_contracts = prepared_contracts(&DeployConfig::default());
+        let deploy = prepare(&prepared_contracts, None, 1);
+
+        let mut tampered = deploy.clone();
+        tampered.term = tampered.term.replacen("Nil", "@0!(1)", 1);
+        assert!(matches!(
+            prepared_contracts.verify([&sign(&tampered)], SendEndpoint::Transfer, None),
+            Err(SignedContractError::NotPrepared)
+        ));
+
+        let mut tampered = deploy.clone();
+        tampered.term = format!("{}\n@0!(1)", tampered.term);
+        assert_eq!(untagged(&tampered.term), tampered.term);
+        assert!(matches!(
+            prepared_contracts.verify([&sign(&tampered)], SendEndpoint::Transfer, None),
+            Err(SignedContractError::NotPrepared)
+        ));
+
+        let mut tampered = deploy;
+        tampered.phlo_limit += 1;
+        assert!(matches!(
+            prepared_contracts.verify([&sign(&tampered)], SendEndpoint::Transfer, None),
+            Err(SignedContractError::NotPrepared)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_prepared_contract_checks_deployer() {
+        let prepared_contracts = prepared_contracts(&DeployConfig::default());
+        let other: WalletAddress = SecretKey::from_byte_array([1; 32])
+            .unwrap()
+            .public_key(&Secp256k1::new())
+            .into();
+
+        let signed = sign(&prepare(&prepared_contracts, Some(&other), 1));
+        assert!(matches!(
+            prepared_contracts.verify([&signed], SendEndpoint::Transfer, None),
+            Err(SignedContractError::DeployerMismatch { expected, .. }) if expected == other
+        ));
+
+        let signed = sign(&prepare(&prepared_contracts, None, 1));
+        assert!(matches!(
+            prepared_contracts.verify([&signed], SendEndpoint::Transfer, Some(&other)),
+            Err(SignedContractError::DeployerMismatch { expected, .. }) if expected == other
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_prepared_contract_expires() {
+        let prepared_contracts = prepared_contracts(&DeployConfig {
+            prepared_contract_ttl_secs: 0,
+            ..Default::default()
+        });
+        let signed = sign(&prepare(&prepared_contracts, None, 1));
+
+        assert!(matches!(
+            prepared_contracts.verify([&signed], SendEndpoint::Transfer, None),
+            Err(SignedContractError::Expired)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_prepared_contracts_are_sent_together() {
+        let prepared_contracts = prepared_contracts(&DeployConfig {
+            max_sent_contracts: 2,
+            ..Default::default()
+        });
+        let first = sign(&prepare(&prepared_contracts, None, 1));
+        let second = sign(&prepare(&prepared_contracts, None, 2));
+        let third = sign(&prepare(&prepared_contracts, None, 3));
+
+        assert!(matches!(
+            prepared_contracts.verify([&first, &first], SendEndpoint::Transfer, None),
+            Err(SignedContractError::AlreadySent)
+        ));
+        prepared_contracts
+            .verify([&first, &second], SendEndpoint::Transfer, None)
+            .unwrap()
+            .sent();
+        assert!(matches!(
+            prepared_contracts.verify([&third], SendEndpoint::Transfer, None),
+            Err(SignedContractError::TooManySent)
+        ));
+    }
+}
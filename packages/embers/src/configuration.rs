@@ -1,38 +1,79 @@
+use std::net::SocketAddr;
+
 use anyhow::Context;
 use figment::Figment;
 use figment::providers::Env;
-use secp256k1::SecretKey;
+use firefly_client::signer::SignerConfig;
+use firefly_client::{ConnectionOptions, ValidatorEndpoint};
 use serde::Deserialize;
 
+use crate::common::models::DeployConfig;
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct MainNet {
     pub deploy_service_url: String,
     pub propose_service_url: String,
+    #[serde(default)]
+    pub fallback_validators: Vec<ValidatorEndpoint>,
+    #[serde(default)]
+    pub validator_connect_deadline_secs: Option<u64>,
+    #[serde(default)]
+    pub connection: ConnectionOptions,
     pub validator_ws_api_url: String,
     pub observer_url: String,
+    #[serde(default)]
+    pub observer_grpc_url: Option<String>,
     pub observer_ws_api_url: String,
-    pub service_key: SecretKey,
-    pub wallets_env_key: SecretKey,
-    pub agents_env_key: SecretKey,
-    pub agents_teams_env_key: SecretKey,
+    pub service_key: SignerConfig,
+    pub wallets_env_key: SignerConfig,
+    pub agents_env_key: SignerConfig,
+    pub agents_teams_env_key: SignerConfig,
+    #[serde(default, deserialize_with = "deserialize_deploy_config")]
+    pub deploy: DeployConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct TestNet {
     pub deploy_service_url: String,
     pub propose_service_url: String,
+    #[serde(default)]
+    pub fallback_validators: Vec<ValidatorEndpoint>,
+    #[serde(default)]
+    pub validator_connect_deadline_secs: Option<u64>,
+    #[serde(default)]
+    pub connection: ConnectionOptions,
     pub validator_ws_api_url: String,
     pub observer_url: String,
+    #[serde(default)]
+    pub observer_grpc_url: Option<String>,
     pub observer_ws_api_url: String,
-    pub service_key: SecretKey,
-    pub env_key: SecretKey,
+    pub service_key: SignerConfig,
+    pub env_key: SignerConfig,
+    #[serde(default, deserialize_with = "deserialize_deploy_config")]
+    pub deploy: DeployConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OtlpConfig {
+    pub endpoint: String,
+    #[serde(default = "default_service_name")]
+    pub service_name: String,
+}
+
+fn default_service_name() -> String {
+    "embers".to_owned()
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub address: String,
     pub port: u16,
+    /// Where to serve Prometheus metrics, apart from the API. Metrics are not collected when unset.
+    #[serde(default)]
+    pub metrics_address: Option<SocketAddr>,
     pub log_level: String,
+    #[serde(default)]
+    pub otlp: Option<OtlpConfig>,
     pub mainnet: MainNet,
     pub testnet: TestNet,
     #[serde(deserialize_with = "deserialize_hex_key")]
@@ -55,3 +96,50 @@ where
     hex::decode_to_slice(&s, &mut array).map_err(serde::de::Error::custom)?;
     Ok(array)
 }
+
+fn deserialize_deploy_config<'de, D>(deserializer: D) -> Result<DeployConfig, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let config = DeployConfig::deserialize(deserializer)?;
+    config
+        .phlo_price(Some(config.phlo_price))
+        .map_err(|err| serde::de::Error::custom(format!("default {err}")))?;
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[derive(Debug, Deserialize)]
+    struct Network {
+        #[serde(default, deserialize_with = "deserialize_deploy_config")]
+        deploy: DeployConfig,
+    }
+
+    #[test]
+    fn test_deploy_config_defaults() {
+        let network: Network = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(network.deploy.phlo_price, 1);
+
+        let network: Network =
+            serde_json::from_value(json!({"deploy": {"phlo_price": 5}})).unwrap();
+        assert_eq!(network.deploy.phlo_price, 5);
+    }
+
+    #[test]
+    fn test_deploy_config_rejects_price_out_of_bounds() {
+        for deploy in [
+            json!({"phlo_price": 0}),
+            json!({"phlo_price": 1001}),
+            json!({"phlo_price": 5, "min_phlo_price": 10, "max_phlo_price": 20}),
+            json!({"min_phlo_price": 10, "max_phlo_price": 5}),
+        ] {
+            let err = serde_json::from_value::<Network>(json!({ "deploy": deploy })).unwrap_err();
+            assert!(err.to_string().contains("out of bounds"), "{err}");
+        }
+    }
+}
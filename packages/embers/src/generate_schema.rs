@@ -1,3 +1,6 @@
+// The schema only needs the api, the services are constructed by the server binary.
+#![allow(dead_code)]
+
 mod ai_agents;
 mod ai_agents_teams;
 mod common;
@@ -1,5 +1,17 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::Context;
-use firefly_client::{NodeEvents, ReadNodeClient, WriteNodeClient};
+use firefly_client::{
+    DeployTransport,
+    EventSource,
+    NodeEvents,
+    ProposeScheduler,
+    Proposer,
+    ReadNodeClient,
+    ValidatorEndpoint,
+    WriteNodeClient,
+};
 use poem::listener::TcpListener;
 use poem::middleware::{Compression, Cors, NormalizePath, RequestId, Tracing, TrailingSlash};
 use poem::{EndpointExt, Route, Server};
@@ -11,6 +23,8 @@ use crate::ai_agents::handlers::AgentsService;
 use crate::ai_agents_teams::api::AIAgentsTeams;
 use crate::ai_agents_teams::handlers::AgentsTeamsService;
 use crate::common::api::Service;
+use crate::common::health::{HealthService, NetworkClients, RegistryChecks};
+use crate::common::prepared_contracts::PreparedContracts;
 use crate::configuration::collect_config;
 use crate::testnet::api::Testnet;
 use crate::testnet::handlers::TestnetService;
@@ -22,6 +36,8 @@ mod ai_agents_teams;
 mod bootstrap;
 mod common;
 mod configuration;
+mod metrics;
+mod telemetry;
 mod testnet;
 mod wallets;
 
@@ -29,90 +45,199 @@ mod wallets;
 async fn main() -> anyhow::Result<()> {
     let config = collect_config().context("failed to read configuration")?;
 
-    let env_filter = tracing_subscriber::EnvFilter::try_new(config.log_level)
-        .context("failed to init log filter")?;
-
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
-        .event_format(
-            tracing_subscriber::fmt::format()
-                .with_file(true)
-                .with_line_number(true),
-        )
-        .init();
+    let tracer_provider = telemetry::init(&config.log_level, config.otlp.as_ref())?;
 
-    let read_client = ReadNodeClient::new(config.mainnet.observer_url);
-    let validator_node_events = NodeEvents::new(&config.mainnet.validator_ws_api_url);
-    let observer_node_events = NodeEvents::new(&config.mainnet.observer_ws_api_url);
+    if let Some(address) = config.metrics_address {
+        metrics::install(address).context("failed to install metrics recorder")?;
+    }
 
-    let testnet_read_client = ReadNodeClient::new(config.testnet.observer_url);
-    let _testnet_validator_node_events = NodeEvents::new(&config.testnet.validator_ws_api_url);
-    let testnet_observer_node_events = NodeEvents::new(&config.testnet.observer_ws_api_url);
+    let read_client = match config.mainnet.observer_grpc_url {
+        Some(url) => ReadNodeClient::grpc(url, &config.mainnet.connection).await?,
+        None => {
+            ReadNodeClient::with_options(config.mainnet.observer_url, &config.mainnet.connection)?
+        }
+    };
+    let testnet_read_client = match config.testnet.observer_grpc_url {
+        Some(url) => ReadNodeClient::grpc(url, &config.testnet.connection).await?,
+        None => {
+            ReadNodeClient::with_options(config.testnet.observer_url, &config.testnet.connection)?
+        }
+    };
+    let testnet_validator_node_events: Arc<dyn EventSource> = Arc::new(NodeEvents::with_options(
+        &config.testnet.validator_ws_api_url,
+        config.testnet.connection.clone(),
+        None,
+    )?);
 
     let ((agents_service, agents_teams_service, wallets_service), testnet_service) = try_join!(
         async {
-            let mut write_client = WriteNodeClient::new(
-                config.mainnet.deploy_service_url,
-                config.mainnet.propose_service_url,
-            )
-            .await?;
+            let write_client = WriteNodeClient::builder()
+                .endpoints(
+                    std::iter::once(ValidatorEndpoint {
+                        deploy_service_url: config.mainnet.deploy_service_url,
+                        propose_service_url: config.mainnet.propose_service_url,
+                    })
+                    .chain(config.mainnet.fallback_validators)
+                    .collect(),
+                )
+                .maybe_connect_deadline(
+                    config
+                        .mainnet
+                        .validator_connect_deadline_secs
+                        .map(Duration::from_secs),
+                )
+                .options(config.mainnet.connection.clone())
+                .connect()
+                .await?;
+            let propose_scheduler: Arc<dyn Proposer> = Arc::new(
+                ProposeScheduler::builder()
+                    .write_client(write_client.clone())
+                    .build(),
+            );
+
+            let validator_node_events: Arc<dyn EventSource> = Arc::new(NodeEvents::with_options(
+                &config.mainnet.validator_ws_api_url,
+                config.mainnet.connection.clone(),
+                Some(write_client.clone()),
+            )?);
+            let observer_node_events: Arc<dyn EventSource> = Arc::new(NodeEvents::with_options(
+                &config.mainnet.observer_ws_api_url,
+                config.mainnet.connection.clone(),
+                Some(write_client.clone()),
+            )?);
+            let write_client: Arc<dyn DeployTransport> = Arc::new(write_client);
+
+            let service_signer = config.mainnet.service_key.into_signer().await?;
+            let agents_env_signer = config.mainnet.agents_env_key.into_signer().await?;
+            let agents_teams_env_signer = config.mainnet.agents_teams_env_key.into_signer().await?;
+            let wallets_env_signer = config.mainnet.wallets_env_key.into_signer().await?;
+            let prepared_contracts =
+                PreparedContracts::new(&config.mainnet.deploy, &config.aes_encryption_key);
 
             let agents_service = AgentsService::bootstrap(
                 write_client.clone(),
                 read_client.clone(),
-                &config.mainnet.service_key,
-                &config.mainnet.agents_env_key,
+                propose_scheduler.clone(),
+                service_signer.clone(),
+                &*agents_env_signer,
+                config.mainnet.deploy.clone(),
+                prepared_contracts.clone(),
             )
             .await?;
 
             let agents_teams_service = AgentsTeamsService::bootstrap(
                 write_client.clone(),
                 read_client.clone(),
+                propose_scheduler.clone(),
                 observer_node_events.clone(),
-                &config.mainnet.service_key,
-                &config.mainnet.agents_teams_env_key,
+                service_signer.clone(),
+                &*agents_teams_env_signer,
                 config.aes_encryption_key,
+                config.mainnet.deploy.clone(),
+                prepared_contracts.clone(),
             )
             .await?;
 
             let wallets_service = WalletsService::bootstrap(
                 write_client.clone(),
                 read_client,
+                propose_scheduler.clone(),
                 validator_node_events,
                 observer_node_events,
-                &config.mainnet.service_key,
-                &config.mainnet.wallets_env_key,
+                service_signer,
+                &*wallets_env_signer,
+                config.mainnet.deploy,
+                prepared_contracts,
             )
             .await?;
 
-            // Try to propose, but don't fail if validator is busy
-            let _ = write_client.propose().await;
+            propose_scheduler.request_propose();
 
             anyhow::Ok((agents_service, agents_teams_service, wallets_service))
         },
         async {
-            let mut testnet_write_client = WriteNodeClient::new(
-                config.testnet.deploy_service_url,
-                config.testnet.propose_service_url,
-            )
-            .await?;
+            let testnet_write_client = WriteNodeClient::builder()
+                .endpoints(
+                    std::iter::once(ValidatorEndpoint {
+                        deploy_service_url: config.testnet.deploy_service_url,
+                        propose_service_url: config.testnet.propose_service_url,
+                    })
+                    .chain(config.testnet.fallback_validators)
+                    .collect(),
+                )
+                .maybe_connect_deadline(
+                    config
+                        .testnet
+                        .validator_connect_deadline_secs
+                        .map(Duration::from_secs),
+                )
+                .options(config.testnet.connection.clone())
+                .connect()
+                .await?;
+            let testnet_propose_scheduler: Arc<dyn Proposer> = Arc::new(
+                ProposeScheduler::builder()
+                    .write_client(testnet_write_client.clone())
+                    .build(),
+            );
+
+            let testnet_observer_node_events: Arc<dyn EventSource> =
+                Arc::new(NodeEvents::with_options(
+                    &config.testnet.observer_ws_api_url,
+                    config.testnet.connection.clone(),
+                    Some(testnet_write_client.clone()),
+                )?);
+            let testnet_write_client: Arc<dyn DeployTransport> = Arc::new(testnet_write_client);
+
+            let testnet_service_signer = config.testnet.service_key.into_signer().await?;
+            let testnet_env_signer = config.testnet.env_key.into_signer().await?;
+            let testnet_prepared_contracts =
+                PreparedContracts::new(&config.testnet.deploy, &config.aes_encryption_key);
 
             let testnet_service = TestnetService::bootstrap(
                 testnet_write_client.clone(),
                 testnet_read_client,
+                testnet_propose_scheduler.clone(),
                 testnet_observer_node_events,
-                config.testnet.service_key,
-                &config.testnet.env_key,
+                testnet_service_signer,
+                &*testnet_env_signer,
+                config.testnet.deploy,
+                testnet_prepared_contracts,
             )
             .await?;
 
-            // Try to propose, but don't fail if validator is busy
-            let _ = testnet_write_client.propose().await;
+            testnet_propose_scheduler.request_propose();
 
             anyhow::Ok(testnet_service)
         },
     )?;
 
+    let health_service = HealthService {
+        mainnet: NetworkClients {
+            write_client: wallets_service.write_client.clone(),
+            read_client: wallets_service.read_client.clone(),
+            node_events: vec![
+                ("validator", wallets_service.validator_node_events.clone()),
+                ("observer", wallets_service.observer_node_events.clone()),
+            ],
+            env_contracts: vec![
+                ("agents", agents_service.uri.clone()),
+                ("agents_teams", agents_teams_service.uri.clone()),
+                ("wallets", wallets_service.uri.clone()),
+            ],
+            registry_checks: RegistryChecks::default(),
+        },
+        testnet: NetworkClients {
+            write_client: testnet_service.write_client.clone(),
+            read_client: testnet_service.read_client.clone(),
+            node_events: vec![
+                ("validator", testnet_validator_node_events),
+                ("observer", testnet_service.observer_node_events.clone()),
+            ],
+            env_contracts: vec![("testnet_log", testnet_service.uri.clone())],
+            registry_checks: RegistryChecks::default(),
+        },
+    };
+
     let api = OpenApiService::new(
         (Service, Testnet, WalletsApi, AIAgents, AIAgentsTeams),
         "Embers API",
@@ -129,11 +254,14 @@ async fn main() -> anyhow::Result<()> {
         .nest("/swagger-ui/index.html", ui)
         .nest("/swagger-ui/openapi.json", spec)
         .nest("/swagger-ui/openapi.yaml", spec_yaml)
+        .around(metrics::track_requests)
         .data(agents_service)
         .data(agents_teams_service)
         .data(wallets_service)
         .data(testnet_service)
+        .data(health_service)
         .with(Cors::new().allow_origin_regex("*"))
+        .around(telemetry::propagate_context)
         .with(RequestId::default())
         .with(Tracing)
         .with(Compression::default())
@@ -151,5 +279,11 @@ async fn main() -> anyhow::Result<()> {
         )
         .await?;
 
+    if let Some(provider) = tracer_provider {
+        provider
+            .shutdown()
+            .context("failed to flush pending traces")?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,49 @@
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use ::metrics::{Unit, counter, describe_counter, describe_histogram, histogram};
+use poem::{Endpoint, Request, Response};
+
+use crate::telemetry::route_and_status;
+
+const HTTP_REQUESTS: &str = "embers_http_requests_total";
+const HTTP_REQUEST_DURATION: &str = "embers_http_request_duration_seconds";
+
+/// Installs the Prometheus recorder that every metric, including the ones of firefly-client, is
+/// recorded into, and serves them on `address`. The metrics are unauthenticated, so the address
+/// should not be reachable from outside the deployment.
+pub fn install(address: SocketAddr) -> anyhow::Result<()> {
+    firefly_client::metrics::install_prometheus(address)?;
+    describe_counter!(
+        HTTP_REQUESTS,
+        "Requests served, by method, route and status"
+    );
+    describe_histogram!(
+        HTTP_REQUEST_DURATION,
+        Unit::Seconds,
+        "Time taken to serve requests, by method and route"
+    );
+    Ok(())
+}
+
+/// Middleware counting requests per route. Routes are labeled by their pattern, e.g.
+/// `/wallets/:address/state`, so that path parameters don't create new series.
+pub async fn track_requests<E: Endpoint>(next: E, req: Request) -> poem::Result<Response> {
+    let method = req.method().to_string();
+    let started = Instant::now();
+
+    let (result, route, status) = route_and_status(next.call(req).await);
+    let route = route.map_or_else(|| "unmatched".to_owned(), |route| route.to_string());
+
+    histogram!(HTTP_REQUEST_DURATION, "method" => method.clone(), "route" => route.clone())
+        .record(started.elapsed());
+    counter!(
+        HTTP_REQUESTS,
+        "method" => method,
+        "route" => route,
+        "status" => status.as_u16().to_string(),
+    )
+    .increment(1);
+
+    result
+}
@@ -0,0 +1,145 @@
+use std::sync::Arc;
+
+use anyhow::Context;
+use opentelemetry::propagation::Extractor;
+use opentelemetry::trace::TracerProvider;
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use opentelemetry_sdk::trace::SdkTracerProvider;
+use poem::http::{HeaderMap, HeaderName, StatusCode};
+use poem::middleware::ReqId;
+use poem::{Endpoint, IntoResponse, PathPattern, Request, Response};
+use tracing::Instrument;
+use tracing_opentelemetry::OpenTelemetrySpanExt;
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{EnvFilter, Layer};
+
+use crate::configuration::OtlpConfig;
+
+/// Installs the log subscriber and, when configured, the OTLP trace exporter. The returned
+/// provider has to be shut down on exit to flush the pending spans.
+pub fn init(
+    log_level: &str,
+    otlp: Option<&OtlpConfig>,
+) -> anyhow::Result<Option<SdkTracerProvider>> {
+    let env_filter = EnvFilter::try_new(log_level).context("failed to init log filter")?;
+
+    let provider = otlp
+        .map(|otlp| {
+            let exporter = opentelemetry_otlp::SpanExporter::builder()
+                .with_tonic()
+                .with_endpoint(&otlp.endpoint)
+                .build()
+                .context("failed to build OTLP exporter")?;
+
+            anyhow::Ok(
+                SdkTracerProvider::builder()
+                    .with_batch_exporter(exporter)
+                    .with_resource(
+                        Resource::builder()
+                            .with_service_name(otlp.service_name.clone())
+                            .build(),
+                    )
+                    .build(),
+            )
+        })
+        .transpose()?;
+
+    // poem's own request spans are replaced by the ones of `propagate_context`, which carry the
+    // remote parent.
+    let otel_layer = provider.as_ref().map(|provider| {
+        tracing_opentelemetry::layer()
+            .with_tracer(provider.tracer("embers"))
+            .with_filter(
+                Targets::new()
+                    .with_default(LevelFilter::TRACE)
+                    .with_target("poem", LevelFilter::OFF),
+            )
+    });
+    opentelemetry::global::set_text_map_propagator(TraceContextPropagator::new());
+
+    tracing_subscriber::registry()
+        .with(env_filter)
+        .with(
+            tracing_subscriber::fmt::layer().event_format(
+                tracing_subscriber::fmt::format()
+                    .with_file(true)
+                    .with_line_number(true),
+            ),
+        )
+        .with(otel_layer)
+        .init();
+
+    Ok(provider)
+}
+
+struct HeaderExtractor<'a>(&'a HeaderMap);
+
+impl Extractor for HeaderExtractor<'_> {
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0.get(key).and_then(|value| value.to_str().ok())
+    }
+
+    fn keys(&self) -> Vec<&str> {
+        self.0.keys().map(HeaderName::as_str).collect()
+    }
+}
+
+/// Middleware opening the server span of a request. The span continues the trace of the caller,
+/// if it sent a `traceparent` header, and is tagged with the id assigned by [`poem::middleware::RequestId`],
+/// so it has to run inside of it. Node calls made while handling the request are nested under it.
+pub async fn propagate_context<E: Endpoint>(next: E, req: Request) -> poem::Result<Response> {
+    let parent = opentelemetry::global::get_text_map_propagator(|propagator| {
+        propagator.extract(&HeaderExtractor(req.headers()))
+    });
+    let request_id = req
+        .data::<ReqId>()
+        .map(ToString::to_string)
+        .unwrap_or_default();
+    let method = req.method().clone();
+
+    let span = tracing::info_span!(
+        "request",
+        otel.name = %format_args!("{method} {}", req.uri().path()),
+        otel.kind = "server",
+        request_id,
+        http.request.method = %method,
+        url.path = req.uri().path(),
+        http.route = tracing::field::Empty,
+        http.response.status_code = tracing::field::Empty,
+    );
+    let _ = span.set_parent(parent);
+
+    let (result, route, status) = route_and_status(next.call(req).instrument(span.clone()).await);
+
+    if let Some(route) = route {
+        span.record("otel.name", format!("{method} {route}"));
+        span.record("http.route", route.as_ref());
+    }
+    span.record("http.response.status_code", status.as_u16());
+
+    result
+}
+
+/// Route pattern the request matched, e.g. `/wallets/:address/state`, and status of the response,
+/// along with the response itself.
+pub fn route_and_status(
+    result: poem::Result<impl IntoResponse>,
+) -> (poem::Result<Response>, Option<Arc<str>>, StatusCode) {
+    match result {
+        Ok(resp) => {
+            let resp = resp.into_response();
+            let route = resp.data::<PathPattern>().map(|pattern| pattern.0.clone());
+            let status = resp.status();
+            (Ok(resp), route, status)
+        }
+        Err(err) => {
+            let route = err.data::<PathPattern>().map(|pattern| pattern.0.clone());
+            let status = err.status();
+            (Err(err), route, status)
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use poem::web::Data;
 use poem_openapi::OpenApi;
+use poem_openapi::param::Query;
 use poem_openapi::payload::Json;
 
 use crate::common::api::dtos::ApiTags;
+use crate::common::prepared_contracts::SendEndpoint;
 use crate::testnet::api::dtos::{
     CreateTestwalletResp,
     DeploySignedTestReq,
@@ -11,6 +13,7 @@ use crate::testnet::api::dtos::{
     DeployTestResp,
 };
 use crate::testnet::handlers::TestnetService;
+use crate::testnet::models;
 
 mod dtos;
 
@@ -32,9 +35,14 @@ impl Testnet {
     async fn prepare_deploy(
         &self,
         Json(body): Json<DeployTestReq>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(testnet): Data<&TestnetService>,
     ) -> poem::Result<Json<DeployTestResp>> {
-        let contracts = testnet.prepare_test_contract(body.into()).await?;
+        let phlo_price = testnet.deploy_config.phlo_price(phlo_price)?;
+        testnet.deploy_config.check_arbitrary_code()?;
+        let contracts = testnet
+            .prepare_test_contract(body.into(), phlo_price)
+            .await?;
         Ok(Json(contracts.into()))
     }
 
@@ -44,7 +52,14 @@ impl Testnet {
         Json(body): Json<DeploySignedTestReq>,
         Data(testnet): Data<&TestnetService>,
     ) -> poem::Result<Json<DeploySignedTestResp>> {
-        let result = testnet.deploy_test_contract(body.into()).await?;
+        let request: models::DeploySignedTestReq = body.into();
+        let sending = testnet.prepared_contracts.verify(
+            request.env.iter().chain([&request.test]),
+            SendEndpoint::DeployTest,
+            None,
+        )?;
+        let result = testnet.deploy_test_contract(request).await?;
+        sending.sent();
         Ok(Json(result.into()))
     }
 }
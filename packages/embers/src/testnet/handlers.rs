@@ -1,6 +1,11 @@
+use std::sync::Arc;
+
 use firefly_client::models::Uri;
-use firefly_client::{NodeEvents, ReadNodeClient, WriteNodeClient};
-use secp256k1::SecretKey;
+use firefly_client::signer::Signer;
+use firefly_client::{DeployTransport, EventSource, Proposer, ReadNodeClient};
+
+use crate::common::models::DeployConfig;
+use crate::common::prepared_contracts::PreparedContracts;
 
 mod create_test_wallet;
 mod deploy_test;
@@ -8,8 +13,11 @@ mod deploy_test;
 #[derive(Clone)]
 pub struct TestnetService {
     pub uri: Uri,
-    pub service_key: SecretKey,
-    pub write_client: WriteNodeClient,
+    pub service_signer: Arc<dyn Signer>,
+    pub write_client: Arc<dyn DeployTransport>,
     pub read_client: ReadNodeClient,
-    pub observer_node_events: NodeEvents,
+    pub propose_scheduler: Arc<dyn Proposer>,
+    pub observer_node_events: Arc<dyn EventSource>,
+    pub deploy_config: DeployConfig,
+    pub prepared_contracts: PreparedContracts,
 }
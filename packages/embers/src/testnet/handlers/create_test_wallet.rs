@@ -1,6 +1,6 @@
 use firefly_client::models::WalletAddress;
 use firefly_client::rendering::Render;
-use secp256k1::{PublicKey, Secp256k1, rand};
+use secp256k1::{Secp256k1, rand};
 
 use crate::testnet::handlers::TestnetService;
 use crate::testnet::models::CreateTestwalletResp;
@@ -21,7 +21,7 @@ impl TestnetService {
         let sk = Secp256k1::new();
         let (test_account_secret_key, test_account_public_key) =
             sk.generate_keypair(&mut rand::rng());
-        let service_address_public_key = PublicKey::from_secret_key(&sk, &self.service_key);
+        let service_address_public_key = self.service_signer.public_key();
 
         let deploy_data = FundTestWallet {
             wallet_address_from: service_address_public_key.into(),
@@ -29,11 +29,15 @@ impl TestnetService {
             amount: TEST_WALLET_BALANCE,
         }
         .builder()?
+        .phlo_price(self.deploy_config.phlo_price)
+        .shard_id(self.deploy_config.shard_id.clone())
         .build();
 
-        let mut write_client = self.write_client.clone();
-        write_client.deploy(&self.service_key, deploy_data).await?;
-        write_client.propose().await?;
+        let deploy_id = self
+            .write_client
+            .deploy(&*self.service_signer, deploy_data)
+            .await?;
+        self.propose_scheduler.enqueue(deploy_id).await?;
 
         Ok(CreateTestwalletResp {
             key: test_account_secret_key,
@@ -3,8 +3,10 @@ use std::time::Duration;
 use anyhow::anyhow;
 use firefly_client::models::{DeployId, Uri};
 use firefly_client::rendering::Render;
+use firefly_client::{DeployOutcome, DeployTracker};
 
 use crate::common::prepare_for_signing;
+use crate::common::prepared_contracts::SendEndpoint;
 use crate::common::tracing::record_trace;
 use crate::testnet::blockchain;
 use crate::testnet::handlers::TestnetService;
@@ -33,20 +35,29 @@ impl TestnetService {
     pub async fn prepare_test_contract(
         &self,
         request: DeployTestReq,
+        phlo_price: u64,
     ) -> anyhow::Result<DeployTestResp> {
         record_trace!(request);
 
-        let valid_after = self.write_client.clone().get_head_block_index().await?;
+        let valid_after = self.write_client.get_head_block_index().await?;
         Ok(DeployTestResp {
             env_contract: request.env.map(|env| {
                 prepare_for_signing()
+                    .prepared_contracts(&self.prepared_contracts)
+                    .endpoint(SendEndpoint::DeployTest)
                     .code(env)
                     .valid_after_block_number(valid_after)
+                    .phlo_price(phlo_price)
+                    .shard_id(self.deploy_config.shard_id.clone())
                     .call()
             }),
             test_contract: prepare_for_signing()
+                .prepared_contracts(&self.prepared_contracts)
+                .endpoint(SendEndpoint::DeployTest)
                 .code(request.test)
                 .valid_after_block_number(valid_after)
+                .phlo_price(phlo_price)
+                .shard_id(self.deploy_config.shard_id.clone())
                 .call(),
         })
     }
@@ -64,19 +75,29 @@ impl TestnetService {
     ) -> anyhow::Result<DeploySignedTestResp> {
         record_trace!(request);
 
-        let mut write_client = self.write_client.clone();
+        let write_client = &self.write_client;
 
         if let Some(contract) = request.env {
-            let result = write_client.deploy_signed_contract(contract).await;
-            if let Err(err) = result {
-                return Ok(DeploySignedTestResp::EnvDeployFailed {
-                    error: err.to_string(),
-                });
-            }
+            let deploy_id = match write_client.deploy_signed_contract(contract).await {
+                Ok(deploy_id) => deploy_id,
+                Err(err) => {
+                    return Ok(DeploySignedTestResp::EnvDeployFailed {
+                        error: err.to_string(),
+                    });
+                }
+            };
 
-            write_client.propose().await?;
+            self.propose_scheduler.enqueue(deploy_id).await?;
         }
 
+        let valid_after = match request.test.deploy_data() {
+            Ok(deploy_data) => Some(deploy_data.valid_after_block_number as _),
+            Err(err) => {
+                return Ok(DeploySignedTestResp::TestDeployFailed {
+                    error: err.to_string(),
+                });
+            }
+        };
         let result = write_client.deploy_signed_contract(request.test).await;
         let deploy_id = match result {
             Ok(deploy_id) => deploy_id,
@@ -87,14 +108,19 @@ impl TestnetService {
             }
         };
 
-        let deploy_waiter = self
-            .observer_node_events
-            .wait_for_deploy(&deploy_id, Duration::from_secs(60));
-        let (_, finalized) =
-            tokio::try_join!(write_client.propose(), async { Ok(deploy_waiter.await) })?;
+        let deploy_tracker = DeployTracker::builder()
+            .write_client(self.write_client.clone())
+            .node_events(self.observer_node_events.clone())
+            .build();
+        let (_, outcome) =
+            tokio::try_join!(self.propose_scheduler.enqueue(deploy_id.clone()), async {
+                Ok(deploy_tracker
+                    .track(&deploy_id, valid_after, Duration::from_secs(60))
+                    .await)
+            })?;
 
-        if !finalized {
-            return Err(anyhow!("block is not finalized"));
+        if !matches!(outcome, DeployOutcome::Finalized { .. }) {
+            return Err(anyhow!("block is not finalized: {outcome:?}"));
         }
 
         let code = GetLogs {
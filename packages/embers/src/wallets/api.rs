@@ -1,18 +1,22 @@
 use firefly_client::models::WalletAddress;
 use futures::future;
 use futures::sink::SinkExt;
+use poem::http::StatusCode;
 use poem::web::{Data, websocket};
 use poem_openapi::OpenApi;
-use poem_openapi::param::Path;
+use poem_openapi::param::{Path, Query};
 use poem_openapi::payload::Json;
 use poem_openapi::types::ToJSON;
 use tracing::error;
 
 use crate::common::api::dtos::{ApiTags, SendResp, SignedContract, Stringified};
+use crate::common::prepared_contracts::SendEndpoint;
 use crate::wallets::api::dtos::{
     BoostReq,
     BoostResp,
     DeployEvent,
+    DeployPreview,
+    DeployPreviewReq,
     TransferReq,
     TransferResp,
     WalletStateAndHistory,
@@ -30,10 +34,11 @@ impl WalletsApi {
     async fn wallet_state_and_history(
         &self,
         Path(address): Path<Stringified<WalletAddress>>,
+        Query(block_hash): Query<Option<String>>,
         Data(wallets): Data<&WalletsService>,
     ) -> poem::Result<Json<WalletStateAndHistory>> {
         let wallet_state_and_history = wallets
-            .get_wallet_state_and_history(address.0.clone())
+            .get_wallet_state_and_history(address.0.clone(), block_hash.map(Into::into))
             .await
             .map_err(|err| {
                 error!(
@@ -54,11 +59,13 @@ impl WalletsApi {
     async fn prepare_transfer(
         &self,
         Json(body): Json<TransferReq>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(wallets): Data<&WalletsService>,
     ) -> poem::Result<Json<TransferResp>> {
+        let phlo_price = wallets.deploy_config.phlo_price(phlo_price)?;
         let input = body.try_into()?;
         let result = wallets
-            .prepare_transfer_contract(input)
+            .prepare_transfer_contract(input, phlo_price)
             .await
             .map_err(|err| {
                 error!(
@@ -69,9 +76,7 @@ impl WalletsApi {
                 );
                 err
             })?;
-        Ok(Json(TransferResp {
-            contract: result.into(),
-        }))
+        Ok(Json(result.into()))
     }
 
     #[oai(path = "/transfer/send", method = "post")]
@@ -80,8 +85,13 @@ impl WalletsApi {
         Json(body): Json<SignedContract>,
         Data(wallets): Data<&WalletsService>,
     ) -> poem::Result<Json<SendResp>> {
+        let contract = body.into();
+        let sending =
+            wallets
+                .prepared_contracts
+                .verify([&contract], SendEndpoint::Transfer, None)?;
         let deploy_id = wallets
-            .deploy_signed_transfer(body.into())
+            .deploy_signed_transfer(contract)
             .await
             .map_err(|err| {
                 error!(
@@ -92,6 +102,7 @@ impl WalletsApi {
                 );
                 err
             })?;
+        sending.sent();
         Ok(Json(deploy_id.into()))
     }
 
@@ -99,12 +110,14 @@ impl WalletsApi {
     async fn prepare_boost(
         &self,
         Json(body): Json<BoostReq>,
+        Query(phlo_price): Query<Option<u64>>,
         Data(wallets): Data<&WalletsService>,
     ) -> poem::Result<Json<BoostResp>> {
-        let result = wallets.prepare_boost_contract(body.into()).await?;
-        Ok(Json(BoostResp {
-            contract: result.into(),
-        }))
+        let phlo_price = wallets.deploy_config.phlo_price(phlo_price)?;
+        let result = wallets
+            .prepare_boost_contract(body.into(), phlo_price)
+            .await?;
+        Ok(Json(result.into()))
     }
 
     #[oai(path = "/boost/send", method = "post")]
@@ -113,15 +126,36 @@ impl WalletsApi {
         Json(body): Json<SignedContract>,
         Data(wallets): Data<&WalletsService>,
     ) -> poem::Result<Json<SendResp>> {
-        let deploy_id = wallets.deploy_boost_transfer(body.into()).await?;
+        let contract = body.into();
+        let sending = wallets
+            .prepared_contracts
+            .verify([&contract], SendEndpoint::Boost, None)?;
+        let deploy_id = wallets.deploy_boost_transfer(contract).await?;
+        sending.sent();
         Ok(Json(deploy_id.into()))
     }
 
+    #[allow(clippy::unused_async)]
+    #[oai(path = "/preview", method = "post")]
+    async fn preview(
+        &self,
+        Json(body): Json<DeployPreviewReq>,
+        Data(wallets): Data<&WalletsService>,
+    ) -> poem::Result<Json<DeployPreview>> {
+        let preview = wallets
+            .preview_deploy(body.into())
+            .map_err(|err| poem::Error::from_string(err.to_string(), StatusCode::BAD_REQUEST))?;
+        Ok(Json(preview.into()))
+    }
+
+    /// Streams finalized deploys of the wallet. With `include_added`, deploys are also reported
+    /// as soon as they are added to a block.
     #[allow(clippy::unused_async)]
     #[oai(path = "/:address/deploys", method = "get")]
     async fn deploys(
         &self,
         Path(address): Path<Stringified<WalletAddress>>,
+        Query(include_added): Query<Option<bool>>,
         Data(wallets): Data<&WalletsService>,
         ws: websocket::WebSocket,
     ) -> websocket::BoxWebSocketUpgraded {
@@ -132,9 +166,62 @@ impl WalletsApi {
                 let msg = DeployEvent::from(msg).to_json_string();
                 future::ok(websocket::Message::Text(msg))
             });
-            wallets.subscribe_to_deploys(address.0, sink);
+            wallets.subscribe_to_deploys(address.0, include_added.unwrap_or_default(), sink);
             future::ready(())
         })
         .boxed()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use firefly_mock_node::MockNode;
+    use poem_openapi::types::Base64;
+    use secp256k1::SecretKey;
+
+    use super::*;
+    use crate::common::models::PositiveNonZero;
+    use crate::wallets::handlers::tests::{key, service, sign, wallet};
+    use crate::wallets::models;
+
+    #[tokio::test]
+    async fn test_transfer_is_sent_again_after_failing() {
+        let node = MockNode::start().await.unwrap();
+        let service = service(&node).await;
+        let resp = service
+            .prepare_transfer_contract(
+                models::TransferReq {
+                    from: wallet(&key()),
+                    to: wallet(&SecretKey::from_byte_array([1; 32]).unwrap()),
+                    amount: PositiveNonZero(10),
+                    description: None,
+                },
+                3,
+            )
+            .await
+            .unwrap();
+        let signed = sign(resp.contract);
+        let body = || {
+            Json(SignedContract {
+                contract: Base64(signed.contract.clone()),
+                sig: Base64(signed.sig.clone()),
+                sig_algorithm: signed.sig_algorithm.clone(),
+                deployer: Base64(signed.deployer.clone()),
+            })
+        };
+
+        node.set_unavailable(true);
+        assert!(WalletsApi.transfer(body(), Data(&service)).await.is_err());
+        assert!(node.deploys().is_empty());
+
+        node.set_unavailable(false);
+        WalletsApi.transfer(body(), Data(&service)).await.unwrap();
+        assert_eq!(node.deploys().len(), 1);
+
+        let err = WalletsApi
+            .transfer(body(), Data(&service))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::CONFLICT);
+    }
+}
@@ -1,6 +1,9 @@
 use chrono::{DateTime, Utc};
-use firefly_client::models::WalletAddress;
+use firefly_client::helpers::ShortHex;
+use firefly_client::models::{SignedCode, WalletAddress};
+use poem_openapi::types::Base64;
 use poem_openapi::{Enum, Object, Union};
+use secp256k1::PublicKey;
 use structural_convert::StructuralConvert;
 
 use crate::common::api::dtos::{PreparedContract, Stringified};
@@ -72,7 +75,8 @@ pub struct TransferReq {
     pub description: Option<String>,
 }
 
-#[derive(Debug, Clone, Object)]
+#[derive(Debug, Clone, Object, StructuralConvert)]
+#[convert(from(models::TransferResp))]
 pub struct TransferResp {
     pub contract: PreparedContract,
 }
@@ -88,11 +92,64 @@ pub struct BoostReq {
     pub post_id: Option<String>,
 }
 
-#[derive(Debug, Clone, Object)]
+#[derive(Debug, Clone, Object, StructuralConvert)]
+#[convert(from(models::BoostResp))]
 pub struct BoostResp {
     pub contract: PreparedContract,
 }
 
+#[derive(derive_more::Debug, Clone, Object)]
+pub struct DeploySignature {
+    #[debug("{:?}", hex::encode(&sig.0))]
+    pub sig: Base64<Vec<u8>>,
+    pub sig_algorithm: String,
+    #[debug("{:?}", hex::encode(&deployer.0))]
+    pub deployer: Base64<Vec<u8>>,
+}
+
+/// Prepared contract to preview, with the signature when it is already signed.
+#[derive(derive_more::Debug, Clone, Object)]
+pub struct DeployPreviewReq {
+    #[debug("{:?}", contract.0.short_hex(32))]
+    pub contract: Base64<Vec<u8>>,
+    pub signature: Option<DeploySignature>,
+}
+
+impl From<DeployPreviewReq> for models::DeployPreviewReq {
+    fn from(value: DeployPreviewReq) -> Self {
+        match value.signature {
+            Some(signature) => Self::Signed(SignedCode {
+                contract: value.contract.0,
+                sig: signature.sig.0,
+                sig_algorithm: signature.sig_algorithm,
+                deployer: signature.deployer.0,
+            }),
+            None => Self::Prepared(crate::common::models::PreparedContract(value.contract.0)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Object, StructuralConvert)]
+#[convert(from(firefly_client::Deployer))]
+pub struct Deployer {
+    pub public_key: Stringified<PublicKey>,
+    pub wallet_address: Stringified<WalletAddress>,
+    pub signature_valid: bool,
+}
+
+#[derive(Debug, Clone, Object, StructuralConvert)]
+#[convert(from(models::DeployPreview))]
+pub struct DeployPreview {
+    pub term: String,
+    pub timestamp: Stringified<DateTime<Utc>>,
+    pub phlo_limit: Stringified<u64>,
+    pub phlo_price: Stringified<u64>,
+    pub shard_id: String,
+    pub valid_after_block_number: Stringified<u64>,
+    pub deployer: Option<Deployer>,
+    pub summary: Option<String>,
+}
+
 #[derive(Debug, Clone, Enum, StructuralConvert)]
 #[convert(from(models::NodeType))]
 pub enum NodeType {
@@ -113,5 +170,6 @@ pub struct DeployDescription {
 #[oai(discriminator_name = "type")]
 #[convert(from(models::DeployEvent))]
 pub enum DeployEvent {
+    Added(DeployDescription),
     Finalized(DeployDescription),
 }
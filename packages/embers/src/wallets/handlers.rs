@@ -1,16 +1,216 @@
+use std::sync::Arc;
+
 use firefly_client::models::Uri;
-use firefly_client::{NodeEvents, ReadNodeClient, WriteNodeClient};
+use firefly_client::rendering::Value;
+use firefly_client::{DeployTransport, EventSource, Proposer, ReadNodeClient};
+
+use crate::common::models::DeployConfig;
+use crate::common::prepared_contracts::PreparedContracts;
 
 mod boost;
 mod get_wallet_state_and_history;
+mod preview_deploy;
 mod subscribe_to_deploys;
 mod transfer;
 
 #[derive(Clone)]
 pub struct WalletsService {
     pub uri: Uri,
-    pub write_client: WriteNodeClient,
+    pub write_client: Arc<dyn DeployTransport>,
     pub read_client: ReadNodeClient,
-    pub validator_node_events: NodeEvents,
-    pub observer_node_events: NodeEvents,
+    pub propose_scheduler: Arc<dyn Proposer>,
+    pub validator_node_events: Arc<dyn EventSource>,
+    pub observer_node_events: Arc<dyn EventSource>,
+    pub deploy_config: DeployConfig,
+    pub prepared_contracts: PreparedContracts,
+}
+
+/// Arguments of the `@wallets!(...)` call in a wallets contract.
+fn wallets_call_args(term: &str) -> Option<Vec<Value>> {
+    let (_, call) = term.split_once("@wallets!")?;
+    match Value::parse_prefix(call).ok()? {
+        (Value::Tuple(args), _) => Some(args),
+        _ => None,
+    }
+}
+
+/// Reads back an `Option<String>` rendered as a string or `Nil`.
+fn optional_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(value) => Some(value.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::collections::BTreeMap;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    use blake2::digest::consts::U32;
+    use blake2::{Blake2b, Digest};
+    use firefly_client::models::{SignedCode, WalletAddress};
+    use firefly_client::{NodeEvents, ProposeScheduler, WriteNodeClient};
+    use firefly_mock_node::MockNode;
+    use secp256k1::{Message, Secp256k1, SecretKey};
+
+    use super::*;
+    use crate::common::models::{PositiveNonZero, PreparedContract};
+    use crate::common::prepared_contracts::SendEndpoint;
+    use crate::wallets::models::{DeployPreviewReq, TransferReq};
+
+    const KEY: &str = "f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc";
+
+    pub fn key() -> SecretKey {
+        SecretKey::from_str(KEY).unwrap()
+    }
+
+    pub fn wallet(key: &SecretKey) -> WalletAddress {
+        key.public_key(&Secp256k1::new()).into()
+    }
+
+    pub fn sign(contract: PreparedContract) -> SignedCode {
+        let secp = Secp256k1::new();
+        let hash = Blake2b::<U32>::new().chain_update(&contract.0).finalize();
+
+        SignedCode {
+            sig: secp
+                .sign_ecdsa(Message::from_digest(hash.into()), &key())
+                .serialize_der()
+                .to_vec(),
+            sig_algorithm: "secp256k1".to_owned(),
+            deployer: key().public_key(&secp).serialize_uncompressed().to_vec(),
+            contract: contract.0,
+        }
+    }
+
+    pub async fn service(node: &MockNode) -> WalletsService {
+        let write_client = WriteNodeClient::new(node.grpc_url(), node.grpc_url())
+            .await
+            .unwrap();
+        let propose_scheduler = ProposeScheduler::builder()
+            .write_client(write_client.clone())
+            .debounce(Duration::from_millis(10))
+            .build();
+        let node_events: Arc<dyn EventSource> = Arc::new(NodeEvents::new(&node.ws_url()));
+
+        WalletsService {
+            uri: key().public_key(&Secp256k1::new()).into(),
+            write_client: Arc::new(write_client),
+            read_client: ReadNodeClient::new(node.http_url()),
+            propose_scheduler: Arc::new(propose_scheduler),
+            validator_node_events: node_events.clone(),
+            observer_node_events: node_events,
+            deploy_config: DeployConfig::default(),
+            prepared_contracts: PreparedContracts::new(&DeployConfig::default(), &[0; 32]),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transfer() {
+        let node = MockNode::start().await.unwrap();
+        let service = service(&node).await;
+        let to = wallet(&SecretKey::from_byte_array([1; 32]).unwrap());
+
+        let resp = service
+            .prepare_transfer_contract(
+                TransferReq {
+                    from: wallet(&key()),
+                    to: to.clone(),
+                    amount: PositiveNonZero(10),
+                    description: Some("rent".to_owned()),
+                },
+                3,
+            )
+            .await
+            .unwrap();
+
+        let preview = service
+            .preview_deploy(DeployPreviewReq::Prepared(resp.contract.clone()))
+            .unwrap();
+        assert_eq!(preview.phlo_price, 3);
+        assert_eq!(
+            preview.summary.unwrap(),
+            format!(
+                "transfer 10 from {} to {}",
+                wallet(&key()).as_ref(),
+                to.as_ref()
+            )
+        );
+
+        let signed = sign(resp.contract);
+        let sending = service
+            .prepared_contracts
+            .verify([&signed], SendEndpoint::Transfer, None)
+            .unwrap();
+        service.deploy_signed_transfer(signed).await.unwrap();
+        sending.sent();
+
+        let deploys = node.deploys();
+        assert_eq!(deploys.len(), 1);
+        assert_eq!(deploys[0].term, preview.term);
+        assert_eq!(
+            service.write_client.get_head_block_index().await.unwrap(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_wallet_state_and_history() {
+        let node = MockNode::start().await.unwrap();
+        let service = service(&node).await;
+        let from = wallet(&key());
+        let to = wallet(&SecretKey::from_byte_array([1; 32]).unwrap());
+
+        let transfer = Value::Map(BTreeMap::from([
+            ("id".to_owned(), Value::String("1".to_owned())),
+            ("timestamp".to_owned(), Value::Int(1_700_000_000)),
+            ("from".to_owned(), Value::String(from.as_ref().to_owned())),
+            ("to".to_owned(), Value::String(to.as_ref().to_owned())),
+            ("amount".to_owned(), Value::Int(10)),
+            ("description".to_owned(), Value::Nil),
+        ]));
+        node.on_explore(move |term| {
+            term.contains("getBalanceAndHistory").then(|| {
+                Value::Tuple(vec![
+                    Value::Bool(true),
+                    Value::Map(BTreeMap::from([
+                        ("balance".to_owned(), Value::Int(90)),
+                        ("transfers".to_owned(), Value::List(vec![transfer.clone()])),
+                        ("boosts".to_owned(), Value::List(vec![])),
+                    ])),
+                ])
+            })
+        });
+
+        let state = service
+            .get_wallet_state_and_history(from.clone(), None)
+            .await
+            .unwrap();
+        assert_eq!(state.balance, 90);
+        assert_eq!(state.transfers.len(), 1);
+        assert_eq!(state.transfers[0].from, from);
+        assert_eq!(state.transfers[0].to, to);
+        assert_eq!(state.transfers[0].amount.0, 10);
+        assert!(state.boosts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_wallet_state_and_history_contract_error() {
+        let node = MockNode::start().await.unwrap();
+        let service = service(&node).await;
+        node.on_explore(|_| {
+            Some(Value::Tuple(vec![
+                Value::Bool(false),
+                Value::String("wallet not found".to_owned()),
+            ]))
+        });
+
+        let err = service
+            .get_wallet_state_and_history(wallet(&key()), None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("wallet not found"));
+    }
 }
@@ -1,12 +1,12 @@
 use chrono::{DateTime, Utc};
 use firefly_client::models::{DeployId, SignedCode, Uri, WalletAddress};
-use firefly_client::rendering::Render;
+use firefly_client::rendering::{Render, Value};
 
-use crate::common::models::PreparedContract;
 use crate::common::prepare_for_signing;
+use crate::common::prepared_contracts::SendEndpoint;
 use crate::common::tracing::record_trace;
-use crate::wallets::handlers::WalletsService;
-use crate::wallets::models::BoostReq;
+use crate::wallets::handlers::{WalletsService, optional_string, wallets_call_args};
+use crate::wallets::models::{BoostReq, BoostResp};
 
 #[derive(Debug, Clone, Render)]
 #[template(path = "wallets/boost.rho")]
@@ -32,9 +32,11 @@ impl WalletsService {
     pub async fn prepare_boost_contract(
         &self,
         request: BoostReq,
-    ) -> anyhow::Result<PreparedContract> {
+        phlo_price: u64,
+    ) -> anyhow::Result<BoostResp> {
         record_trace!(request);
 
+        let deployer = request.from.clone();
         let contract = BoostContract {
             env_uri: self.uri.clone(),
             timestamp: Utc::now(),
@@ -47,11 +49,18 @@ impl WalletsService {
         }
         .render()?;
 
-        let valid_after = self.write_client.clone().get_head_block_index().await?;
-        Ok(prepare_for_signing()
-            .code(contract)
-            .valid_after_block_number(valid_after)
-            .call())
+        let valid_after = self.write_client.get_head_block_index().await?;
+        Ok(BoostResp {
+            contract: prepare_for_signing()
+                .prepared_contracts(&self.prepared_contracts)
+                .endpoint(SendEndpoint::Boost)
+                .deployer(&deployer)
+                .code(contract)
+                .valid_after_block_number(valid_after)
+                .phlo_price(phlo_price)
+                .shard_id(self.deploy_config.shard_id.clone())
+                .call(),
+        })
     }
 
     #[tracing::instrument(
@@ -64,10 +73,40 @@ impl WalletsService {
     pub async fn deploy_boost_transfer(&self, contract: SignedCode) -> anyhow::Result<DeployId> {
         record_trace!(contract);
 
-        let mut write_client = self.write_client.clone();
-
-        let deploy_id = write_client.deploy_signed_contract(contract).await?;
-        write_client.propose().await?;
+        let deploy_id = self.write_client.deploy_signed_contract(contract).await?;
+        self.propose_scheduler.enqueue(deploy_id.clone()).await?;
         Ok(deploy_id)
     }
+
+    /// Describes `term` when it renders exactly as a boost through this service's contract.
+    pub(super) fn summarize_boost(&self, term: &str) -> Option<String> {
+        let args = wallets_call_args(term)?;
+        let [
+            _,
+            Value::Int(timestamp),
+            Value::String(from),
+            Value::String(to),
+            Value::Int(amount),
+            description @ (Value::Nil | Value::String(_)),
+            Value::String(post_author_did),
+            post_id @ (Value::Nil | Value::String(_)),
+        ] = args.as_slice()
+        else {
+            return None;
+        };
+
+        let contract = BoostContract {
+            env_uri: self.uri.clone(),
+            timestamp: DateTime::from_timestamp(*timestamp, 0)?,
+            wallet_address_from: from.clone().try_into().ok()?,
+            wallet_address_to: to.clone().try_into().ok()?,
+            amount: *amount,
+            description: optional_string(description),
+            post_author_did: post_author_did.clone(),
+            post_id: optional_string(post_id),
+        };
+
+        (contract.render().ok()? == term)
+            .then(|| format!("boost {amount} from {from} to {to} for a post by {post_author_did}"))
+    }
 }
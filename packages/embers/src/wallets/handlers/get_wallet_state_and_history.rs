@@ -1,4 +1,4 @@
-use firefly_client::models::{Either, Uri, WalletAddress};
+use firefly_client::models::{BlockId, Either, Uri, WalletAddress};
 use firefly_client::rendering::Render;
 
 use crate::common::tracing::record_trace;
@@ -24,6 +24,7 @@ impl WalletsService {
     pub async fn get_wallet_state_and_history(
         &self,
         address: WalletAddress,
+        block_hash: Option<BlockId>,
     ) -> anyhow::Result<WalletStateAndHistory> {
         record_trace!(address);
 
@@ -35,7 +36,7 @@ impl WalletsService {
 
         let state = self
             .read_client
-            .get_data::<Either<String, dtos::BalanceAndHistory>>(contract)
+            .get_data_at::<Either<String, dtos::BalanceAndHistory>>(contract, block_hash.as_ref())
             .await?
             .to_result()
             .map_err(|err| anyhow::anyhow!("error from contract: {err}"))?;
@@ -0,0 +1,45 @@
+use firefly_client::DeployPreviewError;
+
+use crate::common::prepared_contracts::untagged;
+use crate::common::tracing::record_trace;
+use crate::wallets::handlers::WalletsService;
+use crate::wallets::models::{DeployPreview, DeployPreviewReq};
+
+impl WalletsService {
+    #[tracing::instrument(
+        level = "info",
+        skip_all,
+        fields(request),
+        err(Debug),
+        ret(Debug, level = "trace")
+    )]
+    pub fn preview_deploy(
+        &self,
+        request: DeployPreviewReq,
+    ) -> Result<DeployPreview, DeployPreviewError> {
+        record_trace!(request);
+
+        let preview = match request {
+            DeployPreviewReq::Prepared(contract) => {
+                firefly_client::DeployPreview::decode(&contract.0)?
+            }
+            DeployPreviewReq::Signed(contract) => contract.try_into()?,
+        };
+
+        let term = untagged(&preview.term);
+        let summary = self
+            .summarize_transfer(term)
+            .or_else(|| self.summarize_boost(term));
+
+        Ok(DeployPreview {
+            term: preview.term,
+            timestamp: preview.timestamp,
+            phlo_limit: preview.phlo_limit,
+            phlo_price: preview.phlo_price,
+            shard_id: preview.shard_id,
+            valid_after_block_number: preview.valid_after_block_number,
+            deployer: preview.deployer,
+            summary,
+        })
+    }
+}
@@ -2,7 +2,7 @@ use std::io;
 
 use firefly_client::models::WalletAddress;
 use firefly_client::node_events;
-use futures::{Sink, SinkExt, StreamExt, stream};
+use futures::{Sink, SinkExt, StreamExt, future, stream};
 use tracing::Instrument;
 
 use crate::wallets::handlers::WalletsService;
@@ -13,36 +13,27 @@ impl WalletsService {
     pub fn subscribe_to_deploys(
         &self,
         wallet_address: WalletAddress,
+        include_added: bool,
         sink: impl Sink<DeployEvent, Error = io::Error> + Send + 'static,
     ) {
+        let wanted = move |deploy_event: &node_events::DeployEvent| {
+            future::ready(
+                include_added || matches!(deploy_event, node_events::DeployEvent::Finalized { .. }),
+            )
+        };
+
         let observer_deploys = self
             .observer_node_events
             .subscribe_for_deploys(wallet_address.clone())
-            .map(|deploy_event| match deploy_event {
-                node_events::DeployEvent::Finalized { id, cost, errored } => {
-                    DeployEvent::Finalized(DeployDescription {
-                        deploy_id: id,
-                        cost,
-                        errored,
-                        node_type: NodeType::Observer,
-                    })
-                }
-            })
+            .filter(wanted)
+            .map(|deploy_event| to_deploy_event(deploy_event, NodeType::Observer))
             .map(Ok);
 
         let validator_deploys = self
             .validator_node_events
             .subscribe_for_deploys(wallet_address)
-            .map(|deploy_event| match deploy_event {
-                node_events::DeployEvent::Finalized { id, cost, errored } => {
-                    DeployEvent::Finalized(DeployDescription {
-                        deploy_id: id,
-                        cost,
-                        errored,
-                        node_type: NodeType::Validator,
-                    })
-                }
-            })
+            .filter(wanted)
+            .map(|deploy_event| to_deploy_event(deploy_event, NodeType::Validator))
             .map(Ok);
 
         tokio::spawn(
@@ -61,3 +52,24 @@ impl WalletsService {
         );
     }
 }
+
+fn to_deploy_event(deploy_event: node_events::DeployEvent, node_type: NodeType) -> DeployEvent {
+    match deploy_event {
+        node_events::DeployEvent::Added {
+            id, cost, errored, ..
+        } => DeployEvent::Added(DeployDescription {
+            deploy_id: id,
+            cost,
+            errored,
+            node_type,
+        }),
+        node_events::DeployEvent::Finalized {
+            id, cost, errored, ..
+        } => DeployEvent::Finalized(DeployDescription {
+            deploy_id: id,
+            cost,
+            errored,
+            node_type,
+        }),
+    }
+}
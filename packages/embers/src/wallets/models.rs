@@ -1,7 +1,8 @@
 use chrono::{DateTime, Utc};
-use firefly_client::models::{DeployId, WalletAddress};
+use firefly_client::Deployer;
+use firefly_client::models::{DeployId, SignedCode, WalletAddress};
 
-use crate::common::models::PositiveNonZero;
+use crate::common::models::{PositiveNonZero, PreparedContract};
 
 pub type Amount = PositiveNonZero<i64>;
 
@@ -36,6 +37,11 @@ pub struct Boost {
     pub post_id: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct BoostResp {
+    pub contract: PreparedContract,
+}
+
 #[derive(Debug, Clone)]
 pub struct Request {
     pub id: String,
@@ -63,6 +69,11 @@ pub struct TransferReq {
     pub description: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub struct TransferResp {
+    pub contract: PreparedContract,
+}
+
 #[derive(Debug, Clone)]
 pub struct BoostReq {
     pub from: WalletAddress,
@@ -73,6 +84,25 @@ pub struct BoostReq {
     pub post_id: Option<String>,
 }
 
+#[derive(Debug, Clone)]
+pub enum DeployPreviewReq {
+    Prepared(PreparedContract),
+    Signed(SignedCode),
+}
+
+#[derive(Debug, Clone)]
+pub struct DeployPreview {
+    pub term: String,
+    pub timestamp: DateTime<Utc>,
+    pub phlo_limit: u64,
+    pub phlo_price: u64,
+    pub shard_id: String,
+    pub valid_after_block_number: u64,
+    pub deployer: Option<Deployer>,
+    /// Human readable description, for deploys built from a wallets template.
+    pub summary: Option<String>,
+}
+
 #[derive(Debug, Clone)]
 pub enum NodeType {
     Validator,
@@ -89,5 +119,6 @@ pub struct DeployDescription {
 
 #[derive(Debug, Clone)]
 pub enum DeployEvent {
+    Added(DeployDescription),
     Finalized(DeployDescription),
 }
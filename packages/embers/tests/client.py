@@ -400,9 +400,15 @@ class AiAgentsTeamsApi:
     def run(self, wallet: Wallet, prompt: str, phlo_limit: int, agents_team: str) -> Responce:
         resp = self._client.post(
             "/ai-agents-teams/run/prepare",
-            json={"prompt": prompt, "phlo_limit": phlo_limit, "agents_team": agents_team},
+            json={
+                "prompt": prompt,
+                "phlo_limit": phlo_limit,
+                "agents_team": agents_team,
+                "deployer": wallet.key.public_key_bytes.hex(),
+            },
         )
         assert resp.status == 200
+        assert len(bytes.fromhex(resp.json["result_channel"])) == 32
 
         resp_next = self._client.post("/ai-agents-teams/run/send", json=sing_contract(wallet, resp.json["contract"]))
         assert resp_next.status == 200
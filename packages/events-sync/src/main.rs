@@ -1,8 +1,9 @@
 mod contracts;
 
 use std::fmt::Display;
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, SocketAddr};
 use std::pin::Pin;
+use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::Context;
@@ -12,9 +13,9 @@ use clap::{Parser, Subcommand};
 use contracts::{rho_init_events_channels, rho_subscribe_to_service, rho_unsubscribe_from_service};
 use firefly_client::CommunicationService;
 use firefly_client::models::{BlockId, DeployData};
+use firefly_client::signer::{Signer, SignerArgs, SignerConfig};
 use futures::stream::select_all;
 use futures::{FutureExt, SinkExt, Stream, StreamExt, TryStreamExt, future};
-use secp256k1::SecretKey;
 use serde::{Deserialize, Serialize};
 use tokio::select;
 use tokio::signal::ctrl_c;
@@ -26,9 +27,8 @@ use warp::Filter;
 
 #[derive(Debug, Parser)]
 struct Args {
-    /// Wallet key in hex format
-    #[arg(long)]
-    wallet_key: SecretKey,
+    #[command(flatten)]
+    signer: SignerArgs,
 
     /// Firefly deploy service url
     #[arg(long)]
@@ -42,6 +42,10 @@ struct Args {
     #[arg(long)]
     service_id: String,
 
+    /// Port to serve Prometheus metrics on, metrics are not collected when unset
+    #[arg(long)]
+    metrics_port: Option<u16>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -89,6 +93,10 @@ enum Commands {
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
+    if let Some(port) = args.metrics_port {
+        firefly_client::metrics::install_prometheus((Ipv4Addr::UNSPECIFIED, port))?;
+    }
+    let signer = SignerConfig::from(args.signer).into_signer().await?;
 
     let mut client =
         firefly_client::WriteNodeClient::new(args.deploy_service_url, args.propose_service_url)
@@ -111,7 +119,7 @@ async fn main() -> anyhow::Result<()> {
             let task = tokio::spawn(async move {
                 let mut all_sources = select_all([
                     subscribe_to_firefly(
-                        &args.wallet_key,
+                        signer,
                         client,
                         args.service_id,
                         &external_hostname,
@@ -180,7 +188,7 @@ async fn main() -> anyhow::Result<()> {
                     let rho_code = rho_save_events(channel_name, &events)?;
                     let deploy_data = DeployData::builder(rho_code).build();
                     let hash = client
-                        .full_deploy(&args.wallet_key, deploy_data)
+                        .full_deploy(&signer, deploy_data)
                         .await
                         .context("failed save events")?;
                     println!("events deployed");
@@ -194,7 +202,7 @@ async fn main() -> anyhow::Result<()> {
                     );
                     let deploy_data = DeployData::builder(rho_code).build();
                     client
-                        .full_deploy(&args.wallet_key, deploy_data)
+                        .full_deploy(&signer, deploy_data)
                         .await
                         .context("failed to notify listeners")?;
                     println!("notified");
@@ -212,7 +220,7 @@ async fn main() -> anyhow::Result<()> {
             let rho_code = rho_init_events_channels(&args.service_id);
             let deploy_data = DeployData::builder(rho_code).build();
             let hash = client
-                .full_deploy(&args.wallet_key, deploy_data)
+                .full_deploy(&signer, deploy_data)
                 .await
                 .context("failed to init channels")?;
             println!("{hash}");
@@ -341,7 +349,7 @@ async fn subscribe_to_event_source(
 }
 
 async fn subscribe_to_firefly(
-    key: &SecretKey,
+    signer: Arc<dyn Signer>,
     mut client: firefly_client::WriteNodeClient,
     service_id: String,
     external_hostname: &str,
@@ -353,17 +361,16 @@ async fn subscribe_to_firefly(
     let rho_code = rho_subscribe_to_service(&service_id, self_id, external_hostname, grpc_port);
     let deploy_data = DeployData::builder(rho_code).build();
     client
-        .full_deploy(key, deploy_data)
+        .full_deploy(&signer, deploy_data)
         .await
         .context("failed subscribe to service failed")?;
 
-    let key = *key;
     let mut client = scopeguard::guard(client, move |mut client| {
         tokio::spawn(async move {
             let rho_code = rho_unsubscribe_from_service(&service_id, self_id);
             let deploy_data = DeployData::builder(rho_code).build();
             client
-                .full_deploy(&key, deploy_data)
+                .full_deploy(&signer, deploy_data)
                 .await
                 .context("failed to unsubscribe from service")?;
             println!("unsubscribed");
@@ -0,0 +1,223 @@
+use darling::util::Override;
+use darling::{FromDeriveInput, FromField, FromVariant, ast};
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{DeriveInput, parse_macro_input};
+
+#[derive(Debug, Clone, FromDeriveInput)]
+#[darling(
+    attributes(from_expr),
+    supports(struct_named, struct_newtype, struct_tuple, enum_any)
+)]
+struct Args {
+    ident: syn::Ident,
+    generics: syn::Generics,
+    data: ast::Data<VariantArgs, FieldArgs>,
+}
+
+#[derive(Debug, Clone, FromField)]
+#[darling(attributes(from_expr))]
+struct FieldArgs {
+    ident: Option<syn::Ident>,
+    rename: Option<String>,
+    default: Option<Override<syn::Path>>,
+}
+
+#[derive(Debug, Clone, FromVariant)]
+#[darling(attributes(from_expr))]
+struct VariantArgs {
+    ident: syn::Ident,
+    fields: ast::Fields<FieldArgs>,
+    rename: Option<String>,
+}
+
+pub fn from_expr_derive(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let args = match Args::from_derive_input(&input) {
+        Ok(v) => v,
+        Err(err) => return err.write_errors().into(),
+    };
+
+    let body = match args.data {
+        ast::Data::Struct(fields) => impl_for_struct(fields),
+        ast::Data::Enum(variants) => impl_for_enum(variants),
+    };
+
+    let ident = args.ident;
+    let (impl_generics, ty_generics, where_clause) = args.generics.split_for_impl();
+
+    TokenStream::from(quote! {
+        impl #impl_generics ::firefly_client::helpers::FromExpr for #ident #ty_generics
+            #where_clause
+        {
+            #body
+        }
+    })
+}
+
+fn impl_for_struct(fields: ast::Fields<FieldArgs>) -> proc_macro2::TokenStream {
+    if fields.style == ast::Style::Tuple && fields.fields.len() == 1 {
+        return quote! {
+            fn from(
+                val: ::firefly_client::models::rhoapi::expr::ExprInstance,
+            ) -> ::firefly_client::helpers::_dependencies::anyhow::Result<Self> {
+                ::firefly_client::helpers::FromExpr::from(val).map(Self)
+            }
+
+            fn from_par(
+                par: ::firefly_client::models::rhoapi::Par,
+            ) -> ::firefly_client::helpers::_dependencies::anyhow::Result<Self> {
+                ::firefly_client::helpers::FromExpr::from_par(par).map(Self)
+            }
+        };
+    }
+
+    let decode = decode_fields(&quote!(Self), fields);
+
+    quote! {
+        fn from(
+            val: ::firefly_client::models::rhoapi::expr::ExprInstance,
+        ) -> ::firefly_client::helpers::_dependencies::anyhow::Result<Self> {
+            #decode
+        }
+    }
+}
+
+fn impl_for_enum(variants: Vec<VariantArgs>) -> proc_macro2::TokenStream {
+    let arms = variants.into_iter().map(|variant| {
+        let ident = variant.ident;
+        let name = variant.rename.unwrap_or_else(|| ident.to_string());
+
+        match variant.fields.style {
+            ast::Style::Unit => quote! {
+                (#name, ::std::option::Option::None) => ::std::result::Result::Ok(Self::#ident),
+            },
+            ast::Style::Tuple if variant.fields.fields.len() == 1 => quote! {
+                (#name, ::std::option::Option::Some(value)) => {
+                    ::firefly_client::helpers::FromExpr::from_par(value).map(Self::#ident)
+                }
+            },
+            _ => {
+                let decode = decode_fields(&quote!(Self::#ident), variant.fields);
+                quote! {
+                    (#name, ::std::option::Option::Some(value)) => {
+                        let val = ::firefly_client::helpers::expr_instance(value)?;
+                        #decode
+                    }
+                }
+            }
+        }
+    });
+
+    quote! {
+        fn from(
+            val: ::firefly_client::models::rhoapi::expr::ExprInstance,
+        ) -> ::firefly_client::helpers::_dependencies::anyhow::Result<Self> {
+            let (variant, value): (::std::string::String, _) = match val {
+                ::firefly_client::models::rhoapi::expr::ExprInstance::GString(variant) => {
+                    (variant, ::std::option::Option::None)
+                }
+                ::firefly_client::models::rhoapi::expr::ExprInstance::ETupleBody(_) => {
+                    let mut elements = ::firefly_client::helpers::tuple_elements(val, 2)?.into_iter();
+                    let variant = ::firefly_client::helpers::FromExpr::from_par(
+                        elements.next().unwrap_or_default(),
+                    )?;
+                    (variant, elements.next())
+                }
+                other => {
+                    return ::std::result::Result::Err(
+                        ::firefly_client::helpers::_dependencies::anyhow::anyhow!(
+                            "unexpected expr type: {other:?} expected GString or ETupleBody"
+                        ),
+                    );
+                }
+            };
+
+            match (variant.as_str(), value) {
+                #(#arms)*
+                (other, _) => ::std::result::Result::Err(
+                    ::firefly_client::helpers::_dependencies::anyhow::anyhow!(
+                        "unexpected variant: {other}"
+                    ),
+                ),
+            }
+        }
+    }
+}
+
+fn decode_fields(
+    constructor: &proc_macro2::TokenStream,
+    fields: ast::Fields<FieldArgs>,
+) -> proc_macro2::TokenStream {
+    match fields.style {
+        ast::Style::Tuple => {
+            let len = fields.fields.len();
+            let values = (0..len).map(|i| {
+                let message = format!("invalid tuple element {i}");
+                quote! {
+                    ::firefly_client::helpers::_dependencies::anyhow::Context::context(
+                        ::firefly_client::helpers::FromExpr::from_par(
+                            elements.next().unwrap_or_default(),
+                        ),
+                        #message,
+                    )?
+                }
+            });
+
+            quote! {
+                let mut elements = ::firefly_client::helpers::tuple_elements(val, #len)?.into_iter();
+                ::std::result::Result::Ok(#constructor(#(#values),*))
+            }
+        }
+        ast::Style::Struct => {
+            let values = fields.fields.into_iter().map(|f| {
+                let ident = f.ident.unwrap();
+                let key = f.rename.unwrap_or_else(|| ident.to_string());
+                let invalid = format!("invalid field `{key}`");
+                let missing = match f.default {
+                    Some(Override::Inherit) => quote! {
+                        ::std::default::Default::default()
+                    },
+                    Some(Override::Explicit(path)) => quote! {
+                        #path()
+                    },
+                    None => {
+                        let message = format!("missing field `{key}`");
+                        quote! {
+                            ::firefly_client::helpers::_dependencies::anyhow::Context::context(
+                                ::firefly_client::helpers::FromExpr::from_par(
+                                    ::std::default::Default::default(),
+                                ),
+                                #message,
+                            )?
+                        }
+                    }
+                };
+
+                quote! {
+                    #ident: match map.remove(#key) {
+                        ::std::option::Option::Some(par) => {
+                            ::firefly_client::helpers::_dependencies::anyhow::Context::context(
+                                ::firefly_client::helpers::FromExpr::from_par(par),
+                                #invalid,
+                            )?
+                        }
+                        ::std::option::Option::None => #missing,
+                    }
+                }
+            });
+
+            quote! {
+                let mut map: ::std::collections::HashMap<
+                    ::std::string::String,
+                    ::firefly_client::models::rhoapi::Par,
+                > = ::firefly_client::helpers::FromExpr::from(val)?;
+                ::std::result::Result::Ok(#constructor { #(#values),* })
+            }
+        }
+        ast::Style::Unit => quote! {
+            ::std::result::Result::Ok(#constructor)
+        },
+    }
+}
@@ -1,8 +1,14 @@
 use proc_macro::TokenStream;
 
+mod from_expr;
 mod into_value;
 mod render;
 
+#[proc_macro_derive(FromExpr, attributes(from_expr))]
+pub fn from_expr_derive(input: TokenStream) -> TokenStream {
+    from_expr::from_expr_derive(input)
+}
+
 #[proc_macro_derive(IntoValue)]
 pub fn into_value_derive(input: TokenStream) -> TokenStream {
     into_value::into_value_derive(input)
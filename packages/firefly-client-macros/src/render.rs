@@ -92,7 +92,7 @@ fn impl_for_struct(
         } else {
             quote! {
                 #(#attrs)*
-                #ident: ::firefly_client::rendering::IntoValue::into_value(self.#ident)
+                #ident: ::firefly_client::rendering::template_value(self.#ident)?
             }
         }
     });
@@ -183,7 +183,7 @@ fn impl_for_enum(
             } else {
                 quote! {
                     #(#attrs)*
-                    #ident: ::firefly_client::rendering::IntoValue::into_value(#ident)
+                    #ident: ::firefly_client::rendering::template_value(#ident)?
                 }
             }
         });
@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use serde::Deserialize;
+use tokio_tungstenite::Connector;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tonic::metadata::MetadataMap;
+use tonic::service::Interceptor;
+use tonic::service::interceptor::InterceptedService;
+use tonic::transport::{Certificate, Channel, ClientTlsConfig, Endpoint, Identity};
+
+pub(crate) type GrpcChannel = InterceptedService<Channel, HeaderInterceptor>;
+
+/// How to connect to a node, shared by [`crate::WriteNodeClient`], [`crate::ReadNodeClient`] and
+/// [`crate::NodeEvents`].
+///
+/// TLS is used for `https://` and `wss://` urls and trusts the webpki roots plus `ca_cert`.
+#[derive(derive_more::Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ConnectionOptions {
+    /// PEM bundle with extra CA certificates to trust, e.g. for self-signed node certificates.
+    pub ca_cert: Option<PathBuf>,
+    /// PEM client certificate presented for mutual TLS, together with `client_key`.
+    pub client_cert: Option<PathBuf>,
+    pub client_key: Option<PathBuf>,
+    /// Headers sent with every request, e.g. `authorization: Bearer <token>` or an API key.
+    #[debug("{:?}", headers.keys().collect::<Vec<_>>())]
+    pub headers: HashMap<String, String>,
+    pub connect_timeout_secs: Option<u64>,
+    pub request_timeout_secs: Option<u64>,
+}
+
+impl ConnectionOptions {
+    fn connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout_secs.map(Duration::from_secs)
+    }
+
+    fn request_timeout(&self) -> Option<Duration> {
+        self.request_timeout_secs.map(Duration::from_secs)
+    }
+
+    /// Parses the configured headers. Names and values must be printable ASCII, and binary `-bin`
+    /// headers are not supported, so that every header is sent the same over HTTP, gRPC and
+    /// websockets.
+    pub fn headers(&self) -> anyhow::Result<HeaderMap> {
+        self.headers
+            .iter()
+            .map(|(name, value)| {
+                let name = HeaderName::from_bytes(name.as_bytes())
+                    .with_context(|| format!("invalid header name {name:?}"))?;
+                anyhow::ensure!(
+                    !name.as_str().ends_with("-bin"),
+                    "binary header {name} is not supported"
+                );
+                let value = HeaderValue::from_str(value)
+                    .ok()
+                    .filter(|value| value.to_str().is_ok())
+                    .with_context(|| format!("invalid value for header {name}"))?;
+                Ok((name, value))
+            })
+            .collect()
+    }
+
+    fn read_ca_cert(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        self.ca_cert
+            .as_ref()
+            .map(|path| {
+                std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))
+            })
+            .transpose()
+    }
+
+    fn read_identity(&self) -> anyhow::Result<Option<(Vec<u8>, Vec<u8>)>> {
+        match (&self.client_cert, &self.client_key) {
+            (Some(cert), Some(key)) => {
+                let cert = std::fs::read(cert)
+                    .with_context(|| format!("failed to read {}", cert.display()))?;
+                let key = std::fs::read(key)
+                    .with_context(|| format!("failed to read {}", key.display()))?;
+                Ok(Some((cert, key)))
+            }
+            (None, None) => Ok(None),
+            _ => Err(anyhow::anyhow!(
+                "client_cert and client_key must be set together"
+            )),
+        }
+    }
+
+    /// Lazily connecting gRPC channel to `url` that sends the configured headers.
+    pub(crate) fn grpc_channel(&self, url: String) -> anyhow::Result<GrpcChannel> {
+        let headers = self.headers()?;
+        let endpoint = self.grpc_endpoint(url)?;
+        Ok(intercept(endpoint.connect_lazy(), headers))
+    }
+
+    /// Same as [`Self::grpc_channel`], but connects right away.
+    pub(crate) async fn connect_grpc(&self, url: String) -> anyhow::Result<GrpcChannel> {
+        let headers = self.headers()?;
+        let channel = self.grpc_endpoint(url)?.connect().await?;
+        Ok(intercept(channel, headers))
+    }
+
+    fn grpc_endpoint(&self, url: String) -> anyhow::Result<Endpoint> {
+        let mut endpoint = Endpoint::from_shared(url).context("invalid grpc url")?;
+
+        if let Some(timeout) = self.connect_timeout() {
+            endpoint = endpoint.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.request_timeout() {
+            endpoint = endpoint.timeout(timeout);
+        }
+
+        if endpoint.uri().scheme_str() == Some("https") {
+            let mut tls = ClientTlsConfig::new().with_webpki_roots();
+            if let Some(ca_cert) = self.read_ca_cert()? {
+                tls = tls.ca_certificate(Certificate::from_pem(ca_cert));
+            }
+            if let Some((cert, key)) = self.read_identity()? {
+                tls = tls.identity(Identity::from_pem(cert, key));
+            }
+            endpoint = endpoint.tls_config(tls).context("invalid tls config")?;
+        }
+
+        Ok(endpoint)
+    }
+
+    pub(crate) fn http_client(&self) -> anyhow::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder().default_headers(self.headers()?);
+
+        if let Some(timeout) = self.connect_timeout() {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.request_timeout() {
+            builder = builder.timeout(timeout);
+        }
+        if let Some(ca_cert) = self.read_ca_cert()? {
+            for cert in reqwest::Certificate::from_pem_bundle(&ca_cert)? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        if let Some((mut cert, key)) = self.read_identity()? {
+            cert.extend(key);
+            builder = builder.identity(reqwest::Identity::from_pem(&cert)?);
+        }
+
+        builder.build().context("failed to build http client")
+    }
+
+    pub(crate) fn websocket_request(&self, url: &str) -> anyhow::Result<Request> {
+        let mut request = url.into_client_request()?;
+        request.headers_mut().extend(self.headers()?);
+        Ok(request)
+    }
+
+    /// Custom TLS connector for websockets, `None` when the default one will do.
+    pub(crate) fn websocket_connector(&self) -> anyhow::Result<Option<Connector>> {
+        let ca_cert = self.read_ca_cert()?;
+        let identity = self.read_identity()?;
+        if ca_cert.is_none() && identity.is_none() {
+            return Ok(None);
+        }
+
+        let mut roots = rustls::RootCertStore {
+            roots: webpki_roots::TLS_SERVER_ROOTS.to_vec(),
+        };
+        for cert in CertificateDer::pem_slice_iter(ca_cert.as_deref().unwrap_or_default()) {
+            roots.add(cert.context("invalid ca certificate")?)?;
+        }
+
+        let builder = rustls::ClientConfig::builder_with_provider(Arc::new(
+            rustls::crypto::ring::default_provider(),
+        ))
+        .with_safe_default_protocol_versions()?
+        .with_root_certificates(roots);
+
+        let config = match identity {
+            Some((cert, key)) => {
+                let certs = CertificateDer::pem_slice_iter(&cert)
+                    .collect::<Result<_, _>>()
+                    .context("invalid client certificate")?;
+                let key = PrivateKeyDer::from_pem_slice(&key).context("invalid client key")?;
+                builder.with_client_auth_cert(certs, key)?
+            }
+            None => builder.with_no_client_auth(),
+        };
+
+        Ok(Some(Connector::Rustls(Arc::new(config))))
+    }
+}
+
+fn intercept(channel: Channel, headers: HeaderMap) -> GrpcChannel {
+    InterceptedService::new(
+        channel,
+        HeaderInterceptor {
+            headers: Arc::new(MetadataMap::from_headers(headers)),
+        },
+    )
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct HeaderInterceptor {
+    headers: Arc<MetadataMap>,
+}
+
+impl Interceptor for HeaderInterceptor {
+    fn call(
+        &mut self,
+        mut request: tonic::Request<()>,
+    ) -> Result<tonic::Request<()>, tonic::Status> {
+        // `ConnectionOptions::headers` only lets ascii headers through
+        for entry in self.headers.iter() {
+            if let tonic::metadata::KeyAndValueRef::Ascii(name, value) = entry {
+                request.metadata_mut().insert(name.clone(), value.clone());
+            }
+        }
+        Ok(request)
+    }
+}
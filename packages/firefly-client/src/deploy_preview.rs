@@ -0,0 +1,119 @@
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use chrono::{DateTime, Utc};
+use prost::Message as _;
+use secp256k1::{Message, PublicKey, Secp256k1, ecdsa};
+use thiserror::Error;
+
+use crate::models::casper::DeployDataProto;
+use crate::models::{SignedCode, WalletAddress};
+
+const SECP256K1_ALGORITHM: &str = "secp256k1";
+
+#[derive(Debug, Clone, Error)]
+pub enum DeployPreviewError {
+    #[error("invalid deploy data: {0}")]
+    Decode(#[from] prost::DecodeError),
+
+    #[error("invalid timestamp: {0}")]
+    InvalidTimestamp(i64),
+
+    #[error("invalid deployer: {0}")]
+    InvalidDeployer(#[from] secp256k1::Error),
+
+    #[error("unsupported signature algorithm: {0}")]
+    UnsupportedSigAlgorithm(String),
+}
+
+/// Signer of a deploy, taken from its public key.
+#[derive(Debug, Clone)]
+pub struct Deployer {
+    pub public_key: PublicKey,
+    pub wallet_address: WalletAddress,
+    /// Whether the signature was made by `public_key` over this deploy.
+    pub signature_valid: bool,
+}
+
+/// Decoded contents of a prepared or signed deploy, so it can be shown before it is signed or sent.
+#[derive(Debug, Clone)]
+pub struct DeployPreview {
+    pub term: String,
+    pub timestamp: DateTime<Utc>,
+    pub phlo_limit: u64,
+    pub phlo_price: u64,
+    pub shard_id: String,
+    pub valid_after_block_number: u64,
+    /// Present when the deploy carries a signature.
+    pub deployer: Option<Deployer>,
+}
+
+impl DeployPreview {
+    /// Decodes an encoded `DeployDataProto`, either prepared for signing or already signed.
+    pub fn decode(contract: &[u8]) -> Result<Self, DeployPreviewError> {
+        DeployDataProto::decode(contract)?.try_into()
+    }
+}
+
+impl TryFrom<DeployDataProto> for DeployPreview {
+    type Error = DeployPreviewError;
+
+    fn try_from(mut value: DeployDataProto) -> Result<Self, Self::Error> {
+        let sig = std::mem::take(&mut value.sig);
+        let sig_algorithm = std::mem::take(&mut value.sig_algorithm);
+        let deployer = std::mem::take(&mut value.deployer);
+
+        let deployer = if deployer.is_empty() {
+            None
+        } else {
+            if sig_algorithm != SECP256K1_ALGORITHM {
+                return Err(DeployPreviewError::UnsupportedSigAlgorithm(sig_algorithm));
+            }
+
+            let public_key = PublicKey::from_slice(&deployer)?;
+            let hash = Blake2b::<U32>::new()
+                .chain_update(value.encode_to_vec())
+                .finalize();
+
+            Some(Deployer {
+                public_key,
+                wallet_address: public_key.into(),
+                signature_valid: verify_signature(&public_key, hash.into(), &sig),
+            })
+        };
+
+        Ok(Self {
+            timestamp: DateTime::from_timestamp_millis(value.timestamp)
+                .ok_or(DeployPreviewError::InvalidTimestamp(value.timestamp))?,
+            term: value.term,
+            phlo_limit: value.phlo_limit as _,
+            phlo_price: value.phlo_price as _,
+            shard_id: value.shard_id,
+            valid_after_block_number: value.valid_after_block_number as _,
+            deployer,
+        })
+    }
+}
+
+impl TryFrom<SignedCode> for DeployPreview {
+    type Error = DeployPreviewError;
+
+    fn try_from(value: SignedCode) -> Result<Self, Self::Error> {
+        let mut deploy_data = value.deploy_data()?;
+        deploy_data.sig = value.sig;
+        deploy_data.sig_algorithm = value.sig_algorithm;
+        deploy_data.deployer = value.deployer;
+        deploy_data.try_into()
+    }
+}
+
+/// Checks a DER signature over the deploy hash. High-S signatures are normalized first.
+fn verify_signature(public_key: &PublicKey, hash: [u8; 32], sig: &[u8]) -> bool {
+    let Ok(mut signature) = ecdsa::Signature::from_der(sig) else {
+        return false;
+    };
+    signature.normalize_s();
+
+    Secp256k1::verification_only()
+        .verify_ecdsa(Message::from_digest(hash), &signature, public_key)
+        .is_ok()
+}
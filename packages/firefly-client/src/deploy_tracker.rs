@@ -0,0 +1,126 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::FutureExt;
+use futures::future::FusedFuture;
+use tokio::time::{Instant, MissedTickBehavior};
+
+use crate::models::{BlockId, DeployId};
+use crate::{DeployTransport, EventSource};
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+const DEFAULT_DEPLOY_LIFESPAN: u64 = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeployOutcome {
+    Finalized {
+        block: BlockId,
+        cost: u64,
+        errored: bool,
+    },
+    Included {
+        block: BlockId,
+    },
+    Expired,
+    Unknown,
+}
+
+/// Waits for a deploy to settle using both the websocket events and `findDeploy` + `isFinalized`
+/// polling, so a missed finalization event only delays the outcome instead of losing it.
+#[derive(Clone, bon::Builder)]
+pub struct DeployTracker {
+    write_client: Arc<dyn DeployTransport>,
+    node_events: Arc<dyn EventSource>,
+
+    #[builder(default = DEFAULT_POLL_INTERVAL)]
+    poll_interval: Duration,
+
+    /// Number of blocks after `valid_after_block_number` during which the node accepts the deploy.
+    #[builder(default = DEFAULT_DEPLOY_LIFESPAN)]
+    deploy_lifespan: u64,
+}
+
+impl DeployTracker {
+    pub async fn track(
+        &self,
+        deploy_id: &DeployId,
+        valid_after_block_number: Option<u64>,
+        max_wait: Duration,
+    ) -> DeployOutcome {
+        let deadline = Instant::now() + max_wait;
+        let mut notified =
+            std::pin::pin!(self.node_events.wait_for_deploy(deploy_id, max_wait).fuse());
+
+        let mut poll = tokio::time::interval(self.poll_interval);
+        poll.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        poll.reset();
+
+        let mut included = None;
+
+        loop {
+            tokio::select! {
+                finalized = &mut notified, if !notified.is_terminated() => {
+                    if !finalized {
+                        break;
+                    }
+                }
+                _ = poll.tick() => {}
+                () = tokio::time::sleep_until(deadline) => break,
+            }
+
+            match self.poll(deploy_id).await {
+                Ok(Some(outcome @ DeployOutcome::Finalized { .. })) => return outcome,
+                Ok(Some(outcome)) => included = Some(outcome),
+                Ok(None) => {}
+                Err(err) => tracing::warn!("failed to poll deploy {deploy_id}: {err:?}"),
+            }
+        }
+
+        if let Some(outcome) = included {
+            return outcome;
+        }
+
+        match valid_after_block_number {
+            Some(valid_after) => match self.write_client.get_head_block_index().await {
+                Ok(head) if head > valid_after + self.deploy_lifespan => DeployOutcome::Expired,
+                Ok(_) => DeployOutcome::Unknown,
+                Err(err) => {
+                    tracing::warn!("failed to get head block index: {err:?}");
+                    DeployOutcome::Unknown
+                }
+            },
+            None => DeployOutcome::Unknown,
+        }
+    }
+
+    async fn poll(&self, deploy_id: &DeployId) -> anyhow::Result<Option<DeployOutcome>> {
+        let write_client = &self.write_client;
+
+        let Some(block) = write_client.find_deploy(deploy_id).await? else {
+            return Ok(None);
+        };
+
+        if !write_client.is_finalized(&block.block_hash).await? {
+            return Ok(Some(DeployOutcome::Included {
+                block: block.block_hash,
+            }));
+        }
+
+        let block = write_client.get_block(&block.block_hash).await?;
+        let deploy = block
+            .deploys
+            .into_iter()
+            .find(|deploy| &deploy.id == deploy_id);
+
+        Ok(Some(match deploy {
+            Some(deploy) => DeployOutcome::Finalized {
+                block: block.info.block_hash,
+                cost: deploy.cost,
+                errored: deploy.errored,
+            },
+            None => DeployOutcome::Included {
+                block: block.info.block_hash,
+            },
+        }))
+    }
+}
@@ -1,11 +1,109 @@
+use crate::models::servicemodelapi::ServiceError;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ReadNodeError {
     #[error("contract did not return any value")]
     ReturnValueMissing,
     #[error("read node returned error: status {0}, body {1}")]
     Api(reqwest::StatusCode, String),
+    #[error("read node returned error: {0}")]
+    Service(String),
     #[error("failed to deserialize: {0}")]
     Deserialization(anyhow::Error),
     #[error("http transport error: {0}")]
     Transport(#[from] reqwest::Error),
+    #[error("grpc transport error: {0}")]
+    Grpc(#[from] tonic::Status),
+}
+
+impl ReadNodeError {
+    /// Short label of the variant, e.g. for metrics.
+    pub const fn reason(&self) -> &'static str {
+        match self {
+            Self::ReturnValueMissing => "return_value_missing",
+            Self::Api(..) => "api",
+            Self::Service(_) => "service",
+            Self::Deserialization(_) => "deserialization",
+            Self::Transport(_) => "transport",
+            Self::Grpc(_) => "grpc",
+        }
+    }
+}
+
+/// Why a validator did not propose. The node only reports messages, which are classified here so
+/// that callers can tell what is worth retrying.
+#[derive(Debug, thiserror::Error)]
+pub enum ProposeError {
+    #[error("another propose is in progress")]
+    Busy,
+    #[error("no new deploys to propose")]
+    NoNewDeploys,
+    #[error("validator is not bonded")]
+    NotBonded,
+    #[error("not enough new blocks from other validators")]
+    NotEnoughNewBlocks,
+    #[error("propose failed: {}", .0.join("; "))]
+    Failed(Vec<String>),
+    #[error("invalid propose response: {0}")]
+    InvalidResponse(&'static str),
+    #[error("propose grpc error: {0}")]
+    Grpc(#[from] tonic::Status),
+}
+
+impl ProposeError {
+    /// Whether proposing again later can succeed without anything else changing.
+    pub const fn is_retryable(&self) -> bool {
+        matches!(self, Self::Busy | Self::NotEnoughNewBlocks)
+    }
+
+    /// Short label of the variant, e.g. for metrics.
+    pub const fn reason(&self) -> &'static str {
+        match self {
+            Self::Busy => "busy",
+            Self::NoNewDeploys => "no_new_deploys",
+            Self::NotBonded => "not_bonded",
+            Self::NotEnoughNewBlocks => "not_enough_new_blocks",
+            Self::Failed(_) => "failed",
+            Self::InvalidResponse(_) => "invalid_response",
+            Self::Grpc(_) => "grpc",
+        }
+    }
+}
+
+impl From<ServiceError> for ProposeError {
+    fn from(error: ServiceError) -> Self {
+        let normalized: String = error
+            .messages
+            .iter()
+            .flat_map(|message| message.chars())
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect();
+
+        if normalized.contains("anotherproposeisinprogress") {
+            Self::Busy
+        } else if normalized.contains("nonewdeploys") {
+            Self::NoNewDeploys
+        } else if normalized.contains("notbonded") {
+            Self::NotBonded
+        } else if normalized.contains("notenoughnewblocks") {
+            Self::NotEnoughNewBlocks
+        } else {
+            Self::Failed(error.messages)
+        }
+    }
+}
+
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ProposeSchedulerError {
+    #[error("propose scheduler is stopped")]
+    Stopped,
+    #[error(
+        "deploy was not included in a block after {attempts} proposals: {}",
+        last_error.as_deref().unwrap_or("deploy not found")
+    )]
+    NotIncluded {
+        attempts: u32,
+        last_error: Option<String>,
+    },
 }
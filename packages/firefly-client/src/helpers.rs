@@ -1,17 +1,61 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::hash::Hash;
 
 use anyhow::{Context, anyhow};
 use blake2::digest::consts::U32;
 use blake2::{Blake2b, Digest};
 use chrono::{DateTime, Utc};
+pub use firefly_client_macros::FromExpr;
 use prost::Message as _;
-use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use secp256k1::PublicKey;
+use uuid::Uuid;
 
-use crate::models::rhoapi;
 use crate::models::rhoapi::expr::ExprInstance;
+use crate::models::{BlockId, Uri, WalletAddress, rhoapi};
+use crate::rendering::Value;
+use crate::signer::Signer;
 
 pub trait FromExpr: Sized {
     fn from(val: ExprInstance) -> anyhow::Result<Self>;
+
+    fn from_par(par: rhoapi::Par) -> anyhow::Result<Self> {
+        expr_instance(par).and_then(Self::from)
+    }
+}
+
+/// Takes the first expression out of `par`, which is how single values are sent over the wire.
+pub fn expr_instance(par: rhoapi::Par) -> anyhow::Result<ExprInstance> {
+    par.exprs
+        .into_iter()
+        .next()
+        .context("missing exprs")?
+        .expr_instance
+        .context("missing expr_instance")
+}
+
+impl FromExpr for rhoapi::Par {
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        Ok(Self {
+            exprs: vec![rhoapi::Expr {
+                expr_instance: Some(val),
+            }],
+            ..Default::default()
+        })
+    }
+
+    fn from_par(par: rhoapi::Par) -> anyhow::Result<Self> {
+        Ok(par)
+    }
+}
+
+impl FromExpr for Value {
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        <rhoapi::Par as FromExpr>::from(val).and_then(Self::from_par)
+    }
+
+    fn from_par(par: rhoapi::Par) -> anyhow::Result<Self> {
+        par.try_into().map_err(Into::into)
+    }
 }
 
 impl FromExpr for String {
@@ -23,21 +67,64 @@ impl FromExpr for String {
     }
 }
 
+impl FromExpr for bool {
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        match val {
+            ExprInstance::GBool(value) => Ok(value),
+            other => Err(anyhow!("unexpected expr type: {other:?} expected GBool")),
+        }
+    }
+}
+
+impl FromExpr for i64 {
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        match val {
+            ExprInstance::GInt(value) => Ok(value),
+            other => Err(anyhow!("unexpected expr type: {other:?} expected GInt")),
+        }
+    }
+}
+
+// u8 is left out so that Vec<u8> keeps decoding from GByteArray
+macro_rules! impl_from_expr_for_int {
+    ($($ty:ty),+) => {
+        $(
+            impl FromExpr for $ty {
+                fn from(val: ExprInstance) -> anyhow::Result<Self> {
+                    let value: i64 = FromExpr::from(val)?;
+                    Self::try_from(value)
+                        .with_context(|| format!("{value} is out of range for {}", stringify!($ty)))
+                }
+            }
+        )+
+    };
+}
+
+impl_from_expr_for_int!(i8, i16, i32, u16, u32, u64, usize);
+
+impl<T> FromExpr for Option<T>
+where
+    T: FromExpr,
+{
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        T::from(val).map(Some)
+    }
+
+    fn from_par(par: rhoapi::Par) -> anyhow::Result<Self> {
+        if par == rhoapi::Par::default() {
+            return Ok(None);
+        }
+        T::from_par(par).map(Some)
+    }
+}
+
 impl<T> FromExpr for Vec<T>
 where
     T: FromExpr,
 {
     fn from(val: ExprInstance) -> anyhow::Result<Self> {
         match val {
-            ExprInstance::EListBody(list) => list
-                .ps
-                .into_iter()
-                .map(|par| {
-                    let expr = par.exprs.into_iter().next().context("missing exprs")?;
-                    let expr = expr.expr_instance.context("missing expr_instance")?;
-                    T::from(expr)
-                })
-                .collect(),
+            ExprInstance::EListBody(list) => list.ps.into_iter().map(T::from_par).collect(),
             other => Err(anyhow!(
                 "unexpected expr type: {other:?} expected EListBody"
             )),
@@ -45,38 +132,65 @@ where
     }
 }
 
-impl<T> FromExpr for HashMap<String, T>
+impl<T> FromExpr for BTreeSet<T>
 where
-    T: FromExpr,
+    T: FromExpr + Ord,
+{
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        match val {
+            ExprInstance::ESetBody(set) => set.ps.into_iter().map(T::from_par).collect(),
+            other => Err(anyhow!("unexpected expr type: {other:?} expected ESetBody")),
+        }
+    }
+}
+
+impl<T> FromExpr for HashSet<T>
+where
+    T: FromExpr + Eq + Hash,
 {
     fn from(val: ExprInstance) -> anyhow::Result<Self> {
         match val {
-            ExprInstance::EMapBody(map) => map
-                .kvs
-                .into_iter()
-                .map(|pair| {
-                    let key = pair
-                        .key
-                        .and_then(|key| key.exprs.into_iter().next())
-                        .and_then(|expr| expr.expr_instance)
-                        .context("missing key")?;
-                    let key = FromExpr::from(key)?;
-
-                    let value = pair
-                        .value
-                        .and_then(|value| value.exprs.into_iter().next())
-                        .and_then(|expr| expr.expr_instance)
-                        .context("missing value")?;
-                    let value = FromExpr::from(value)?;
-
-                    Ok((key, value))
-                })
-                .collect(),
-            other => Err(anyhow!("unexpected expr type: {other:?} expected EMapBody")),
+            ExprInstance::ESetBody(set) => set.ps.into_iter().map(T::from_par).collect(),
+            other => Err(anyhow!("unexpected expr type: {other:?} expected ESetBody")),
         }
     }
 }
 
+fn map_entries<K, V>(
+    val: ExprInstance,
+) -> anyhow::Result<impl Iterator<Item = anyhow::Result<(K, V)>>>
+where
+    K: FromExpr,
+    V: FromExpr,
+{
+    match val {
+        ExprInstance::EMapBody(map) => Ok(map.kvs.into_iter().map(|pair| {
+            let key = K::from_par(pair.key.context("missing key")?)?;
+            let value = V::from_par(pair.value.context("missing value")?)?;
+            Ok((key, value))
+        })),
+        other => Err(anyhow!("unexpected expr type: {other:?} expected EMapBody")),
+    }
+}
+
+impl<T> FromExpr for HashMap<String, T>
+where
+    T: FromExpr,
+{
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        map_entries(val)?.collect()
+    }
+}
+
+impl<T> FromExpr for BTreeMap<String, T>
+where
+    T: FromExpr,
+{
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        map_entries(val)?.collect()
+    }
+}
+
 impl FromExpr for Vec<u8> {
     fn from(val: ExprInstance) -> anyhow::Result<Self> {
         match val {
@@ -88,6 +202,78 @@ impl FromExpr for Vec<u8> {
     }
 }
 
+/// Unpacks an `ETupleBody` of exactly `len` elements.
+pub fn tuple_elements(val: ExprInstance, len: usize) -> anyhow::Result<Vec<rhoapi::Par>> {
+    match val {
+        ExprInstance::ETupleBody(tuple) if tuple.ps.len() == len => Ok(tuple.ps),
+        ExprInstance::ETupleBody(tuple) => Err(anyhow!(
+            "unexpected tuple length: {} expected {len}",
+            tuple.ps.len()
+        )),
+        other => Err(anyhow!(
+            "unexpected expr type: {other:?} expected ETupleBody"
+        )),
+    }
+}
+
+macro_rules! impl_from_expr_for_tuple {
+    ($len:literal; $($ty:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$($ty),+> FromExpr for ($($ty,)+)
+        where
+            $($ty: FromExpr,)+
+        {
+            fn from(val: ExprInstance) -> anyhow::Result<Self> {
+                let mut elements = tuple_elements(val, $len)?.into_iter();
+                $(
+                    let $ty = $ty::from_par(elements.next().context("missing tuple element")?)?;
+                )+
+                Ok(($($ty,)+))
+            }
+        }
+    };
+}
+
+impl_from_expr_for_tuple!(1; R1);
+impl_from_expr_for_tuple!(2; R1, R2);
+impl_from_expr_for_tuple!(3; R1, R2, R3);
+impl_from_expr_for_tuple!(4; R1, R2, R3, R4);
+impl_from_expr_for_tuple!(5; R1, R2, R3, R4, R5);
+impl_from_expr_for_tuple!(6; R1, R2, R3, R4, R5, R6);
+impl_from_expr_for_tuple!(7; R1, R2, R3, R4, R5, R6, R7);
+impl_from_expr_for_tuple!(8; R1, R2, R3, R4, R5, R6, R7, R8);
+impl_from_expr_for_tuple!(9; R1, R2, R3, R4, R5, R6, R7, R8, R9);
+
+impl FromExpr for Uri {
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        match val {
+            ExprInstance::GUri(value) => value.try_into().map_err(Into::into),
+            other => Err(anyhow!("unexpected expr type: {other:?} expected GUri")),
+        }
+    }
+}
+
+impl FromExpr for WalletAddress {
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        let value: String = FromExpr::from(val)?;
+        value.try_into().map_err(Into::into)
+    }
+}
+
+impl FromExpr for Uuid {
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        let value: String = FromExpr::from(val)?;
+        value.parse().map_err(Into::into)
+    }
+}
+
+impl FromExpr for BlockId {
+    fn from(val: ExprInstance) -> anyhow::Result<Self> {
+        let value: String = FromExpr::from(val)?;
+        Ok(value.into())
+    }
+}
+
 pub trait ShortHex {
     fn short_hex(&self, length: usize) -> String;
 }
@@ -106,12 +292,15 @@ where
     }
 }
 
-pub fn insert_signed_signature(
-    key: &SecretKey,
+pub async fn insert_signed_signature<S>(
+    signer: &S,
     timestamp: DateTime<Utc>,
     deployer: &PublicKey,
     version: i64,
-) -> Vec<u8> {
+) -> anyhow::Result<Vec<u8>>
+where
+    S: Signer + ?Sized,
+{
     let par = rhoapi::Par {
         exprs: vec![rhoapi::Expr {
             expr_instance: Some(rhoapi::expr::ExprInstance::ETupleBody(rhoapi::ETuple {
@@ -148,16 +337,19 @@ pub fn insert_signed_signature(
 
     let hash = Blake2b::<U32>::new().chain_update(par).finalize();
 
-    Secp256k1::new()
-        .sign_ecdsa(Message::from_digest(hash.into()), key)
-        .serialize_der()
-        .to_vec()
+    Ok(signer.sign(hash.into()).await?.serialize_der().to_vec())
 }
 
-#[test]
-fn test_insert_signed_signature() {
+pub mod _dependencies {
+    pub use anyhow;
+}
+
+#[tokio::test]
+async fn test_insert_signed_signature() {
     use std::str::FromStr;
 
+    use secp256k1::{Secp256k1, SecretKey};
+
     let secp = Secp256k1::new();
     let timestamp = DateTime::from_timestamp_millis(1_559_156_356_769).unwrap();
     let secret_key =
@@ -166,7 +358,9 @@ fn test_insert_signed_signature() {
     let public_key = PublicKey::from_secret_key(&secp, &secret_key);
     let version = 9_223_372_036_854_775_807;
 
-    let sig = insert_signed_signature(&secret_key, timestamp, &public_key, version);
+    let sig = insert_signed_signature(&secret_key, timestamp, &public_key, version)
+        .await
+        .unwrap();
 
     assert_eq!(
         hex::encode(sig),
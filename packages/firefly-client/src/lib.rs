@@ -1,13 +1,30 @@
 mod communication_service;
+pub mod connection;
+mod deploy_preview;
+mod deploy_tracker;
 pub mod errors;
 pub mod helpers;
+pub mod metrics;
 pub mod models;
 pub mod node_events;
+mod propose_scheduler;
 mod read_node_client;
+pub mod read_node_expr;
 pub mod rendering;
+pub mod signer;
 mod write_node_client;
 
 pub use communication_service::CommunicationService;
-pub use node_events::NodeEvents;
-pub use read_node_client::ReadNodeClient;
-pub use write_node_client::WriteNodeClient;
+pub use connection::ConnectionOptions;
+pub use deploy_preview::{DeployPreview, DeployPreviewError, Deployer};
+pub use deploy_tracker::{DeployOutcome, DeployTracker};
+pub use node_events::{EventSource, EventsStatus, NodeEvents};
+pub use propose_scheduler::{ProposeScheduler, Proposer};
+pub use read_node_client::{ExploreTransport, GrpcTransport, HttpTransport, ReadNodeClient};
+pub use write_node_client::{
+    DeployTransport,
+    ValidatorEndpoint,
+    ValidatorProbe,
+    WriteNodeClient,
+    never_sent,
+};
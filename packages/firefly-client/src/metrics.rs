@@ -0,0 +1,120 @@
+//! Metrics recorded by the clients through the [`metrics`](::metrics) facade. Nothing is exported
+//! until the binary installs a recorder, e.g. the Prometheus one.
+
+#[cfg(feature = "prometheus")]
+use std::net::SocketAddr;
+use std::time::Instant;
+
+use ::metrics::{
+    Unit,
+    counter,
+    describe_counter,
+    describe_gauge,
+    describe_histogram,
+    gauge,
+    histogram,
+};
+
+pub const DEPLOY_DURATION: &str = "firefly_deploy_duration_seconds";
+pub const DEPLOY_FAILURES: &str = "firefly_deploy_failures_total";
+pub const PROPOSE_DURATION: &str = "firefly_propose_duration_seconds";
+pub const PROPOSE_FAILURES: &str = "firefly_propose_failures_total";
+pub const EXPLORE_DEPLOY_DURATION: &str = "firefly_explore_deploy_duration_seconds";
+pub const EXPLORE_DEPLOY_FAILURES: &str = "firefly_explore_deploy_failures_total";
+pub const NODE_EVENTS_RECONNECTS: &str = "firefly_node_events_reconnects_total";
+pub const NODE_EVENTS_LAGGED: &str = "firefly_node_events_lagged_total";
+pub const WALLET_SUBSCRIPTIONS: &str = "firefly_wallet_subscriptions";
+
+/// Histogram buckets, in seconds, suited to the node calls.
+pub const LATENCY_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+/// Installs the Prometheus recorder and serves the metrics over HTTP on their own listener, apart
+/// from any API the binary serves. Must be called from within a tokio runtime.
+#[cfg(feature = "prometheus")]
+pub fn install_prometheus(address: impl Into<SocketAddr>) -> anyhow::Result<()> {
+    metrics_exporter_prometheus::PrometheusBuilder::new()
+        .set_buckets(LATENCY_BUCKETS)?
+        .with_http_listener(address)
+        .install()?;
+    describe();
+    Ok(())
+}
+
+/// Registers the unit and help text of every metric. Call it once the recorder is installed.
+pub fn describe() {
+    describe_histogram!(
+        DEPLOY_DURATION,
+        Unit::Seconds,
+        "Time the validator took to accept or reject a deploy"
+    );
+    describe_counter!(
+        DEPLOY_FAILURES,
+        "Deploys that failed, by reason: grpc or rejected"
+    );
+    describe_histogram!(
+        PROPOSE_DURATION,
+        Unit::Seconds,
+        "Time taken by propose calls, by call: propose, propose_async or propose_result"
+    );
+    describe_counter!(PROPOSE_FAILURES, "Propose calls that failed, by reason");
+    describe_histogram!(
+        EXPLORE_DEPLOY_DURATION,
+        Unit::Seconds,
+        "Time the observer took to run an exploratory deploy"
+    );
+    describe_counter!(
+        EXPLORE_DEPLOY_FAILURES,
+        "Exploratory deploys that failed, by reason"
+    );
+    describe_counter!(
+        NODE_EVENTS_RECONNECTS,
+        "Times the node events websocket was reconnected"
+    );
+    describe_counter!(
+        NODE_EVENTS_LAGGED,
+        "Node events dropped because a subscriber fell behind, by subscriber"
+    );
+    describe_gauge!(
+        WALLET_SUBSCRIPTIONS,
+        "Wallets currently subscribed to their deploy events"
+    );
+}
+
+pub(crate) fn record_deploy(started: Instant, failure: Option<&'static str>) {
+    histogram!(DEPLOY_DURATION).record(started.elapsed());
+    if let Some(reason) = failure {
+        counter!(DEPLOY_FAILURES, "reason" => reason).increment(1);
+    }
+}
+
+pub(crate) fn record_propose(call: &'static str, started: Instant, failure: Option<&'static str>) {
+    histogram!(PROPOSE_DURATION, "call" => call).record(started.elapsed());
+    if let Some(reason) = failure {
+        counter!(PROPOSE_FAILURES, "reason" => reason).increment(1);
+    }
+}
+
+pub(crate) fn record_explore_deploy(started: Instant, failure: Option<&'static str>) {
+    histogram!(EXPLORE_DEPLOY_DURATION).record(started.elapsed());
+    if let Some(reason) = failure {
+        counter!(EXPLORE_DEPLOY_FAILURES, "reason" => reason).increment(1);
+    }
+}
+
+pub(crate) fn record_reconnect() {
+    counter!(NODE_EVENTS_RECONNECTS).increment(1);
+}
+
+pub(crate) fn record_lagged(subscriber: &'static str, skipped: u64) {
+    counter!(NODE_EVENTS_LAGGED, "subscriber" => subscriber).increment(skipped);
+}
+
+pub(crate) fn wallet_subscribed() {
+    gauge!(WALLET_SUBSCRIPTIONS).increment(1);
+}
+
+pub(crate) fn wallet_unsubscribed() {
+    gauge!(WALLET_SUBSCRIPTIONS).decrement(1);
+}
@@ -8,12 +8,16 @@ use crc::Crc;
 use derive_more::{AsRef, Display, From, Into};
 use digest::OutputSizeUser;
 use digest::typenum::Unsigned;
+use prost::Message as _;
 use secp256k1::PublicKey;
 use serde::{Deserialize, Deserializer, Serialize, de};
 use thiserror::Error;
 
 use crate::helpers::ShortHex;
-use crate::rendering::{IntoValue, Value};
+use crate::models::rhoapi::expr::ExprInstance;
+use crate::models::rhoapi::g_unforgeable::UnfInstance;
+use crate::read_node_expr::URI_NEWTYPE;
+use crate::rendering::{IntoValue, ParConversionError, Value};
 
 pub mod servicemodelapi {
     #![allow(warnings)]
@@ -100,6 +104,12 @@ pub struct SignedCode {
     pub deployer: Vec<u8>,
 }
 
+impl SignedCode {
+    pub fn deploy_data(&self) -> Result<casper::DeployDataProto, prost::DecodeError> {
+        casper::DeployDataProto::decode(self.contract.as_slice())
+    }
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub enum ReadNodeExprUnforg {
     UnforgPrivate { data: String },
@@ -159,6 +169,93 @@ impl From<ReadNodeExpr> for serde_json::Value {
     }
 }
 
+impl TryFrom<rhoapi::GUnforgeable> for ReadNodeExprUnforg {
+    type Error = ParConversionError;
+
+    fn try_from(value: rhoapi::GUnforgeable) -> Result<Self, Self::Error> {
+        match value.unf_instance.ok_or(ParConversionError::NotGround)? {
+            UnfInstance::GPrivateBody(body) => Ok(Self::UnforgPrivate {
+                data: hex::encode(body.id),
+            }),
+            UnfInstance::GDeployIdBody(body) => Ok(Self::UnforgDeploy {
+                data: hex::encode(body.sig),
+            }),
+            UnfInstance::GDeployerIdBody(body) => Ok(Self::UnforgDeployer {
+                data: hex::encode(body.public_key),
+            }),
+            UnfInstance::GSysAuthTokenBody(_) => Err(ParConversionError::UnsupportedExpr(
+                "GSysAuthToken".to_owned(),
+            )),
+        }
+    }
+}
+
+fn read_node_exprs(pars: Vec<rhoapi::Par>) -> Result<Vec<ReadNodeExpr>, ParConversionError> {
+    pars.into_iter().map(TryInto::try_into).collect()
+}
+
+/// Mirrors what the observer's REST API returns, so gRPC results decode the same way.
+impl TryFrom<rhoapi::Par> for ReadNodeExpr {
+    type Error = ParConversionError;
+
+    fn try_from(mut par: rhoapi::Par) -> Result<Self, Self::Error> {
+        let exprs = std::mem::take(&mut par.exprs);
+        let unforgeables = std::mem::take(&mut par.unforgeables);
+        let rest = rhoapi::Par {
+            locally_free: Vec::new(),
+            connective_used: false,
+            ..par
+        };
+        if rest != rhoapi::Par::default() || exprs.len() + unforgeables.len() > 1 {
+            return Err(ParConversionError::NotGround);
+        }
+
+        if let Some(unforgeable) = unforgeables.into_iter().next() {
+            return unforgeable.try_into().map(|data| Self::ExprUnforg { data });
+        }
+
+        let Some(expr) = exprs.into_iter().next() else {
+            return Ok(Self::ExprNil {});
+        };
+
+        match expr.expr_instance.ok_or(ParConversionError::NotGround)? {
+            ExprInstance::GBool(data) => Ok(Self::ExprBool { data }),
+            ExprInstance::GInt(data) => Ok(Self::ExprInt { data: data.into() }),
+            ExprInstance::GString(data) => Ok(Self::ExprString { data }),
+            ExprInstance::GUri(data) => Ok(Self::ExprUri { data }),
+            ExprInstance::GByteArray(data) => Ok(Self::ExprBytes {
+                data: hex::encode(data),
+            }),
+            ExprInstance::ETupleBody(tuple) => {
+                read_node_exprs(tuple.ps).map(|data| Self::ExprTuple { data })
+            }
+            ExprInstance::EListBody(list) => {
+                read_node_exprs(list.ps).map(|data| Self::ExprList { data })
+            }
+            ExprInstance::ESetBody(set) => {
+                read_node_exprs(set.ps).map(|data| Self::ExprSet { data })
+            }
+            ExprInstance::EMapBody(map) => map
+                .kvs
+                .into_iter()
+                .map(|pair| {
+                    let key = pair.key.ok_or(ParConversionError::MissingMapEntry("key"))?;
+                    let Self::ExprString { data: key } = key.try_into()? else {
+                        return Err(ParConversionError::NonStringMapKey);
+                    };
+                    let value = pair
+                        .value
+                        .ok_or(ParConversionError::MissingMapEntry("value"))?
+                        .try_into()?;
+                    Ok((key, value))
+                })
+                .collect::<Result<_, _>>()
+                .map(|data| Self::ExprMap { data }),
+            other => Err(ParConversionError::UnsupportedExpr(format!("{other:?}"))),
+        }
+    }
+}
+
 pub enum Either<L, R> {
     Left(L),
     Right(R),
@@ -248,6 +345,12 @@ pub struct DeployData {
 
     #[builder(default = ValidAfter::Head)]
     pub valid_after_block_number: ValidAfter,
+
+    #[builder(default = 1)]
+    pub phlo_price: u64,
+
+    #[builder(default = "root".to_owned(), into)]
+    pub shard_id: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -274,6 +377,153 @@ pub struct BlockEventDeploy {
     pub errored: bool,
 }
 
+#[derive(Debug, Clone)]
+pub struct LightBlock {
+    pub block_hash: BlockId,
+    pub block_number: u64,
+    pub sender: String,
+    pub seq_num: u64,
+    pub timestamp: DateTime<Utc>,
+    pub shard_id: String,
+    pub parents: Vec<BlockId>,
+    pub deploy_count: u32,
+    pub fault_tolerance: f32,
+}
+
+impl From<casper::LightBlockInfo> for LightBlock {
+    fn from(value: casper::LightBlockInfo) -> Self {
+        Self {
+            block_hash: value.block_hash.into(),
+            block_number: value.block_number as _,
+            sender: value.sender,
+            seq_num: value.seq_num as _,
+            timestamp: DateTime::from_timestamp_millis(value.timestamp).unwrap_or_default(),
+            shard_id: value.shard_id,
+            parents: value
+                .parents_hash_list
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+            deploy_count: value.deploy_count as _,
+            fault_tolerance: value.fault_tolerance,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BlockDeploy {
+    pub id: DeployId,
+    pub deployer: PublicKey,
+    pub term: String,
+    pub timestamp: DateTime<Utc>,
+    pub phlo_price: u64,
+    pub phlo_limit: u64,
+    pub valid_after_block_number: u64,
+    pub cost: u64,
+    pub errored: bool,
+    pub system_deploy_error: Option<String>,
+}
+
+impl TryFrom<casper::DeployInfo> for BlockDeploy {
+    type Error = ParseBlockError;
+
+    fn try_from(value: casper::DeployInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: value.sig.into(),
+            deployer: value.deployer.parse()?,
+            term: value.term,
+            timestamp: DateTime::from_timestamp_millis(value.timestamp).unwrap_or_default(),
+            phlo_price: value.phlo_price as _,
+            phlo_limit: value.phlo_limit as _,
+            valid_after_block_number: value.valid_after_block_number as _,
+            cost: value.cost,
+            errored: value.errored,
+            system_deploy_error: Some(value.system_deploy_error).filter(|err| !err.is_empty()),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Block {
+    pub info: LightBlock,
+    pub deploys: Vec<BlockDeploy>,
+}
+
+#[derive(Debug, Clone, Error)]
+pub enum ParseBlockError {
+    #[error("missing block info")]
+    MissingBlockInfo,
+
+    #[error("invalid deployer: {0}")]
+    InvalidDeployer(#[from] secp256k1::Error),
+}
+
+impl TryFrom<casper::BlockInfo> for Block {
+    type Error = ParseBlockError;
+
+    fn try_from(value: casper::BlockInfo) -> Result<Self, Self::Error> {
+        Ok(Self {
+            info: value
+                .block_info
+                .ok_or(ParseBlockError::MissingBlockInfo)?
+                .into(),
+            deploys: value
+                .deploys
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct NodeStatus {
+    pub api_version: String,
+    pub node_version: String,
+    pub address: String,
+    pub network_id: String,
+    pub shard_id: String,
+    pub peers: u32,
+    pub nodes: u32,
+    pub min_phlo_price: u64,
+}
+
+impl From<casper::Status> for NodeStatus {
+    fn from(value: casper::Status) -> Self {
+        let version = value.version.unwrap_or_default();
+        Self {
+            api_version: version.api,
+            node_version: version.node,
+            address: value.address,
+            network_id: value.network_id,
+            shard_id: value.shard_id,
+            peers: value.peers as _,
+            nodes: value.nodes as _,
+            min_phlo_price: value.min_phlo_price as _,
+        }
+    }
+}
+
+impl From<Block> for BlockEventPayload {
+    fn from(value: Block) -> Self {
+        Self {
+            block_hash: value.info.block_hash,
+            deploys: value.deploys.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+impl From<BlockDeploy> for BlockEventDeploy {
+    fn from(value: BlockDeploy) -> Self {
+        Self {
+            id: value.id,
+            cost: value.cost,
+            deployer: value.deployer,
+            errored: value.errored,
+        }
+    }
+}
+
 pub const FIRECAP_ID: [u8; 3] = [0, 0, 0];
 pub const FIRECAP_VERSION: u8 = 0;
 
@@ -427,3 +677,53 @@ impl IntoValue for Uri {
         Value::Uri(self.0)
     }
 }
+
+impl<'de> Deserialize<'de> for Uri {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_newtype_struct(URI_NEWTYPE, UriVisitor)
+    }
+}
+
+struct UriVisitor;
+
+impl<'de> de::Visitor<'de> for UriVisitor {
+    type Value = Uri;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("a rho:id uri")
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = String::deserialize(deserializer)?;
+        self.visit_string(value)
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        self.visit_string(v.to_owned())
+    }
+
+    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+    where
+        E: de::Error,
+    {
+        Uri::try_from(v).map_err(de::Error::custom)
+    }
+}
+
+/// Unforgeable name returned by the node, deserializable from `ExprUnforg` through
+/// [`crate::read_node_expr`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum UnforgeableName {
+    Private(String),
+    Deploy(String),
+    Deployer(String),
+}
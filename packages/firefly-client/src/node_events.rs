@@ -1,55 +1,207 @@
-use std::sync::Arc;
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
 use std::task::ready;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use backon::{ExponentialBuilder, Retryable};
 use dashmap::DashMap;
-use futures::{Stream, StreamExt};
-use tokio::sync::{Notify, broadcast};
+use futures::future::BoxFuture;
+use futures::stream::BoxStream;
+use futures::{FutureExt, Stream, StreamExt, future, stream};
+use secp256k1::PublicKey;
+use tokio::sync::{Notify, broadcast, mpsc};
 use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_tungstenite::Connector;
 use tokio_tungstenite::tungstenite::Message;
 use tracing::Instrument;
 use uuid::Uuid;
 
-use crate::models::{BlockEventDeploy, DeployId, NodeEvent, WalletAddress};
+use crate::connection::ConnectionOptions;
+use crate::models::{BlockEventPayload, BlockId, DeployId, NodeEvent, WalletAddress};
+use crate::{WriteNodeClient, metrics};
 
 #[derive(Debug, Clone)]
 pub enum DeployEvent {
+    Added {
+        id: DeployId,
+        block: BlockId,
+        cost: u64,
+        errored: bool,
+    },
     Finalized {
         id: DeployId,
+        block: BlockId,
         cost: u64,
         errored: bool,
     },
 }
 
+impl DeployEvent {
+    pub const fn id(&self) -> &DeployId {
+        match self {
+            Self::Added { id, .. } | Self::Finalized { id, .. } => id,
+        }
+    }
+}
+
+/// State of the websocket an [`EventSource`] receives events from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventsStatus {
+    pub connected: bool,
+    /// Time since the last event arrived, `None` until the first one does.
+    pub since_last_event: Option<Duration>,
+}
+
+/// Source of node events.
+pub trait EventSource: Send + Sync {
+    /// Stream of every event received from the node.
+    fn subscribe(&self) -> BoxStream<'static, NodeEvent>;
+
+    /// Stream of inclusion and finalization events for deploys by `wallet_address`.
+    fn subscribe_for_deploys(
+        &self,
+        wallet_address: WalletAddress,
+    ) -> BoxStream<'static, DeployEvent>;
+
+    /// Resolves to `true` once `deploy_id` is finalized, or to `false` after `max_wait`.
+    fn wait_for_deploy<'a>(
+        &'a self,
+        deploy_id: &'a DeployId,
+        max_wait: Duration,
+    ) -> BoxFuture<'a, bool>;
+
+    /// Whether the websocket is connected and when the last event arrived.
+    fn status(&self) -> EventsStatus;
+}
+
+#[derive(Default)]
+struct SocketState {
+    connected: AtomicBool,
+    last_event: Mutex<Option<Instant>>,
+}
+
+impl SocketState {
+    fn set_connected(&self, connected: bool) {
+        self.connected.store(connected, Ordering::Relaxed);
+    }
+
+    fn event_received(&self) {
+        *self
+            .last_event
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner) = Some(Instant::now());
+    }
+
+    fn status(&self) -> EventsStatus {
+        EventsStatus {
+            connected: self.connected.load(Ordering::Relaxed),
+            since_last_event: self
+                .last_event
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .map(|last_event| last_event.elapsed()),
+        }
+    }
+}
+
 type DeploySubscriptions = Arc<DashMap<DeployId, DashMap<Uuid, Arc<Notify>>>>;
 type WalletSubscriptions = Arc<DashMap<WalletAddress, broadcast::Sender<DeployEvent>>>;
 
 #[derive(Clone)]
 pub struct NodeEvents {
+    events: broadcast::Sender<NodeEvent>,
     deploy_subscriptions: DeploySubscriptions,
     wallet_subscriptions: WalletSubscriptions,
+    socket: Arc<SocketState>,
 }
 
 impl NodeEvents {
     pub fn new(url: &str) -> Self {
+        Self::start(url, ConnectionOptions::default(), None, None)
+    }
+
+    /// Same as [`Self::new`], but after a websocket reconnect replays every block finalized
+    /// while disconnected, using `write_client` to read the missed blocks.
+    pub fn with_backfill(url: &str, write_client: WriteNodeClient) -> Self {
+        Self::start(url, ConnectionOptions::default(), None, Some(write_client))
+    }
+
+    /// Same as [`Self::new`] or [`Self::with_backfill`], but connects with TLS, client
+    /// certificates or extra headers.
+    pub fn with_options(
+        url: &str,
+        options: ConnectionOptions,
+        write_client: Option<WriteNodeClient>,
+    ) -> anyhow::Result<Self> {
+        options.headers()?;
+        let connector = options.websocket_connector()?;
+        Ok(Self::start(url, options, connector, write_client))
+    }
+
+    fn start(
+        url: &str,
+        options: ConnectionOptions,
+        connector: Option<Connector>,
+        write_client: Option<WriteNodeClient>,
+    ) -> Self {
         let url = format!("{url}/ws/events");
-        let tx = broadcast::Sender::<NodeEvent>::new(32);
+        let connect_timeout = options
+            .connect_timeout_secs
+            .map_or(Duration::MAX, Duration::from_secs);
+        let events = broadcast::Sender::<NodeEvent>::new(32);
+        // The dispatcher is fed over a bounded channel, so that a long backfill waits for it
+        // instead of overwriting events it has not seen yet.
+        let (tx, mut rx) = mpsc::channel::<NodeEvent>(32);
         let deploy_subscriptions = DeploySubscriptions::default();
         let wallet_subscriptions = WalletSubscriptions::default();
+        let socket = Arc::<SocketState>::default();
 
         tokio::spawn({
-            let tx = tx.clone();
+            let socket = socket.clone();
             async move {
+                let mut last_finalized = None;
+                let mut reconnect = false;
+
                 loop {
-                    let Ok((mut stream, _)) =
-                        (|| async { tokio_tungstenite::connect_async(&url).await })
-                            .retry(ExponentialBuilder::default().without_max_times())
-                            .await
+                    let Ok((mut stream, _)) = (|| async {
+                        let request = options.websocket_request(&url)?;
+                        let connect = tokio_tungstenite::connect_async_tls_with_config(
+                            request,
+                            None,
+                            false,
+                            connector.clone(),
+                        );
+                        let connected = tokio::time::timeout(connect_timeout, connect).await??;
+                        anyhow::Ok(connected)
+                    })
+                    .retry(ExponentialBuilder::default().without_max_times())
+                    .await
                     else {
                         return;
                     };
 
+                    if reconnect {
+                        metrics::record_reconnect();
+                    }
+                    reconnect = true;
+                    socket.set_connected(true);
+
+                    let mut replayed = HashSet::new();
+                    if let (Some(write_client), Some(block_hash)) = (&write_client, &last_finalized)
+                    {
+                        match backfill(write_client.clone(), block_hash, &tx).await {
+                            Ok(blocks) => {
+                                if let Some(block_hash) = blocks.last() {
+                                    last_finalized = Some(block_hash.clone());
+                                }
+                                replayed.extend(blocks);
+                            }
+                            Err(err) => tracing::warn!("failed to backfill node events: {err:?}"),
+                        }
+                    }
+
                     while let Some(msg) = stream.next().await {
                         let buff = match msg {
                             Ok(Message::Text(buff)) => buff,
@@ -70,54 +222,68 @@ impl NodeEvents {
                                 continue;
                             }
                         };
+                        socket.event_received();
 
-                        let _ = tx.send(event);
+                        if let NodeEvent::BlockFinalised { payload } = &event {
+                            if replayed.remove(&payload.block_hash) {
+                                continue;
+                            }
+                            last_finalized = Some(payload.block_hash.clone());
+                        }
+
+                        if tx.send(event).await.is_err() {
+                            return;
+                        }
                     }
+
+                    socket.set_connected(false);
                 }
             }
             .in_current_span()
         });
 
         tokio::spawn({
-            let mut rx = tx.subscribe();
+            let events = events.clone();
             let deploy_subscriptions = deploy_subscriptions.clone();
             let wallet_subscriptions = wallet_subscriptions.clone();
             async move {
-                loop {
-                    let deploys = match rx.recv().await {
-                        Ok(NodeEvent::Started) => continue,
-                        Ok(NodeEvent::BlockAdded { .. }) => continue,
-                        Ok(NodeEvent::BlockCreated { .. }) => continue,
-                        Ok(NodeEvent::BlockFinalised { payload }) => payload.deploys,
-                        Err(broadcast::error::RecvError::Closed) => return,
-                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
-                    };
+                while let Some(event) = rx.recv().await {
+                    if let NodeEvent::BlockFinalised { payload } = &event {
+                        for deploy in &payload.deploys {
+                            deploy_subscriptions
+                                .remove(&deploy.id)
+                                .map(|(_, waiters)| waiters)
+                                .into_iter()
+                                .flatten()
+                                .for_each(|(_, w)| w.notify_waiters());
+                        }
+                    }
 
-                    for deploy in deploys {
-                        deploy_subscriptions
-                            .remove(&deploy.id)
-                            .map(|(_, waiters)| waiters)
-                            .into_iter()
-                            .flatten()
-                            .for_each(|(_, w)| w.notify_waiters());
-
-                        if let Some(subscription) =
-                            wallet_subscriptions.get(&deploy.deployer.into())
-                        {
-                            let _ = subscription.send(deploy.into());
+                    for (deployer, deploy_event) in deploy_events(event.clone()) {
+                        if let Some(subscription) = wallet_subscriptions.get(&deployer.into()) {
+                            let _ = subscription.send(deploy_event);
                         }
                     }
+
+                    let _ = events.send(event);
                 }
             }
             .in_current_span()
         });
 
         Self {
+            events,
             deploy_subscriptions,
             wallet_subscriptions,
+            socket,
         }
     }
 
+    /// Whether the websocket is connected and when the last event arrived.
+    pub fn status(&self) -> EventsStatus {
+        self.socket.status()
+    }
+
     pub fn wait_for_deploy(
         &self,
         deploy_id: &DeployId,
@@ -154,12 +320,81 @@ impl NodeEvents {
         }
     }
 
+    /// Stream of every event received from the node.
+    pub fn subscribe(&self) -> impl Stream<Item = NodeEvent> + Send + use<> {
+        BroadcastStream::new(self.events.subscribe()).filter_map(|event| {
+            future::ready(
+                event
+                    .inspect_err(|BroadcastStreamRecvError::Lagged(skipped)| {
+                        tracing::warn!("node events subscriber lagged by {skipped} events");
+                        metrics::record_lagged("subscriber", *skipped);
+                    })
+                    .ok(),
+            )
+        })
+    }
+
+    pub fn subscribe_added(&self) -> impl Stream<Item = BlockEventPayload> + Send + use<> {
+        self.subscribe().filter_map(|event| {
+            future::ready(match event {
+                NodeEvent::BlockAdded { payload } => Some(payload),
+                _ => None,
+            })
+        })
+    }
+
+    pub fn subscribe_created(&self) -> impl Stream<Item = BlockEventPayload> + Send + use<> {
+        self.subscribe().filter_map(|event| {
+            future::ready(match event {
+                NodeEvent::BlockCreated { payload } => Some(payload),
+                _ => None,
+            })
+        })
+    }
+
+    pub fn subscribe_finalised(&self) -> impl Stream<Item = BlockEventPayload> + Send + use<> {
+        self.subscribe().filter_map(|event| {
+            future::ready(match event {
+                NodeEvent::BlockFinalised { payload } => Some(payload),
+                _ => None,
+            })
+        })
+    }
+
+    /// Stream of inclusion and finalization events for the given deploys.
+    pub fn subscribe_for_deploy_ids(
+        &self,
+        deploy_ids: HashSet<DeployId>,
+    ) -> impl Stream<Item = DeployEvent> + Send + use<> {
+        self.subscribe()
+            .flat_map(|event| stream::iter(deploy_events(event)))
+            .filter_map(move |(_, deploy_event)| {
+                future::ready(
+                    deploy_ids
+                        .contains(deploy_event.id())
+                        .then_some(deploy_event),
+                )
+            })
+    }
+
+    pub fn subscribe_for_wallets(
+        &self,
+        wallet_addresses: HashSet<WalletAddress>,
+    ) -> impl Stream<Item = DeployEvent> + Send + use<> {
+        stream::select_all(
+            wallet_addresses
+                .into_iter()
+                .map(|wallet_address| self.subscribe_for_deploys(wallet_address)),
+        )
+    }
+
     pub fn subscribe_for_deploys(&self, wallet_address: WalletAddress) -> WalletSubscription {
         let tx = self
             .wallet_subscriptions
             .entry(wallet_address.clone())
             .or_insert_with(|| broadcast::Sender::new(32));
 
+        metrics::wallet_subscribed();
         WalletSubscription {
             wallet_address,
             wallet_subscriptions: self.wallet_subscriptions.clone(),
@@ -168,13 +403,104 @@ impl NodeEvents {
     }
 }
 
-impl From<BlockEventDeploy> for DeployEvent {
-    fn from(value: BlockEventDeploy) -> Self {
-        Self::Finalized {
-            id: value.id,
-            cost: value.cost,
-            errored: value.errored,
+impl EventSource for NodeEvents {
+    fn subscribe(&self) -> BoxStream<'static, NodeEvent> {
+        self.subscribe().boxed()
+    }
+
+    fn subscribe_for_deploys(
+        &self,
+        wallet_address: WalletAddress,
+    ) -> BoxStream<'static, DeployEvent> {
+        self.subscribe_for_deploys(wallet_address).boxed()
+    }
+
+    fn wait_for_deploy<'a>(
+        &'a self,
+        deploy_id: &'a DeployId,
+        max_wait: Duration,
+    ) -> BoxFuture<'a, bool> {
+        self.wait_for_deploy(deploy_id, max_wait).boxed()
+    }
+
+    fn status(&self) -> EventsStatus {
+        self.status()
+    }
+}
+
+/// Emits synthetic `BlockFinalised` events for blocks finalized after `last_finalized`,
+/// in block number order, and returns their hashes. Waits for the dispatcher whenever it falls
+/// behind, so that no replayed block is dropped however long the gap was.
+async fn backfill(
+    mut write_client: WriteNodeClient,
+    last_finalized: &BlockId,
+    tx: &mpsc::Sender<NodeEvent>,
+) -> anyhow::Result<Vec<BlockId>> {
+    let from = write_client
+        .get_block(last_finalized)
+        .await?
+        .info
+        .block_number;
+    let to = write_client.last_finalized_block().await?.info.block_number;
+    if to <= from {
+        return Ok(Vec::new());
+    }
+
+    let mut blocks = write_client.get_blocks_by_heights(from + 1, to).await?;
+    blocks.sort_by_key(|block| block.block_number);
+
+    let mut replayed = Vec::with_capacity(blocks.len());
+    for block in blocks {
+        if !write_client.is_finalized(&block.block_hash).await? {
+            continue;
         }
+
+        let block = write_client.get_block(&block.block_hash).await?;
+        tracing::debug!("replaying finalized block {}", block.info.block_hash);
+
+        replayed.push(block.info.block_hash.clone());
+        tx.send(NodeEvent::BlockFinalised {
+            payload: block.into(),
+        })
+        .await?;
+    }
+
+    Ok(replayed)
+}
+
+fn deploy_events(event: NodeEvent) -> Vec<(PublicKey, DeployEvent)> {
+    match event {
+        NodeEvent::BlockAdded { payload } => payload
+            .deploys
+            .into_iter()
+            .map(|deploy| {
+                (
+                    deploy.deployer,
+                    DeployEvent::Added {
+                        id: deploy.id,
+                        block: payload.block_hash.clone(),
+                        cost: deploy.cost,
+                        errored: deploy.errored,
+                    },
+                )
+            })
+            .collect(),
+        NodeEvent::BlockFinalised { payload } => payload
+            .deploys
+            .into_iter()
+            .map(|deploy| {
+                (
+                    deploy.deployer,
+                    DeployEvent::Finalized {
+                        id: deploy.id,
+                        block: payload.block_hash.clone(),
+                        cost: deploy.cost,
+                        errored: deploy.errored,
+                    },
+                )
+            })
+            .collect(),
+        NodeEvent::Started | NodeEvent::BlockCreated { .. } => Vec::new(),
     }
 }
 
@@ -191,15 +517,22 @@ impl Stream for WalletSubscription {
         mut self: std::pin::Pin<&mut Self>,
         cx: &mut std::task::Context<'_>,
     ) -> std::task::Poll<Option<Self::Item>> {
-        ready!(self.rx.poll_next_unpin(cx))
-            .transpose()
-            .map(std::task::Poll::Ready)
-            .unwrap_or(std::task::Poll::Pending)
+        loop {
+            match ready!(self.rx.poll_next_unpin(cx)) {
+                Some(Ok(event)) => return std::task::Poll::Ready(Some(event)),
+                Some(Err(BroadcastStreamRecvError::Lagged(skipped))) => {
+                    tracing::warn!("wallet subscriber lagged by {skipped} events");
+                    metrics::record_lagged("wallet", skipped);
+                }
+                None => return std::task::Poll::Ready(None),
+            }
+        }
     }
 }
 
 impl Drop for WalletSubscription {
     fn drop(&mut self) {
+        metrics::wallet_unsubscribed();
         self.wallet_subscriptions
             .remove_if(&self.wallet_address, |_, sender| {
                 sender.receiver_count() == 0
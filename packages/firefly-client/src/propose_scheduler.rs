@@ -0,0 +1,247 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::Instant;
+use tracing::Instrument;
+
+use crate::WriteNodeClient;
+use crate::errors::{ProposeError, ProposeSchedulerError};
+use crate::models::{BlockId, DeployId};
+
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(500);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(5);
+const DEFAULT_RETRY_INTERVAL: Duration = Duration::from_secs(2);
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+
+enum Request {
+    Deploy {
+        deploy_id: DeployId,
+        respond: oneshot::Sender<Result<BlockId, ProposeSchedulerError>>,
+    },
+    Propose,
+}
+
+struct Waiter {
+    deploy_id: DeployId,
+    respond: oneshot::Sender<Result<BlockId, ProposeSchedulerError>>,
+    attempts: u32,
+}
+
+/// Gets deploys into blocks.
+#[async_trait]
+pub trait Proposer: Send + Sync {
+    /// Waits for the block that included the already deployed `deploy_id`.
+    async fn enqueue(&self, deploy_id: DeployId) -> anyhow::Result<BlockId>;
+
+    /// Asks for a proposal without waiting for it.
+    fn request_propose(&self);
+}
+
+/// Shared proposer for a validator.
+///
+/// Deploys are enqueued and proposals are debounced, so that one block picks up every deploy made
+/// in the meantime, and each caller gets back the block that included its deploy.
+#[derive(Clone)]
+pub struct ProposeScheduler {
+    requests: mpsc::UnboundedSender<Request>,
+}
+
+#[bon::bon]
+impl ProposeScheduler {
+    #[builder]
+    pub fn new(
+        write_client: WriteNodeClient,
+
+        /// How long to wait for more deploys after the last one before proposing.
+        #[builder(default = DEFAULT_DEBOUNCE)]
+        debounce: Duration,
+
+        /// Upper bound on how long a deploy waits for its batch under constant load.
+        #[builder(default = DEFAULT_MAX_DELAY)]
+        max_delay: Duration,
+
+        /// Pause between proposals while the validator can't propose yet or a deploy is still
+        /// pending.
+        #[builder(default = DEFAULT_RETRY_INTERVAL)]
+        retry_interval: Duration,
+
+        /// Number of proposals after which a deploy that is still not in a block is given up on.
+        #[builder(default = DEFAULT_MAX_ATTEMPTS)]
+        max_attempts: u32,
+    ) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let worker = Worker {
+            write_client,
+            debounce,
+            max_delay,
+            retry_interval,
+            max_attempts,
+            pending: Vec::new(),
+            propose_requested: false,
+        };
+        tokio::spawn(worker.run(rx).in_current_span());
+
+        Self { requests: tx }
+    }
+
+    /// Enqueues an already deployed `deploy_id` for the next proposal and waits for the block that
+    /// included it.
+    pub async fn enqueue(&self, deploy_id: DeployId) -> anyhow::Result<BlockId> {
+        let (tx, rx) = oneshot::channel();
+        self.requests
+            .send(Request::Deploy {
+                deploy_id,
+                respond: tx,
+            })
+            .map_err(|_| ProposeSchedulerError::Stopped)?;
+
+        rx.await
+            .map_err(|_| ProposeSchedulerError::Stopped)?
+            .map_err(Into::into)
+    }
+
+    /// Asks for a proposal without waiting for it, e.g. after deploys that nobody tracks.
+    pub fn request_propose(&self) {
+        let _ = self.requests.send(Request::Propose);
+    }
+}
+
+#[async_trait]
+impl Proposer for ProposeScheduler {
+    async fn enqueue(&self, deploy_id: DeployId) -> anyhow::Result<BlockId> {
+        self.enqueue(deploy_id).await
+    }
+
+    fn request_propose(&self) {
+        self.request_propose();
+    }
+}
+
+struct Worker {
+    write_client: WriteNodeClient,
+    debounce: Duration,
+    max_delay: Duration,
+    retry_interval: Duration,
+    max_attempts: u32,
+    pending: Vec<Waiter>,
+    propose_requested: bool,
+}
+
+impl Worker {
+    async fn run(mut self, mut requests: mpsc::UnboundedReceiver<Request>) {
+        let mut closed = false;
+
+        loop {
+            if self.pending.is_empty() && !self.propose_requested {
+                if closed {
+                    return;
+                }
+                match requests.recv().await {
+                    Some(request) => self.push(request),
+                    None => return,
+                }
+            }
+
+            if !closed {
+                closed = self.collect(&mut requests).await;
+            }
+
+            let propose_error = self.propose().await.err();
+            self.propose_requested = false;
+            self.resolve(propose_error).await;
+
+            if !self.pending.is_empty() {
+                tokio::time::sleep(self.retry_interval).await;
+            }
+        }
+    }
+
+    fn push(&mut self, request: Request) {
+        match request {
+            Request::Deploy { deploy_id, respond } => self.pending.push(Waiter {
+                deploy_id,
+                respond,
+                attempts: 0,
+            }),
+            Request::Propose => self.propose_requested = true,
+        }
+    }
+
+    /// Collects requests until none arrived for `debounce` or `max_delay` has passed. Returns
+    /// whether all [`ProposeScheduler`] handles were dropped.
+    async fn collect(&mut self, requests: &mut mpsc::UnboundedReceiver<Request>) -> bool {
+        let deadline = Instant::now() + self.max_delay;
+
+        loop {
+            let wait_until = (Instant::now() + self.debounce).min(deadline);
+            match tokio::time::timeout_at(wait_until, requests.recv()).await {
+                Ok(Some(request)) => self.push(request),
+                Ok(None) => return true,
+                Err(_) => return false,
+            }
+        }
+    }
+
+    async fn propose(&mut self) -> Result<BlockId, ProposeError> {
+        let mut attempts = 0;
+
+        loop {
+            match self.write_client.propose_async().await {
+                Ok(()) => break,
+                Err(err) if err.is_retryable() && attempts < self.max_attempts => {
+                    attempts += 1;
+                    tracing::debug!("{err}, retrying propose in {:?}", self.retry_interval);
+                    tokio::time::sleep(self.retry_interval).await;
+                }
+                Err(err @ ProposeError::NoNewDeploys) => {
+                    tracing::debug!("nothing to propose, deploys may be in another block");
+                    return Err(err);
+                }
+                Err(err) => {
+                    tracing::warn!("failed to propose: {err:?}");
+                    return Err(err);
+                }
+            }
+        }
+
+        let block = self.write_client.propose_result().await;
+        match &block {
+            Ok(block) => tracing::info!(%block, deploys = self.pending.len(), "proposed block"),
+            Err(err) => tracing::warn!("failed to get propose result: {err:?}"),
+        }
+        block
+    }
+
+    /// Answers every waiter whose deploy made it into a block, whether through our proposal or
+    /// somebody else's, and keeps the rest for the next round.
+    async fn resolve(&mut self, propose_error: Option<ProposeError>) {
+        let last_error = propose_error.map(|err| err.to_string());
+
+        for mut waiter in std::mem::take(&mut self.pending) {
+            if waiter.respond.is_closed() {
+                continue;
+            }
+
+            match self.write_client.find_deploy(&waiter.deploy_id).await {
+                Ok(Some(block)) => {
+                    let _ = waiter.respond.send(Ok(block.block_hash));
+                    continue;
+                }
+                Ok(None) => {}
+                Err(err) => tracing::warn!("failed to find deploy {}: {err:?}", waiter.deploy_id),
+            }
+
+            waiter.attempts += 1;
+            if waiter.attempts < self.max_attempts {
+                self.pending.push(waiter);
+            } else {
+                let _ = waiter.respond.send(Err(ProposeSchedulerError::NotIncluded {
+                    attempts: waiter.attempts,
+                    last_error: last_error.clone(),
+                }));
+            }
+        }
+    }
+}
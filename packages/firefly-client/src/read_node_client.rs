@@ -1,21 +1,64 @@
+use std::sync::Arc;
+use std::time::Instant;
+
 use anyhow::Context;
-use serde_json::Value;
-use tracing::info;
+use async_trait::async_trait;
 
+use crate::connection::ConnectionOptions;
 use crate::errors::ReadNodeError;
-use crate::models::ReadNodeExpr;
+use crate::models::{BlockId, ReadNodeExpr};
+use crate::{metrics, read_node_expr};
+
+mod grpc;
+mod http;
+
+pub use grpc::GrpcTransport;
+pub use http::HttpTransport;
+
+/// Runs exploratory deploys on an observer node.
+#[async_trait]
+pub trait ExploreTransport: Send + Sync {
+    /// Evaluates `rholang_code` on top of the post-state of `block_hash`, or of the latest block
+    /// when `None`, and returns the first value sent to the return channel.
+    async fn explore_deploy(
+        &self,
+        rholang_code: String,
+        block_hash: Option<&BlockId>,
+    ) -> Result<ReadNodeExpr, ReadNodeError>;
+
+    /// Checks that the observer answers its `status` endpoint.
+    async fn status(&self) -> Result<(), ReadNodeError>;
+}
 
 #[derive(Clone)]
 pub struct ReadNodeClient {
-    url: String,
-    client: reqwest::Client,
+    transport: Arc<dyn ExploreTransport>,
 }
 
 impl ReadNodeClient {
+    /// Client talking to the observer's REST API.
     pub fn new(url: String) -> Self {
+        Self::with_transport(HttpTransport::new(url))
+    }
+
+    /// Client talking to the observer's REST API with TLS, client certificates or extra headers.
+    pub fn with_options(url: String, options: &ConnectionOptions) -> anyhow::Result<Self> {
+        HttpTransport::with_options(url, options).map(Self::with_transport)
+    }
+
+    /// Client talking to the observer's gRPC deploy service.
+    pub async fn grpc(url: String, options: &ConnectionOptions) -> anyhow::Result<Self> {
+        GrpcTransport::connect(url, options)
+            .await
+            .map(Self::with_transport)
+    }
+
+    pub fn with_transport<T>(transport: T) -> Self
+    where
+        T: ExploreTransport + 'static,
+    {
         Self {
-            url,
-            client: Default::default(),
+            transport: Arc::new(transport),
         }
     }
 
@@ -23,39 +66,46 @@ impl ReadNodeClient {
     where
         T: serde::de::DeserializeOwned,
     {
-        let mut response_json = self.explore_deploy(rholang_code).await?;
-
-        info!(response_json = %response_json, "explore_deploy response");
-
-        let data_value = response_json
-            .pointer_mut("/expr/0")
-            .map(Value::take)
-            .ok_or(ReadNodeError::ReturnValueMissing)?;
+        self.get_data_at(rholang_code, None).await
+    }
 
-        let intermediate: ReadNodeExpr = serde_json::from_value(data_value)
-            .context("failed to deserialize intermediate model")
-            .map_err(ReadNodeError::Deserialization)?;
+    /// Same as [`Self::get_data`], but reads the state as of `block_hash` when given, so that
+    /// several reads see a consistent snapshot.
+    pub async fn get_data_at<T>(
+        &self,
+        rholang_code: String,
+        block_hash: Option<&BlockId>,
+    ) -> Result<T, ReadNodeError>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let expr = self
+            .explore_deploy(rholang_code, block_hash)
+            .await?;
 
-        serde_json::from_value(intermediate.into())
+        read_node_expr::from_expr(expr)
             .context("failed to deserialize filed model")
             .map_err(ReadNodeError::Deserialization)
     }
 
-    async fn explore_deploy(&self, rholang_code: String) -> Result<Value, ReadNodeError> {
-        let request = self
-            .client
-            .post(format!("{}/api/explore-deploy", self.url))
-            .body(rholang_code)
-            .header("Content-Type", "text/plain")
-            .send()
-            .await?;
-
-        if !request.status().is_success() {
-            let status = request.status();
-            let body = request.text().await?;
-            return Err(ReadNodeError::Api(status, body));
-        }
+    /// Checks that the observer answers its `status` endpoint.
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client"))]
+    pub async fn status(&self) -> Result<(), ReadNodeError> {
+        self.transport.status().await
+    }
 
-        request.json().await.map_err(Into::into)
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client"))]
+    async fn explore_deploy(
+        &self,
+        rholang_code: String,
+        block_hash: Option<&BlockId>,
+    ) -> Result<ReadNodeExpr, ReadNodeError> {
+        let started = Instant::now();
+        let result = self
+            .transport
+            .explore_deploy(rholang_code, block_hash)
+            .await;
+        metrics::record_explore_deploy(started, result.as_ref().err().map(ReadNodeError::reason));
+        result
     }
 }
@@ -0,0 +1,88 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use tracing::info;
+
+use crate::connection::{ConnectionOptions, GrpcChannel};
+use crate::errors::ReadNodeError;
+use crate::models::casper::ExploratoryDeployQuery;
+use crate::models::casper::v1::deploy_service_client::DeployServiceClient;
+use crate::models::casper::v1::{exploratory_deploy_response, status_response};
+use crate::models::{BlockId, ReadNodeExpr};
+use crate::read_node_client::ExploreTransport;
+
+/// Exploratory deploys through the observer's gRPC deploy service.
+#[derive(Clone)]
+pub struct GrpcTransport {
+    client: DeployServiceClient<GrpcChannel>,
+}
+
+impl GrpcTransport {
+    pub async fn connect(url: String, options: &ConnectionOptions) -> anyhow::Result<Self> {
+        let client = options
+            .connect_grpc(url)
+            .await
+            .map(DeployServiceClient::new)
+            .context("failed to connect to observer deploy service")?;
+
+        Ok(Self { client })
+    }
+}
+
+#[async_trait]
+impl ExploreTransport for GrpcTransport {
+    async fn explore_deploy(
+        &self,
+        rholang_code: String,
+        block_hash: Option<&BlockId>,
+    ) -> Result<ReadNodeExpr, ReadNodeError> {
+        let resp = self
+            .client
+            .clone()
+            .exploratory_deploy(ExploratoryDeployQuery {
+                term: rholang_code,
+                block_hash: block_hash.map(ToString::to_string).unwrap_or_default(),
+                use_pre_state_hash: false,
+            })
+            .await?
+            .into_inner()
+            .message
+            .ok_or_else(|| ReadNodeError::Service("missing exploratory_deploy responce".into()))?;
+
+        let data = match resp {
+            exploratory_deploy_response::Message::Result(data) => data,
+            exploratory_deploy_response::Message::Error(err) => {
+                return Err(ReadNodeError::Service(err.messages.join("; ")));
+            }
+        };
+
+        info!(block = ?data.block.map(|block| block.block_hash), "explore_deploy response");
+
+        let par = data
+            .post_block_data
+            .into_iter()
+            .next()
+            .ok_or(ReadNodeError::ReturnValueMissing)?;
+
+        ReadNodeExpr::try_from(par)
+            .context("failed to convert par into intermediate model")
+            .map_err(ReadNodeError::Deserialization)
+    }
+
+    async fn status(&self) -> Result<(), ReadNodeError> {
+        let resp = self
+            .client
+            .clone()
+            .status(())
+            .await?
+            .into_inner()
+            .message
+            .ok_or_else(|| ReadNodeError::Service("missing status responce".into()))?;
+
+        match resp {
+            status_response::Message::Status(_) => Ok(()),
+            status_response::Message::Error(err) => {
+                Err(ReadNodeError::Service(err.messages.join("; ")))
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use serde_json::{Value, json};
+use tracing::info;
+
+use crate::connection::ConnectionOptions;
+use crate::errors::ReadNodeError;
+use crate::models::{BlockId, ReadNodeExpr};
+use crate::read_node_client::ExploreTransport;
+
+/// Exploratory deploys through the observer's REST API.
+#[derive(Clone)]
+pub struct HttpTransport {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HttpTransport {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            client: Default::default(),
+        }
+    }
+
+    pub fn with_options(url: String, options: &ConnectionOptions) -> anyhow::Result<Self> {
+        Ok(Self {
+            url,
+            client: options.http_client()?,
+        })
+    }
+}
+
+#[async_trait]
+impl ExploreTransport for HttpTransport {
+    async fn explore_deploy(
+        &self,
+        rholang_code: String,
+        block_hash: Option<&BlockId>,
+    ) -> Result<ReadNodeExpr, ReadNodeError> {
+        let request = match block_hash {
+            Some(block_hash) => self
+                .client
+                .post(format!("{}/api/explore-deploy-by-block-hash", self.url))
+                .json(&json!({
+                    "term": rholang_code,
+                    "blockHash": block_hash,
+                    "usePreStateHash": false,
+                })),
+            None => self
+                .client
+                .post(format!("{}/api/explore-deploy", self.url))
+                .body(rholang_code)
+                .header("Content-Type", "text/plain"),
+        };
+
+        let response = request.send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await?;
+            return Err(ReadNodeError::Api(status, body));
+        }
+
+        let mut response_json: Value = response.json().await?;
+
+        info!(response_json = %response_json, "explore_deploy response");
+
+        let data_value = response_json
+            .pointer_mut("/expr/0")
+            .map(Value::take)
+            .ok_or(ReadNodeError::ReturnValueMissing)?;
+
+        serde_json::from_value(data_value)
+            .context("failed to deserialize intermediate model")
+            .map_err(ReadNodeError::Deserialization)
+    }
+
+    async fn status(&self) -> Result<(), ReadNodeError> {
+        let response = self
+            .client
+            .get(format!("{}/api/status", self.url))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await?;
+            return Err(ReadNodeError::Api(status, body));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,455 @@
+use std::fmt;
+
+use serde::de::value::{SeqDeserializer, StringDeserializer};
+use serde::de::{self, DeserializeSeed, IntoDeserializer, Unexpected, Visitor};
+use serde::forward_to_deserialize_any;
+
+use crate::models::{ReadNodeExpr, ReadNodeExprUnforg};
+
+/// Newtype name [`crate::models::Uri`] deserializes through, so that only `ExprUri` is accepted
+/// for it instead of any string.
+pub(crate) const URI_NEWTYPE: &str = "$firefly_client::Uri";
+
+/// Decoding error together with the path inside the expression where it happened, e.g.
+/// `$.agents[2].created_at`.
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message} at {}", path.as_deref().unwrap_or("$"))]
+pub struct Error {
+    pub path: Option<String>,
+    pub message: String,
+}
+
+impl Error {
+    fn at(mut self, path: &str) -> Self {
+        if self.path.is_none() {
+            self.path = Some(path.to_owned());
+        }
+        self
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T>(msg: T) -> Self
+    where
+        T: fmt::Display,
+    {
+        Self {
+            path: None,
+            message: msg.to_string(),
+        }
+    }
+}
+
+/// Deserializes `T` straight from the node expression, keeping bytes, URIs, sets and unforgeable
+/// names apart.
+pub fn from_expr<T>(expr: ReadNodeExpr) -> Result<T, Error>
+where
+    T: de::DeserializeOwned,
+{
+    T::deserialize(Deserializer::new(expr, "$".to_owned()))
+}
+
+pub struct Deserializer {
+    expr: ReadNodeExpr,
+    path: String,
+}
+
+impl Deserializer {
+    pub const fn new(expr: ReadNodeExpr, path: String) -> Self {
+        Self { expr, path }
+    }
+
+    fn unexpected(&self) -> Unexpected<'_> {
+        match &self.expr {
+            ReadNodeExpr::ExprTuple { .. } => Unexpected::Other("tuple"),
+            ReadNodeExpr::ExprList { .. } | ReadNodeExpr::ExprSet { .. } => Unexpected::Seq,
+            ReadNodeExpr::ExprMap { .. } => Unexpected::Map,
+            ReadNodeExpr::ExprNil {} => Unexpected::Unit,
+            ReadNodeExpr::ExprBool { data } => Unexpected::Bool(*data),
+            ReadNodeExpr::ExprInt { .. } => Unexpected::Other("integer"),
+            ReadNodeExpr::ExprString { data } => Unexpected::Str(data),
+            ReadNodeExpr::ExprBytes { .. } => Unexpected::Other("bytes"),
+            ReadNodeExpr::ExprUri { .. } => Unexpected::Other("uri"),
+            ReadNodeExpr::ExprUnforg { .. } => Unexpected::Other("unforgeable name"),
+        }
+    }
+
+    fn decode_bytes(data: &str) -> Result<Vec<u8>, Error> {
+        hex::decode(data).map_err(|err| de::Error::custom(format!("invalid bytes: {err}")))
+    }
+
+    fn deserialize_any_inner<'de, V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let path = self.path;
+        match self.expr {
+            ReadNodeExpr::ExprTuple { data }
+            | ReadNodeExpr::ExprList { data }
+            | ReadNodeExpr::ExprSet { data } => visitor.visit_seq(SeqAccess::new(data, path)),
+            ReadNodeExpr::ExprMap { data } => visitor.visit_map(MapAccess {
+                entries: data.into_iter(),
+                value: None,
+                path,
+            }),
+            ReadNodeExpr::ExprNil {} => visitor.visit_unit(),
+            ReadNodeExpr::ExprBool { data } => visitor.visit_bool(data),
+            ReadNodeExpr::ExprInt { data } => {
+                if let Some(value) = data.as_i64() {
+                    visitor.visit_i64(value)
+                } else if let Some(value) = data.as_u64() {
+                    visitor.visit_u64(value)
+                } else {
+                    visitor.visit_f64(data.as_f64().unwrap_or(f64::NAN))
+                }
+            }
+            ReadNodeExpr::ExprString { data } | ReadNodeExpr::ExprUri { data } => {
+                visitor.visit_string(data)
+            }
+            ReadNodeExpr::ExprBytes { data } => visitor.visit_byte_buf(Self::decode_bytes(&data)?),
+            ReadNodeExpr::ExprUnforg { data } => visitor.visit_string(unforg_data(data)),
+        }
+    }
+}
+
+const fn unforg_variant(unforg: &ReadNodeExprUnforg) -> &'static str {
+    match unforg {
+        ReadNodeExprUnforg::UnforgPrivate { .. } => "Private",
+        ReadNodeExprUnforg::UnforgDeploy { .. } => "Deploy",
+        ReadNodeExprUnforg::UnforgDeployer { .. } => "Deployer",
+    }
+}
+
+fn unforg_data(unforg: ReadNodeExprUnforg) -> String {
+    match unforg {
+        ReadNodeExprUnforg::UnforgPrivate { data }
+        | ReadNodeExprUnforg::UnforgDeploy { data }
+        | ReadNodeExprUnforg::UnforgDeployer { data } => data,
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Deserializer {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_any_inner(visitor)
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.expr {
+            ReadNodeExpr::ExprNil {} => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_unit<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.expr {
+            ReadNodeExpr::ExprNil {} => visitor.visit_unit(),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_unit(visitor)
+    }
+
+    // Bytes stay readable as their hex form for targets that ask for a string
+    fn deserialize_str<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.expr {
+            ReadNodeExpr::ExprBytes { data } => visitor.visit_string(data),
+            _ => self.deserialize_any_inner(visitor),
+        }
+    }
+
+    fn deserialize_string<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_str(visitor)
+    }
+
+    fn deserialize_bytes<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.expr {
+            ReadNodeExpr::ExprBytes { data } => visitor.visit_byte_buf(Self::decode_bytes(&data)?),
+            _ => Err(de::Error::invalid_type(self.unexpected(), &visitor)),
+        }
+    }
+
+    fn deserialize_byte_buf<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.deserialize_bytes(visitor)
+    }
+
+    // `Vec<u8>` asks for a sequence, so bytes are handed out one by one
+    fn deserialize_seq<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.expr {
+            ReadNodeExpr::ExprBytes { data } => {
+                visitor.visit_seq(SeqDeserializer::new(Self::decode_bytes(&data)?.into_iter()))
+            }
+            _ => self.deserialize_any_inner(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match (name, self.expr) {
+            (URI_NEWTYPE, ReadNodeExpr::ExprUri { data }) => {
+                visitor.visit_newtype_struct(IntoDeserializer::<Error>::into_deserializer(data))
+            }
+            (URI_NEWTYPE, expr) => Err(de::Error::invalid_type(
+                Self::new(expr, self.path).unexpected(),
+                &"uri",
+            )),
+            (_, expr) => visitor.visit_newtype_struct(Self::new(expr, self.path)),
+        }
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let path = self.path;
+        let (variant, value) = match self.expr {
+            ReadNodeExpr::ExprString { data } => (data, None),
+            ReadNodeExpr::ExprUnforg { data } => (
+                unforg_variant(&data).to_owned(),
+                Some(ReadNodeExpr::ExprString {
+                    data: unforg_data(data),
+                }),
+            ),
+            ReadNodeExpr::ExprMap { data } if data.len() == 1 => data
+                .into_iter()
+                .next()
+                .map(|(variant, value)| (variant, Some(value)))
+                .ok_or_else(|| de::Error::custom("expected single entry map"))?,
+            ReadNodeExpr::ExprTuple { data } if data.len() == 2 => {
+                let mut data = data.into_iter();
+                match (data.next(), data.next()) {
+                    (Some(ReadNodeExpr::ExprString { data: variant }), value) => (variant, value),
+                    _ => return Err(de::Error::custom("expected (variant, value) tuple")),
+                }
+            }
+            expr => {
+                return Err(de::Error::invalid_type(
+                    Self::new(expr, path).unexpected(),
+                    &"enum",
+                ));
+            }
+        };
+
+        visitor.visit_enum(EnumAccess {
+            variant,
+            value,
+            path,
+        })
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char
+        tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct SeqAccess {
+    elements: std::iter::Enumerate<std::vec::IntoIter<ReadNodeExpr>>,
+    path: String,
+}
+
+impl SeqAccess {
+    fn new(elements: Vec<ReadNodeExpr>, path: String) -> Self {
+        Self {
+            elements: elements.into_iter().enumerate(),
+            path,
+        }
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for SeqAccess {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let Some((i, expr)) = self.elements.next() else {
+            return Ok(None);
+        };
+
+        let path = format!("{}[{i}]", self.path);
+        seed.deserialize(Deserializer::new(expr, path.clone()))
+            .map(Some)
+            .map_err(|err| err.at(&path))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.elements.len())
+    }
+}
+
+struct MapAccess {
+    entries: std::collections::hash_map::IntoIter<String, ReadNodeExpr>,
+    value: Option<(String, ReadNodeExpr)>,
+    path: String,
+}
+
+impl<'de> de::MapAccess<'de> for MapAccess {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+
+        let path = format!("{}.{key}", self.path);
+        let key = seed
+            .deserialize(StringDeserializer::<Error>::new(key))
+            .map_err(|err| err.at(&path))?;
+        self.value = Some((path, value));
+        Ok(Some(key))
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let (path, value) = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value requested before key"))?;
+
+        seed.deserialize(Deserializer::new(value, path.clone()))
+            .map_err(|err| err.at(&path))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.entries.len())
+    }
+}
+
+struct EnumAccess {
+    variant: String,
+    value: Option<ReadNodeExpr>,
+    path: String,
+}
+
+impl<'de> de::EnumAccess<'de> for EnumAccess {
+    type Error = Error;
+    type Variant = VariantAccess;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let path = format!("{}.{}", self.path, self.variant);
+        let variant = seed
+            .deserialize(StringDeserializer::<Error>::new(self.variant))
+            .map_err(|err| err.at(&self.path))?;
+
+        Ok((
+            variant,
+            VariantAccess {
+                value: self.value,
+                path,
+            },
+        ))
+    }
+}
+
+struct VariantAccess {
+    value: Option<ReadNodeExpr>,
+    path: String,
+}
+
+impl VariantAccess {
+    fn into_deserializer(self) -> Result<Deserializer, Error> {
+        self.value
+            .map(|value| Deserializer::new(value, self.path))
+            .ok_or_else(|| de::Error::invalid_type(Unexpected::UnitVariant, &"variant with value"))
+    }
+}
+
+impl<'de> de::VariantAccess<'de> for VariantAccess {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.value {
+            None | Some(ReadNodeExpr::ExprNil {}) => Ok(()),
+            Some(_) => Err(de::Error::invalid_type(
+                Unexpected::NewtypeVariant,
+                &"unit variant",
+            )),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let path = self.path.clone();
+        seed.deserialize(self.into_deserializer()?)
+            .map_err(|err| err.at(&path))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let path = self.path.clone();
+        de::Deserializer::deserialize_seq(self.into_deserializer()?, visitor)
+            .map_err(|err| err.at(&path))
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let path = self.path.clone();
+        de::Deserializer::deserialize_map(self.into_deserializer()?, visitor)
+            .map_err(|err| err.at(&path))
+    }
+}
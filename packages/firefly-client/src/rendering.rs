@@ -1,11 +1,18 @@
 use std::collections::{BTreeMap, BTreeSet};
 use std::fmt;
 
-use derive_more::{From, Into};
+use derive_more::Into;
 pub use firefly_client_macros::{IntoValue, Render};
 use uuid::Uuid;
 
-use crate::models::{DeployData, DeployDataBuilder};
+use crate::models::rhoapi::expr::ExprInstance;
+use crate::models::{DeployData, DeployDataBuilder, rhoapi};
+
+mod parser;
+mod term;
+
+pub use parser::ValueParseError;
+pub use term::{Bind, InvalidName, Name, NewName, Pattern, Term};
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Value {
@@ -23,6 +30,33 @@ pub enum Value {
     Inline(String),
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("character {0:?} can't be rendered into a deploy, the lexer has no escape for it")]
+pub struct UnprintableChar(pub char);
+
+impl Value {
+    /// Checks that the value has no characters the Rholang lexer has no escape for, or that would
+    /// hide what the rendered term does. [`Render`] checks every value it renders.
+    pub fn check(&self) -> Result<(), UnprintableChar> {
+        match self {
+            Self::Tuple(values) | Self::List(values) => values.iter().try_for_each(Self::check),
+            Self::Set(values) => values.iter().try_for_each(Self::check),
+            Self::Map(map) => map.iter().try_for_each(|(key, value)| {
+                check_chars(key)?;
+                value.check()
+            }),
+            Self::Nil | Self::Bool(_) | Self::Int(_) | Self::Bytes(_) => Ok(()),
+            Self::String(string) | Self::Uri(string) | Self::Inline(string) => check_chars(string),
+        }
+    }
+}
+
+fn check_chars(s: &str) -> Result<(), UnprintableChar> {
+    s.chars()
+        .find(|&c| is_unprintable(c))
+        .map_or(Ok(()), |c| Err(UnprintableChar(c)))
+}
+
 pub trait IntoValue {
     fn into_value(self) -> Value;
 }
@@ -168,17 +202,61 @@ impl<Tz: chrono::TimeZone> IntoValue for chrono::DateTime<Tz> {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, From, Into)]
+/// Rholang spliced into a template as is. Only built from a [`Term`], so it can't carry raw
+/// user input.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Into)]
 pub struct Inline(String);
 
+impl From<Term> for Inline {
+    fn from(term: Term) -> Self {
+        Self(term.to_string())
+    }
+}
+
 impl IntoValue for Inline {
     fn into_value(self) -> Value {
         Value::Inline(self.0)
     }
 }
 
+/// Whether `c` is kept out of deploys by [`Value::check`]: control characters the lexer has no
+/// escape for and invisible formatting characters, such as bidi overrides, that would hide what a
+/// term does. `\r` is let through as it is, so that CRLF line breaks can be rendered.
+const fn is_unprintable(c: char) -> bool {
+    matches!(c,
+        '\0'..='\u{8}' | '\u{b}' | '\u{c}' | '\u{e}'..='\u{1f}' | '\u{7f}'..='\u{9f}'
+        | '\u{200b}'..='\u{200f}' | '\u{202a}'..='\u{202e}' | '\u{2060}'..='\u{2064}'
+        | '\u{2066}'..='\u{2069}' | '\u{feff}'
+    )
+}
+
+/// Escapes a string literal the way the Rholang lexer reads it back: `\"`, `\\`, `\n` and `\t`
+/// are the only escapes. Any other character is read back as it is, so it is written as it is.
 fn escape_rho_string(s: &str) -> String {
-    s.replace('\\', "\\\\").replace('"', "\\\"")
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Escapes a uri literal, where only `` \` `` and `\\` are escapes.
+fn escape_rho_uri(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '`' => escaped.push_str("\\`"),
+            '\\' => escaped.push_str("\\\\"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
 }
 
 fn display_iterable<T, F>(values: T, f: &mut fmt::Formatter<'_>, mut format: F) -> fmt::Result
@@ -205,7 +283,7 @@ impl fmt::Display for Value {
             Self::Int(number) => number.fmt(f),
             Self::String(string) => write!(f, "\"{}\"", escape_rho_string(string)),
             Self::Bytes(bytes) => write!(f, "\"{}\".hexToBytes()", hex::encode(bytes)),
-            Self::Uri(string) => write!(f, "`{string}`"),
+            Self::Uri(string) => write!(f, "`{}`", escape_rho_uri(string)),
             Self::Inline(string) => f.write_str(string),
             Self::Tuple(values) => {
                 f.write_str("(")?;
@@ -235,6 +313,129 @@ impl fmt::Display for Value {
     }
 }
 
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ParConversionError {
+    #[error("inline rholang can not be converted to par: {0}")]
+    Inline(String),
+    #[error("par is not a single ground expression")]
+    NotGround,
+    #[error("unsupported expr type: {0}")]
+    UnsupportedExpr(String),
+    #[error("map key must be a string")]
+    NonStringMapKey,
+    #[error("missing map entry {0}")]
+    MissingMapEntry(&'static str),
+}
+
+fn ground(expr: ExprInstance) -> rhoapi::Par {
+    rhoapi::Par {
+        exprs: vec![rhoapi::Expr {
+            expr_instance: Some(expr),
+        }],
+        ..Default::default()
+    }
+}
+
+fn pars(values: impl IntoIterator<Item = Value>) -> Result<Vec<rhoapi::Par>, ParConversionError> {
+    values.into_iter().map(TryInto::try_into).collect()
+}
+
+impl TryFrom<Value> for rhoapi::Par {
+    type Error = ParConversionError;
+
+    fn try_from(value: Value) -> Result<Self, Self::Error> {
+        let expr = match value {
+            Value::Nil => return Ok(Self::default()),
+            Value::Tuple(values) => ExprInstance::ETupleBody(rhoapi::ETuple {
+                ps: pars(values)?,
+                ..Default::default()
+            }),
+            Value::List(values) => ExprInstance::EListBody(rhoapi::EList {
+                ps: pars(values)?,
+                ..Default::default()
+            }),
+            Value::Set(values) => ExprInstance::ESetBody(rhoapi::ESet {
+                ps: pars(values)?,
+                ..Default::default()
+            }),
+            Value::Map(map) => ExprInstance::EMapBody(rhoapi::EMap {
+                kvs: map
+                    .into_iter()
+                    .map(|(key, value)| {
+                        Ok(rhoapi::KeyValuePair {
+                            key: Some(ground(ExprInstance::GString(key))),
+                            value: Some(value.try_into()?),
+                        })
+                    })
+                    .collect::<Result<_, ParConversionError>>()?,
+                ..Default::default()
+            }),
+            Value::Bool(value) => ExprInstance::GBool(value),
+            Value::Int(value) => ExprInstance::GInt(value),
+            Value::String(value) => ExprInstance::GString(value),
+            Value::Bytes(value) => ExprInstance::GByteArray(value),
+            Value::Uri(value) => ExprInstance::GUri(value),
+            Value::Inline(value) => return Err(ParConversionError::Inline(value)),
+        };
+
+        Ok(ground(expr))
+    }
+}
+
+fn values(pars: Vec<rhoapi::Par>) -> Result<Vec<Value>, ParConversionError> {
+    pars.into_iter().map(TryInto::try_into).collect()
+}
+
+impl TryFrom<rhoapi::Par> for Value {
+    type Error = ParConversionError;
+
+    fn try_from(mut par: rhoapi::Par) -> Result<Self, Self::Error> {
+        let exprs = std::mem::take(&mut par.exprs);
+        let rest = rhoapi::Par {
+            locally_free: Vec::new(),
+            connective_used: false,
+            ..par
+        };
+        if rest != rhoapi::Par::default() || exprs.len() > 1 {
+            return Err(ParConversionError::NotGround);
+        }
+
+        let Some(expr) = exprs.into_iter().next() else {
+            return Ok(Self::Nil);
+        };
+
+        match expr.expr_instance.ok_or(ParConversionError::NotGround)? {
+            ExprInstance::GBool(value) => Ok(Self::Bool(value)),
+            ExprInstance::GInt(value) => Ok(Self::Int(value)),
+            ExprInstance::GString(value) => Ok(Self::String(value)),
+            ExprInstance::GUri(value) => Ok(Self::Uri(value)),
+            ExprInstance::GByteArray(value) => Ok(Self::Bytes(value)),
+            ExprInstance::ETupleBody(tuple) => values(tuple.ps).map(Self::Tuple),
+            ExprInstance::EListBody(list) => values(list.ps).map(Self::List),
+            ExprInstance::ESetBody(set) => {
+                values(set.ps).map(|v| Self::Set(v.into_iter().collect()))
+            }
+            ExprInstance::EMapBody(map) => map
+                .kvs
+                .into_iter()
+                .map(|pair| {
+                    let key = pair.key.ok_or(ParConversionError::MissingMapEntry("key"))?;
+                    let Self::String(key) = key.try_into()? else {
+                        return Err(ParConversionError::NonStringMapKey);
+                    };
+                    let value = pair
+                        .value
+                        .ok_or(ParConversionError::MissingMapEntry("value"))?
+                        .try_into()?;
+                    Ok((key, value))
+                })
+                .collect::<Result<_, _>>()
+                .map(Self::Map),
+            other => Err(ParConversionError::UnsupportedExpr(format!("{other:?}"))),
+        }
+    }
+}
+
 pub trait Render: Sized {
     fn render(self) -> Result<String, askama::Error>;
 
@@ -243,6 +444,14 @@ pub trait Render: Sized {
     }
 }
 
+/// Converts a [`Render`] field, failing on values that [`Value::check`] rejects.
+#[doc(hidden)]
+pub fn template_value(value: impl IntoValue) -> Result<Value, askama::Error> {
+    let value = value.into_value();
+    value.check().map_err(askama::Error::custom)?;
+    Ok(value)
+}
+
 pub mod _dependencies {
     pub use askama;
 }
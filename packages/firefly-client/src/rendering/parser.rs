@@ -0,0 +1,266 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use crate::rendering::Value;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValueParseError {
+    #[error("expected {expected} at {position}")]
+    Expected {
+        expected: &'static str,
+        position: usize,
+    },
+    #[error("invalid integer at {0}")]
+    InvalidInt(usize),
+    #[error("invalid hex bytes at {0}")]
+    InvalidHex(usize),
+    #[error("unknown escape sequence at {0}")]
+    InvalidEscape(usize),
+    #[error("map key must be a string at {0}")]
+    NonStringMapKey(usize),
+    #[error("unexpected trailing input at {0}")]
+    TrailingInput(usize),
+}
+
+/// Parses the Rholang literal syntax [`Value`] renders into. Parenthesized values are read back
+/// as tuples, the same way they are rendered.
+impl FromStr for Value {
+    type Err = ValueParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (value, rest) = Self::parse_prefix(s)?;
+
+        let rest_start = rest.len() - rest.trim_start().len();
+        if rest_start < rest.len() {
+            return Err(ValueParseError::TrailingInput(
+                s.len() - rest.len() + rest_start,
+            ));
+        }
+        Ok(value)
+    }
+}
+
+impl Value {
+    /// Parses a value at the start of `s`, returning it with the input left after it.
+    pub fn parse_prefix(s: &str) -> Result<(Self, &str), ValueParseError> {
+        let mut parser = Parser {
+            input: s,
+            position: 0,
+        };
+        let value = parser.value()?;
+        Ok((value, parser.rest()))
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &'static str) -> Result<(), ValueParseError> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(ValueParseError::Expected {
+                expected: token,
+                position: self.position,
+            })
+        }
+    }
+
+    /// Eats a keyword only when it is not the prefix of a longer identifier.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let is_keyword = rest.starts_with(keyword)
+            && !rest[keyword.len()..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_alphanumeric() || c == '_');
+
+        if is_keyword {
+            self.position += keyword.len();
+        }
+        is_keyword
+    }
+
+    fn value(&mut self) -> Result<Value, ValueParseError> {
+        self.skip_whitespace();
+
+        if self.eat_keyword("Nil") {
+            return Ok(Value::Nil);
+        }
+        if self.eat_keyword("true") {
+            return Ok(Value::Bool(true));
+        }
+        if self.eat_keyword("false") {
+            return Ok(Value::Bool(false));
+        }
+        if self.eat("Set(") {
+            return self
+                .sequence(")")
+                .map(|values| Value::Set(values.into_iter().collect::<BTreeSet<_>>()));
+        }
+        if self.eat("(") {
+            return self.sequence(")").map(Value::Tuple);
+        }
+        if self.eat("[") {
+            return self.sequence("]").map(Value::List);
+        }
+        if self.eat("{") {
+            return self.map();
+        }
+
+        match self.peek() {
+            Some('"') => {
+                let start = self.position;
+                let string = self.string()?;
+                if self.eat(".hexToBytes()") {
+                    hex::decode(string)
+                        .map(Value::Bytes)
+                        .map_err(|_| ValueParseError::InvalidHex(start))
+                } else {
+                    Ok(Value::String(string))
+                }
+            }
+            Some('`') => self.uri().map(Value::Uri),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.int().map(Value::Int),
+            _ => Err(ValueParseError::Expected {
+                expected: "value",
+                position: self.position,
+            }),
+        }
+    }
+
+    /// Comma separated values up to `close`, allowing a trailing comma.
+    fn sequence(&mut self, close: &'static str) -> Result<Vec<Value>, ValueParseError> {
+        let mut values = Vec::new();
+
+        while !self.eat(close) {
+            values.push(self.value()?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                break;
+            }
+        }
+
+        Ok(values)
+    }
+
+    fn map(&mut self) -> Result<Value, ValueParseError> {
+        let mut map = BTreeMap::new();
+
+        while !self.eat("}") {
+            self.skip_whitespace();
+            let key_position = self.position;
+            let Value::String(key) = self.value()? else {
+                return Err(ValueParseError::NonStringMapKey(key_position));
+            };
+            self.expect(":")?;
+            map.insert(key, self.value()?);
+
+            if !self.eat(",") {
+                self.expect("}")?;
+                break;
+            }
+        }
+
+        Ok(Value::Map(map))
+    }
+
+    fn string(&mut self) -> Result<String, ValueParseError> {
+        self.expect("\"")?;
+        let mut string = String::new();
+
+        let mut chars = self.rest().char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += offset + 1;
+                    return Ok(string);
+                }
+                '\\' => {
+                    let escaped = match chars.next() {
+                        Some((_, '"')) => '"',
+                        Some((_, '\\')) => '\\',
+                        Some((_, 'n')) => '\n',
+                        Some((_, 't')) => '\t',
+                        _ => return Err(ValueParseError::InvalidEscape(self.position + offset)),
+                    };
+                    string.push(escaped);
+                }
+                c => string.push(c),
+            }
+        }
+
+        Err(ValueParseError::Expected {
+            expected: "\"",
+            position: self.input.len(),
+        })
+    }
+
+    fn uri(&mut self) -> Result<String, ValueParseError> {
+        self.expect("`")?;
+        let mut uri = String::new();
+
+        let mut chars = self.rest().char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '`' => {
+                    self.position += offset + 1;
+                    return Ok(uri);
+                }
+                '\\' => match chars.next() {
+                    Some((_, c @ ('`' | '\\'))) => uri.push(c),
+                    _ => return Err(ValueParseError::InvalidEscape(self.position + offset)),
+                },
+                c => uri.push(c),
+            }
+        }
+
+        Err(ValueParseError::Expected {
+            expected: "`",
+            position: self.input.len(),
+        })
+    }
+
+    fn int(&mut self) -> Result<i64, ValueParseError> {
+        let start = self.position;
+        let rest = self.rest();
+        let sign = usize::from(rest.starts_with('-'));
+        let len = sign
+            + rest[sign..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len() - sign);
+
+        let number = rest[..len]
+            .parse()
+            .map_err(|_| ValueParseError::InvalidInt(start))?;
+        self.position += len;
+        Ok(number)
+    }
+}
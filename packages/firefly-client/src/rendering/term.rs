@@ -0,0 +1,321 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::rendering::{IntoValue, Value, escape_rho_uri};
+
+const KEYWORDS: &[&str] = &[
+    "Bool",
+    "ByteArray",
+    "Int",
+    "Nil",
+    "Set",
+    "String",
+    "Uri",
+    "and",
+    "bundle",
+    "bundle0",
+    "contract",
+    "else",
+    "false",
+    "for",
+    "if",
+    "in",
+    "let",
+    "match",
+    "matches",
+    "new",
+    "not",
+    "or",
+    "select",
+    "true",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("invalid rholang name: {0:?}")]
+pub struct InvalidName(pub String);
+
+/// Rholang variable. Only identifiers the lexer reads as a single variable can be built, so a name
+/// can be spliced into a term as is.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, derive_more::Display)]
+pub struct Name(String);
+
+impl Name {
+    pub fn new(name: impl Into<String>) -> Result<Self, InvalidName> {
+        let name = name.into();
+
+        let mut chars = name.chars();
+        let valid = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '\'')
+            && name != "_"
+            && !KEYWORDS.contains(&name.as_str());
+
+        if valid {
+            Ok(Self(name))
+        } else {
+            Err(InvalidName(name))
+        }
+    }
+}
+
+impl FromStr for Name {
+    type Err = InvalidName;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl AsRef<str> for Name {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Name bound by `new`, optionally to a system channel uri.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NewName {
+    pub name: Name,
+    pub uri: Option<String>,
+}
+
+impl NewName {
+    pub fn system(name: Name, uri: impl Into<String>) -> Self {
+        Self {
+            name,
+            uri: Some(uri.into()),
+        }
+    }
+}
+
+impl From<Name> for NewName {
+    fn from(name: Name) -> Self {
+        Self { name, uri: None }
+    }
+}
+
+impl fmt::Display for NewName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.uri {
+            Some(uri) => write!(f, "{}(`{}`)", self.name, escape_rho_uri(uri)),
+            None => self.name.fmt(f),
+        }
+    }
+}
+
+/// Single binding of a `for`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Bind {
+    /// `name <- channel`, binds the received name.
+    Name { name: Name, channel: Name },
+    /// `@name <- channel`, binds the received process.
+    Process { name: Name, channel: Name },
+}
+
+impl Bind {
+    pub const fn name(name: Name, channel: Name) -> Self {
+        Self::Name { name, channel }
+    }
+
+    pub const fn process(name: Name, channel: Name) -> Self {
+        Self::Process { name, channel }
+    }
+}
+
+impl fmt::Display for Bind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Name { name, channel } => write!(f, "{name} <- {channel}"),
+            Self::Process { name, channel } => write!(f, "@{name} <- {channel}"),
+        }
+    }
+}
+
+/// Pattern of a `match` case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    Wildcard,
+    Var(Name),
+    Value(Value),
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Wildcard => f.write_str("_"),
+            Self::Var(name) => name.fmt(f),
+            Self::Value(value) => value.fmt(f),
+        }
+    }
+}
+
+/// Rholang process built from typed parts, for terms that templates can't express without
+/// splicing raw strings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Nil,
+    Value(Value),
+    Var(Name),
+    Eval(Name),
+    Send {
+        channel: Name,
+        args: Vec<Self>,
+    },
+    Receive {
+        binds: Vec<Bind>,
+        body: Box<Self>,
+    },
+    New {
+        names: Vec<NewName>,
+        body: Box<Self>,
+    },
+    Match {
+        target: Box<Self>,
+        cases: Vec<(Pattern, Self)>,
+    },
+    Par(Vec<Self>),
+}
+
+impl Term {
+    pub fn value(value: impl IntoValue) -> Self {
+        Self::Value(value.into_value())
+    }
+
+    pub const fn var(name: Name) -> Self {
+        Self::Var(name)
+    }
+
+    pub const fn eval(name: Name) -> Self {
+        Self::Eval(name)
+    }
+
+    pub fn send(channel: Name, args: impl IntoIterator<Item = Self>) -> Self {
+        Self::Send {
+            channel,
+            args: args.into_iter().collect(),
+        }
+    }
+
+    pub fn receive(binds: impl IntoIterator<Item = Bind>, body: Self) -> Self {
+        Self::Receive {
+            binds: binds.into_iter().collect(),
+            body: Box::new(body),
+        }
+    }
+
+    pub fn new<N>(names: impl IntoIterator<Item = N>, body: Self) -> Self
+    where
+        N: Into<NewName>,
+    {
+        Self::New {
+            names: names.into_iter().map(Into::into).collect(),
+            body: Box::new(body),
+        }
+    }
+
+    pub fn match_on(target: Self, cases: impl IntoIterator<Item = (Pattern, Self)>) -> Self {
+        Self::Match {
+            target: Box::new(target),
+            cases: cases.into_iter().collect(),
+        }
+    }
+
+    /// Runs `terms` in parallel, flattening nested pars and dropping `Nil`s.
+    pub fn par(terms: impl IntoIterator<Item = Self>) -> Self {
+        let mut flat: Vec<_> = terms
+            .into_iter()
+            .flat_map(|term| match term {
+                Self::Par(terms) => terms,
+                Self::Nil => Vec::new(),
+                term => vec![term],
+            })
+            .collect();
+
+        match flat.len() {
+            0 => Self::Nil,
+            1 => flat.remove(0),
+            _ => Self::Par(flat),
+        }
+    }
+
+    /// Whether the term is a single token, that needs no parentheses as a match target.
+    const fn is_atomic(&self) -> bool {
+        matches!(self, Self::Nil | Self::Value(_) | Self::Var(_) | Self::Eval(_))
+    }
+}
+
+/// Writes `body` indented by one level, wrapped in braces.
+fn block(f: &mut fmt::Formatter<'_>, body: &Term) -> fmt::Result {
+    write!(f, "{{\n{}\n}}", indent(body))
+}
+
+fn join<T: fmt::Display>(f: &mut fmt::Formatter<'_>, items: &[T], separator: &str) -> fmt::Result {
+    items.iter().enumerate().try_for_each(|(i, item)| {
+        if i > 0 {
+            f.write_str(separator)?;
+        }
+        item.fmt(f)
+    })
+}
+
+impl fmt::Display for Term {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Nil => f.write_str("Nil"),
+            Self::Value(value) => value.fmt(f),
+            Self::Var(name) => name.fmt(f),
+            Self::Eval(name) => write!(f, "*{name}"),
+            Self::Send { channel, args } => {
+                write!(f, "{channel}!(")?;
+                join(f, args, ", ")?;
+                f.write_str(")")
+            }
+            Self::Receive { binds, body } => {
+                f.write_str("for(")?;
+                join(f, binds, " & ")?;
+                f.write_str(") ")?;
+                block(f, body)
+            }
+            Self::New { names, body } => {
+                f.write_str("new ")?;
+                join(f, names, ", ")?;
+                f.write_str(" in ")?;
+                block(f, body)
+            }
+            Self::Match { target, cases } => {
+                if target.is_atomic() {
+                    writeln!(f, "match {target} {{")?;
+                } else {
+                    writeln!(f, "match ({target}) {{")?;
+                }
+                for (pattern, body) in cases {
+                    let case = format!("{pattern} => {{\n{}\n}}", indent(body));
+                    writeln!(f, "{}", indent(&case))?;
+                }
+                f.write_str("}")
+            }
+            Self::Par(terms) => join(f, terms, " |\n"),
+        }
+    }
+}
+
+fn indent(term: &impl fmt::Display) -> String {
+    term.to_string()
+        .lines()
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("    {line}")
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+impl IntoValue for Term {
+    fn into_value(self) -> Value {
+        Value::Inline(self.to_string())
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use secp256k1::ecdsa::Signature;
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use serde::Deserialize;
+
+mod keystore;
+mod remote;
+
+pub use keystore::{Keystore, KeystoreSigner};
+pub use remote::{RemoteSigner, SignerRequest, SignerResponse, handle_request, serve_unix};
+
+/// Produces secp256k1 signatures over blake2b-256 digests for deploys and registry entries.
+#[async_trait]
+pub trait Signer: Send + Sync {
+    fn public_key(&self) -> PublicKey;
+
+    async fn sign(&self, digest: [u8; 32]) -> anyhow::Result<Signature>;
+}
+
+#[async_trait]
+impl Signer for SecretKey {
+    fn public_key(&self) -> PublicKey {
+        Self::public_key(self, &Secp256k1::signing_only())
+    }
+
+    async fn sign(&self, digest: [u8; 32]) -> anyhow::Result<Signature> {
+        Ok(Secp256k1::signing_only().sign_ecdsa(Message::from_digest(digest), self))
+    }
+}
+
+#[async_trait]
+impl<T> Signer for Arc<T>
+where
+    T: Signer + ?Sized,
+{
+    fn public_key(&self) -> PublicKey {
+        self.as_ref().public_key()
+    }
+
+    async fn sign(&self, digest: [u8; 32]) -> anyhow::Result<Signature> {
+        self.as_ref().sign(digest).await
+    }
+}
+
+/// Where a signing key comes from: a raw hex key, an encrypted keystore file or a remote signer
+/// endpoint (`http(s)://...` or `unix:/path/to/socket`).
+#[derive(derive_more::Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum SignerConfig {
+    Key(SecretKey),
+    Keystore {
+        keystore: PathBuf,
+        #[debug(skip)]
+        password: String,
+    },
+    Remote {
+        remote_signer: String,
+    },
+}
+
+/// Command line arguments choosing one of the [`SignerConfig`] sources.
+#[cfg(feature = "cli")]
+#[derive(Debug, Clone, clap::Args)]
+#[group(skip)]
+#[command(about = None, long_about = None)]
+#[command(group(clap::ArgGroup::new("signer").required(true)))]
+pub struct SignerArgs {
+    /// Wallet key in hex format
+    #[arg(long, group = "signer")]
+    wallet_key: Option<SecretKey>,
+
+    /// Encrypted wallet keystore file
+    #[arg(long, group = "signer", requires = "keystore_password")]
+    keystore: Option<PathBuf>,
+
+    /// Wallet keystore password
+    #[arg(long, env = "KEYSTORE_PASSWORD", hide_env_values = true)]
+    keystore_password: Option<String>,
+
+    /// Remote signer url, `http(s)://...` or `unix:/path/to/socket`
+    #[arg(long, group = "signer")]
+    remote_signer: Option<String>,
+}
+
+#[cfg(feature = "cli")]
+impl From<SignerArgs> for SignerConfig {
+    fn from(args: SignerArgs) -> Self {
+        match (args.wallet_key, args.keystore, args.remote_signer) {
+            (Some(key), _, _) => Self::Key(key),
+            (None, Some(keystore), _) => Self::Keystore {
+                keystore,
+                password: args.keystore_password.unwrap_or_default(),
+            },
+            (None, None, remote_signer) => Self::Remote {
+                remote_signer: remote_signer.unwrap_or_default(),
+            },
+        }
+    }
+}
+
+impl SignerConfig {
+    pub async fn into_signer(self) -> anyhow::Result<Arc<dyn Signer>> {
+        Ok(match self {
+            Self::Key(key) => Arc::new(key),
+            Self::Keystore { keystore, password } => {
+                Arc::new(KeystoreSigner::open(&keystore, &password)?)
+            }
+            Self::Remote { remote_signer } => {
+                Arc::new(RemoteSigner::connect(&remote_signer).await?)
+            }
+        })
+    }
+}
@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::path::Path;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use secp256k1::ecdsa::Signature;
+use secp256k1::{PublicKey, SecretKey};
+use serde::{Deserialize, Serialize};
+
+use crate::signer::Signer;
+
+const KEYSTORE_VERSION: u8 = 1;
+const SALT_LEN: usize = 32;
+
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScryptParams {
+    pub log_n: u8,
+    pub r: u32,
+    pub p: u32,
+    #[serde(with = "hex")]
+    pub salt: Vec<u8>,
+}
+
+/// Secret key encrypted at rest with an scrypt-derived AES-256-GCM key.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keystore {
+    pub version: u8,
+    pub public_key: PublicKey,
+    pub kdf: ScryptParams,
+    #[serde(with = "hex")]
+    pub nonce: Vec<u8>,
+    #[serde(with = "hex")]
+    pub ciphertext: Vec<u8>,
+}
+
+impl Keystore {
+    pub fn encrypt(key: &SecretKey, password: &str) -> anyhow::Result<Self> {
+        let mut salt = vec![0; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+
+        let kdf = ScryptParams {
+            log_n: SCRYPT_LOG_N,
+            r: SCRYPT_R,
+            p: SCRYPT_P,
+            salt,
+        };
+        let public_key = Signer::public_key(key);
+
+        let cipher = Aes256Gcm::new(&derive_key(password, &kdf)?);
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: &key.secret_bytes(),
+                    aad: &public_key.serialize(),
+                },
+            )
+            .map_err(|err| anyhow!("failed to encrypt key: {err}"))?;
+
+        Ok(Self {
+            version: KEYSTORE_VERSION,
+            public_key,
+            kdf,
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    pub fn decrypt(&self, password: &str) -> anyhow::Result<SecretKey> {
+        if self.version != KEYSTORE_VERSION {
+            return Err(anyhow!("unsupported keystore version: {}", self.version));
+        }
+
+        let nonce = Nonce::from_exact_iter(self.nonce.iter().copied()).context("invalid nonce")?;
+        let cipher = Aes256Gcm::new(&derive_key(password, &self.kdf)?);
+        let plaintext = cipher
+            .decrypt(
+                &nonce,
+                Payload {
+                    msg: &self.ciphertext,
+                    aad: &self.public_key.serialize(),
+                },
+            )
+            .map_err(|_| anyhow!("invalid keystore password"))?;
+
+        let key = SecretKey::from_byte_array(
+            plaintext
+                .try_into()
+                .map_err(|_| anyhow!("invalid decrypted key length"))?,
+        )?;
+
+        if Signer::public_key(&key) != self.public_key {
+            return Err(anyhow!("keystore public key mismatch"));
+        }
+
+        Ok(key)
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let file = File::open(path).context("failed to open keystore")?;
+        serde_json::from_reader(file).context("failed to parse keystore")
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let file = File::create(path).context("failed to create keystore")?;
+        serde_json::to_writer_pretty(file, self).context("failed to write keystore")
+    }
+}
+
+fn derive_key(password: &str, kdf: &ScryptParams) -> anyhow::Result<Key<Aes256Gcm>> {
+    let params = scrypt::Params::new(kdf.log_n, kdf.r, kdf.p, 32)
+        .map_err(|err| anyhow!("invalid scrypt params: {err}"))?;
+
+    let mut key = Key::<Aes256Gcm>::default();
+    scrypt::scrypt(password.as_bytes(), &kdf.salt, &params, &mut key)
+        .map_err(|err| anyhow!("failed to derive key: {err}"))?;
+
+    Ok(key)
+}
+
+/// Signer backed by a [`Keystore`] file, decrypted once when opened.
+pub struct KeystoreSigner {
+    key: SecretKey,
+}
+
+impl KeystoreSigner {
+    pub fn open(path: &Path, password: &str) -> anyhow::Result<Self> {
+        let key = Keystore::load(path)?.decrypt(password)?;
+        Ok(Self { key })
+    }
+}
+
+#[async_trait]
+impl Signer for KeystoreSigner {
+    fn public_key(&self) -> PublicKey {
+        Signer::public_key(&self.key)
+    }
+
+    async fn sign(&self, digest: [u8; 32]) -> anyhow::Result<Signature> {
+        self.key.sign(digest).await
+    }
+}
@@ -0,0 +1,197 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, anyhow};
+use async_trait::async_trait;
+use secp256k1::ecdsa::Signature;
+use secp256k1::{Message, PublicKey, Secp256k1};
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{UnixListener, UnixStream};
+use tracing::Instrument;
+
+use crate::signer::Signer;
+
+/// Request understood by remote signers, sent as a JSON body over HTTP or as a JSON line over a
+/// Unix socket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum SignerRequest {
+    PublicKey,
+    Sign {
+        #[serde(with = "hex")]
+        digest: [u8; 32],
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SignerResponse {
+    PublicKey {
+        public_key: PublicKey,
+    },
+    Signature {
+        #[serde(with = "hex")]
+        signature: Vec<u8>,
+    },
+    Error {
+        error: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+enum Transport {
+    Http {
+        client: reqwest::Client,
+        url: String,
+    },
+    Unix {
+        path: PathBuf,
+    },
+}
+
+impl Transport {
+    async fn call(&self, request: &SignerRequest) -> anyhow::Result<SignerResponse> {
+        let response = match self {
+            Self::Http { client, url } => {
+                client
+                    .post(url)
+                    .json(request)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .json()
+                    .await?
+            }
+            Self::Unix { path } => {
+                let mut stream = UnixStream::connect(path)
+                    .await
+                    .context("failed to connect to signer socket")?;
+
+                let mut buff = serde_json::to_vec(request)?;
+                buff.push(b'\n');
+                stream.write_all(&buff).await?;
+
+                let mut line = String::new();
+                BufReader::new(stream).read_line(&mut line).await?;
+                serde_json::from_str(&line)?
+            }
+        };
+
+        match response {
+            SignerResponse::Error { error } => Err(anyhow!("remote signer error: {error}")),
+            response => Ok(response),
+        }
+    }
+}
+
+/// Signer that delegates signing to another process, so the key never enters this one.
+#[derive(Debug, Clone)]
+pub struct RemoteSigner {
+    transport: Transport,
+    public_key: PublicKey,
+}
+
+impl RemoteSigner {
+    /// Connects to `http(s)://...` or `unix:/path/to/socket` and fetches the signer public key.
+    pub async fn connect(endpoint: &str) -> anyhow::Result<Self> {
+        let transport = endpoint.strip_prefix("unix:").map_or_else(
+            || Transport::Http {
+                client: reqwest::Client::new(),
+                url: endpoint.to_owned(),
+            },
+            |path| Transport::Unix { path: path.into() },
+        );
+
+        let public_key = match transport.call(&SignerRequest::PublicKey).await? {
+            SignerResponse::PublicKey { public_key } => public_key,
+            response => return Err(anyhow!("unexpected remote signer response: {response:?}")),
+        };
+
+        Ok(Self {
+            transport,
+            public_key,
+        })
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key
+    }
+
+    async fn sign(&self, digest: [u8; 32]) -> anyhow::Result<Signature> {
+        let signature = match self.transport.call(&SignerRequest::Sign { digest }).await? {
+            SignerResponse::Signature { signature } => Signature::from_der(&signature)?,
+            response => return Err(anyhow!("unexpected remote signer response: {response:?}")),
+        };
+
+        Secp256k1::verification_only()
+            .verify_ecdsa(Message::from_digest(digest), &signature, &self.public_key)
+            .context("remote signer returned invalid signature")?;
+
+        Ok(signature)
+    }
+}
+
+pub async fn handle_request<S>(signer: &S, request: SignerRequest) -> SignerResponse
+where
+    S: Signer + ?Sized,
+{
+    match request {
+        SignerRequest::PublicKey => SignerResponse::PublicKey {
+            public_key: signer.public_key(),
+        },
+        SignerRequest::Sign { digest } => match signer.sign(digest).await {
+            Ok(signature) => SignerResponse::Signature {
+                signature: signature.serialize_der().to_vec(),
+            },
+            Err(err) => SignerResponse::Error {
+                error: err.to_string(),
+            },
+        },
+    }
+}
+
+/// Serves `signer` to [`RemoteSigner`] clients connecting to `listener`.
+pub async fn serve_unix<S>(listener: UnixListener, signer: S) -> anyhow::Result<()>
+where
+    S: Signer + Clone + 'static,
+{
+    loop {
+        let (stream, _) = listener.accept().await?;
+        let signer = signer.clone();
+
+        tokio::spawn(
+            async move {
+                let _ = handle_connection(stream, &signer)
+                    .await
+                    .inspect_err(|err| tracing::debug!("signer connection error: {err:?}"));
+            }
+            .in_current_span(),
+        );
+    }
+}
+
+async fn handle_connection<S>(stream: UnixStream, signer: &S) -> anyhow::Result<()>
+where
+    S: Signer,
+{
+    let (reader, mut writer) = stream.into_split();
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Some(line) = lines.next_line().await? {
+        let response = match serde_json::from_str(&line) {
+            Ok(request) => handle_request(signer, request).await,
+            Err(err) => SignerResponse::Error {
+                error: err.to_string(),
+            },
+        };
+
+        let mut buff = serde_json::to_vec(&response)?;
+        buff.push(b'\n');
+        writer.write_all(&buff).await?;
+    }
+
+    Ok(())
+}
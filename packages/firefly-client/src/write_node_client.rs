@@ -1,45 +1,153 @@
+use std::sync::Arc;
+use std::time::Instant;
+
 use anyhow::{Context, anyhow};
+use async_trait::async_trait;
 use blake2::digest::consts::U32;
 use blake2::{Blake2b, Digest};
+use chrono::{DateTime, Utc};
 use futures::TryStreamExt;
 use prost::Message as _;
-use secp256k1::{Message, Secp256k1, SecretKey};
-use tokio::time::{sleep, Duration};
+use secp256k1::PublicKey;
+use tokio::time::{Duration, sleep};
+use tonic::{Code, ConnectError, Status};
 use tracing::warn;
 
-use crate::helpers::FromExpr;
+use crate::connection::{ConnectionOptions, GrpcChannel};
+use crate::errors::ProposeError;
+use crate::metrics;
 use crate::models::casper::v1::deploy_service_client::DeployServiceClient;
 use crate::models::casper::v1::propose_service_client::ProposeServiceClient;
 use crate::models::casper::v1::{
     block_info_response,
+    block_response,
     deploy_response,
+    find_deploy_response,
+    is_finalized_response,
+    last_finalized_block_response,
+    private_name_preview_response,
     propose_response,
+    propose_result_response,
     rho_data_response,
+    status_response,
+};
+use crate::models::casper::{
+    BlockQuery,
+    BlocksQuery,
+    BlocksQueryByHeight,
+    DataAtNameByBlockQuery,
+    DeployDataProto,
+    FindDeployQuery,
+    IsFinalizedQuery,
+    LastFinalizedBlockQuery,
+    PrivateNamePreviewQuery,
+    ProposeQuery,
+    ProposeResultQuery,
 };
-use crate::models::casper::{BlocksQuery, DataAtNameByBlockQuery, DeployDataProto, ProposeQuery};
-use crate::models::rhoapi::expr::ExprInstance;
-use crate::models::rhoapi::{Expr, Par};
-use crate::models::{BlockId, DeployData, DeployId, SignedCode, ValidAfter};
+use crate::models::rhoapi::Par;
+use crate::models::{
+    Block,
+    BlockId,
+    DeployData,
+    DeployId,
+    LightBlock,
+    NodeStatus,
+    SignedCode,
+    UnforgeableName,
+    ValidAfter,
+};
+use crate::rendering::IntoValue;
+use crate::signer::Signer;
+use crate::write_node_client::pool::{Node, NodePool};
+
+mod pool;
+
+pub use pool::{ValidatorEndpoint, ValidatorProbe};
 
 const INITIAL_BACKOFF_SECS: u64 = 1;
 const MAX_BACKOFF_SECS: u64 = 64;
+const MAX_CONNECT_ATTEMPTS: u32 = 10;
+
+const DEFAULT_CONNECT_DEADLINE: Duration = Duration::from_secs(30);
+const DEFAULT_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+const DEFAULT_HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+const CONNECT_RETRY_INTERVAL: Duration = Duration::from_secs(1);
+
+const DEPLOY_NOT_FOUND_ERROR: &str = "Couldn't find block containing deploy with id";
+
+/// Deploys to a validator and follows what happens to the deploys.
+#[async_trait]
+pub trait DeployTransport: Send + Sync {
+    async fn deploy(
+        &self,
+        signer: &dyn Signer,
+        deploy_data: DeployData,
+    ) -> anyhow::Result<DeployId>;
+
+    async fn deploy_signed_contract(&self, contract: SignedCode) -> anyhow::Result<DeployId>;
+
+    async fn get_head_block_index(&self) -> anyhow::Result<u64>;
+
+    /// Block that included `deploy_id`, or `None` while it is not in a block yet.
+    async fn find_deploy(&self, deploy_id: &DeployId) -> anyhow::Result<Option<LightBlock>>;
+
+    async fn is_finalized(&self, block_id: &BlockId) -> anyhow::Result<bool>;
+
+    async fn get_block(&self, block_id: &BlockId) -> anyhow::Result<Block>;
+
+    /// See [`WriteNodeClient::preview_private_names`].
+    async fn preview_private_names(
+        &self,
+        deployer: &PublicKey,
+        timestamp: DateTime<Utc>,
+        count: u32,
+    ) -> anyhow::Result<Vec<UnforgeableName>>;
+
+    /// Whether the validator can currently be reached.
+    fn is_healthy(&self) -> bool;
+
+    /// Calls `status` on the deploy and propose channels of every validator.
+    async fn probe(&self, timeout: Duration) -> Vec<ValidatorProbe>;
+}
 
 #[derive(Clone)]
 pub struct WriteNodeClient {
-    deploy_client: DeployServiceClient<tonic::transport::Channel>,
-    propose_client: ProposeServiceClient<tonic::transport::Channel>,
+    pool: Arc<NodePool>,
 }
 
 impl WriteNodeClient {
+    /// Connects to a single validator, retrying with a backoff while it is not ready yet, e.g.
+    /// during genesis. Gives up after a few minutes.
     pub async fn new(
         deploy_service_url: String,
         propose_service_url: String,
     ) -> anyhow::Result<Self> {
+        Self::with_options(
+            deploy_service_url,
+            propose_service_url,
+            ConnectionOptions::default(),
+        )
+        .await
+    }
+
+    /// Same as [`Self::new`], but connects with TLS, client certificates or extra headers.
+    pub async fn with_options(
+        deploy_service_url: String,
+        propose_service_url: String,
+        options: ConnectionOptions,
+    ) -> anyhow::Result<Self> {
+        options.headers()?;
         let mut backoff = INITIAL_BACKOFF_SECS;
+        let mut attempts = 1;
 
         loop {
-            match Self::try_connect(&deploy_service_url, &propose_service_url).await {
+            match Self::try_connect(&deploy_service_url, &propose_service_url, &options).await {
                 Ok(client) => return Ok(client),
+                Err(err) if attempts >= MAX_CONNECT_ATTEMPTS => {
+                    return Err(err.context(format!(
+                        "validator not ready after {MAX_CONNECT_ATTEMPTS} attempts"
+                    )));
+                }
                 Err(err) => {
                     warn!(
                         error = ?err,
@@ -47,6 +155,7 @@ impl WriteNodeClient {
                     );
                     sleep(Duration::from_secs(backoff)).await;
                     backoff = (backoff.saturating_mul(2)).min(MAX_BACKOFF_SECS);
+                    attempts += 1;
                 }
             }
         }
@@ -55,26 +164,195 @@ impl WriteNodeClient {
     async fn try_connect(
         deploy_service_url: &str,
         propose_service_url: &str,
+        options: &ConnectionOptions,
     ) -> anyhow::Result<Self> {
-        let deploy_client = DeployServiceClient::connect(deploy_service_url.to_owned())
+        let deploy_channel = options
+            .connect_grpc(deploy_service_url.to_owned())
             .await
             .context("failed to connect to deploy service")?;
 
-        let propose_client = ProposeServiceClient::connect(propose_service_url.to_owned())
+        let propose_channel = options
+            .connect_grpc(propose_service_url.to_owned())
             .await
             .context("failed to connect to propose service")?;
 
-        Ok(Self {
-            deploy_client,
-            propose_client,
+        let endpoint = ValidatorEndpoint {
+            deploy_service_url: deploy_service_url.to_owned(),
+            propose_service_url: propose_service_url.to_owned(),
+        };
+        let pool = Arc::new(NodePool::new(vec![Node::new(
+            endpoint,
+            deploy_channel,
+            propose_channel,
+        )]));
+        pool.spawn_health_checks(DEFAULT_HEALTH_CHECK_INTERVAL, DEFAULT_HEALTH_CHECK_TIMEOUT);
+
+        Ok(Self { pool })
+    }
+
+    /// Whether at least one validator answered the last health check.
+    pub fn is_healthy(&self) -> bool {
+        self.pool.is_healthy()
+    }
+
+    /// Calls `status` on the deploy and propose channels of every validator, giving each call up
+    /// to `timeout` to answer.
+    pub async fn probe(&self, timeout: Duration) -> Vec<ValidatorProbe> {
+        self.pool.probe(timeout).await
+    }
+
+    /// Sends the call to the active validator, failing over to the next one when it is unavailable.
+    /// Calls that are not `idempotent` only fail over when no connection could be made, since the
+    /// validator may otherwise have applied them already.
+    async fn failover<C, R, T, F, Fut>(
+        &self,
+        client: fn(&Node) -> &C,
+        idempotent: bool,
+        request: R,
+        call: F,
+    ) -> Result<T, Status>
+    where
+        C: Clone,
+        R: Clone,
+        F: Fn(C, R) -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        let mut attempts = 1;
+
+        loop {
+            let index = self.pool.active();
+            let node = self.pool.node(index);
+
+            match call(client(node).clone(), request.clone()).await {
+                Err(status) if status.code() == Code::Unavailable => {
+                    self.pool.mark_unhealthy(index);
+                    if attempts >= self.pool.len() || !(idempotent || never_sent(&status)) {
+                        return Err(status);
+                    }
+                    warn!(
+                        endpoint = ?node.endpoint,
+                        error = %status,
+                        "validator unavailable, failing over"
+                    );
+                    attempts += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Reads from the deploy service, sending the call to the next validator as needed.
+    async fn deploy_service<R, T, F, Fut>(&self, request: R, call: F) -> Result<T, Status>
+    where
+        R: Clone,
+        F: Fn(DeployServiceClient<GrpcChannel>, R) -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        self.failover(|node| &node.deploy_client, true, request, call)
+            .await
+    }
+
+    /// Calls the propose service, which only moves on to the next validator when the call never
+    /// reached the active one.
+    async fn propose_service<R, T, F, Fut>(&self, request: R, call: F) -> Result<T, Status>
+    where
+        R: Clone,
+        F: Fn(ProposeServiceClient<GrpcChannel>, R) -> Fut,
+        Fut: Future<Output = Result<T, Status>>,
+    {
+        self.failover(|node| &node.propose_client, false, request, call)
+            .await
+    }
+
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client"))]
+    async fn do_deploy(&self, msg: DeployDataProto) -> anyhow::Result<DeployId> {
+        let started = Instant::now();
+        let resp = self
+            .failover(
+                |node| &node.deploy_client,
+                false,
+                msg,
+                |mut client, request| async move { client.do_deploy(request).await },
+            )
+            .await;
+
+        let resp = match resp {
+            Ok(resp) => resp
+                .into_inner()
+                .message
+                .context("missing do_deploy responce")?,
+            Err(status) => {
+                metrics::record_deploy(started, Some("grpc"));
+                return Err(status.into());
+            }
+        };
+
+        let deploy_id = match resp {
+            deploy_response::Message::Result(deploy_id) => {
+                metrics::record_deploy(started, None);
+                deploy_id
+            }
+            deploy_response::Message::Error(err) => {
+                metrics::record_deploy(started, Some("rejected"));
+                return Err(anyhow!("do_deploy error: {err:?}"));
+            }
+        };
+
+        deploy_id
+            .strip_prefix("Success! DeployId is: ")
+            .map(|id| DeployId::from(id.to_owned()))
+            .context("failed to extract deploy_id")
+    }
+}
+
+#[bon::bon]
+impl WriteNodeClient {
+    /// Client over several validators, health-checked with the `status` RPC.
+    ///
+    /// Waits at most `connect_deadline` for one of them to become healthy. When none does, the
+    /// client is returned anyway and keeps checking in the background, so that callers can start
+    /// in a degraded mode instead of blocking.
+    #[builder(start_fn = builder, finish_fn = connect)]
+    pub async fn with_endpoints(
+        endpoints: Vec<ValidatorEndpoint>,
+        #[builder(default)] options: ConnectionOptions,
+        #[builder(default = DEFAULT_CONNECT_DEADLINE)] connect_deadline: Duration,
+        #[builder(default = DEFAULT_HEALTH_CHECK_INTERVAL)] health_check_interval: Duration,
+        #[builder(default = DEFAULT_HEALTH_CHECK_TIMEOUT)] health_check_timeout: Duration,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(!endpoints.is_empty(), "no validator endpoints given");
+
+        let nodes = endpoints
+            .into_iter()
+            .map(|endpoint| Node::lazy(endpoint, &options))
+            .collect::<anyhow::Result<_>>()?;
+        let pool = Arc::new(NodePool::new(nodes));
+
+        let connected = tokio::time::timeout(connect_deadline, async {
+            while !pool.check(health_check_timeout).await {
+                sleep(CONNECT_RETRY_INTERVAL).await;
+            }
         })
+        .await;
+        if connected.is_err() {
+            warn!("no validator became healthy within {connect_deadline:?}, starting degraded");
+        }
+
+        pool.spawn_health_checks(health_check_interval, health_check_timeout);
+
+        Ok(Self { pool })
     }
+}
 
-    pub async fn deploy(
+impl WriteNodeClient {
+    pub async fn deploy<S>(
         &mut self,
-        key: &SecretKey,
+        signer: &S,
         deploy_data: DeployData,
-    ) -> anyhow::Result<DeployId> {
+    ) -> anyhow::Result<DeployId>
+    where
+        S: Signer + ?Sized,
+    {
         let valid_after_block_number = match deploy_data.valid_after_block_number {
             ValidAfter::Head => self.get_head_block_index().await?,
             ValidAfter::Index(i) => i,
@@ -83,114 +361,124 @@ impl WriteNodeClient {
         let mut msg = DeployDataProto {
             term: deploy_data.term,
             timestamp: deploy_data.timestamp.timestamp_millis(),
-            phlo_price: 1,
+            phlo_price: deploy_data.phlo_price as _,
             phlo_limit: deploy_data.phlo_limit as _,
             valid_after_block_number: valid_after_block_number as _,
-            shard_id: "root".into(),
+            shard_id: deploy_data.shard_id,
             ..Default::default()
         };
 
-        let secp = Secp256k1::new();
-
         let hash = Blake2b::<U32>::new()
             .chain_update(msg.encode_to_vec())
             .finalize();
 
-        let signature = secp.sign_ecdsa(Message::from_digest(hash.into()), key);
+        let signature = signer.sign(hash.into()).await?;
 
         msg.sig = signature.serialize_der().to_vec();
         msg.sig_algorithm = "secp256k1".into();
+        msg.deployer = signer.public_key().serialize_uncompressed().into();
 
-        let public_key = key.public_key(&secp);
-        msg.deployer = public_key.serialize_uncompressed().into();
-
-        let resp = self
-            .deploy_client
-            .do_deploy(msg)
-            .await?
-            .into_inner()
-            .message
-            .context("missing do_deploy responce")?;
-
-        let deploy_id = match resp {
-            deploy_response::Message::Result(deploy_id) => deploy_id,
-            deploy_response::Message::Error(err) => {
-                return Err(anyhow!("do_deploy error: {err:?}"));
-            }
-        };
-
-        deploy_id
-            .strip_prefix("Success! DeployId is: ")
-            .map(|id| DeployId::from(id.to_owned()))
-            .context("failed to extract deploy_id")
+        self.do_deploy(msg).await
     }
 
     pub async fn deploy_signed_contract(
         &mut self,
         contract: SignedCode,
     ) -> anyhow::Result<DeployId> {
-        let mut msg = DeployDataProto::decode(contract.contract.as_slice())?;
+        let mut msg = contract.deploy_data()?;
 
         msg.sig = contract.sig;
         msg.sig_algorithm = contract.sig_algorithm;
         msg.deployer = contract.deployer;
 
-        let resp = self
-            .deploy_client
-            .do_deploy(msg)
-            .await?
-            .into_inner()
-            .message
-            .context("missing do_deploy responce")?;
+        self.do_deploy(msg).await
+    }
 
-        let deploy_id = match resp {
-            deploy_response::Message::Result(deploy_id) => deploy_id,
-            deploy_response::Message::Error(err) => {
-                return Err(anyhow!("do_deploy error: {err:?}"));
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client"))]
+    pub async fn propose(&mut self) -> Result<BlockId, ProposeError> {
+        record_propose("propose", async {
+            let resp = self
+                .propose_service(
+                    ProposeQuery { is_async: false },
+                    |mut client, request| async move { client.propose(request).await },
+                )
+                .await?
+                .into_inner()
+                .message
+                .ok_or(ProposeError::InvalidResponse("missing propose responce"))?;
+
+            match resp {
+                propose_response::Message::Result(result) => created_block(&result),
+                propose_response::Message::Error(err) => Err(err.into()),
             }
-        };
-
-        deploy_id
-            .strip_prefix("Success! DeployId is: ")
-            .map(|id| DeployId::from(id.to_owned()))
-            .context("failed to extract deploy_id")
+        })
+        .await
     }
 
-    pub async fn propose(&mut self) -> anyhow::Result<BlockId> {
-        let resp = self
-            .propose_client
-            .propose(ProposeQuery { is_async: false })
-            .await
-            .context("propose grpc error")?
-            .into_inner()
-            .message
-            .context("missing propose responce")?;
-
-        let block_hash = match resp {
-            propose_response::Message::Result(block_hash) => block_hash,
-            propose_response::Message::Error(err) => return Err(anyhow!("propose error: {err:?}")),
-        };
+    /// Starts a propose on the validator without waiting for the block to be created, see
+    /// [`Self::propose_result`].
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client"))]
+    pub async fn propose_async(&mut self) -> Result<(), ProposeError> {
+        record_propose("propose_async", async {
+            let resp = self
+                .propose_service(
+                    ProposeQuery { is_async: true },
+                    |mut client, request| async move { client.propose(request).await },
+                )
+                .await?
+                .into_inner()
+                .message
+                .ok_or(ProposeError::InvalidResponse("missing propose responce"))?;
+
+            match resp {
+                propose_response::Message::Result(_) => Ok(()),
+                propose_response::Message::Error(err) => Err(err.into()),
+            }
+        })
+        .await
+    }
 
-        block_hash
-            .strip_prefix("Success! Block ")
-            .and_then(|block_hash| block_hash.strip_suffix(" created and added."))
-            .map(|id| BlockId::from(id.to_owned()))
-            .context("failed to extract block hash")
+    /// Waits for the propose currently running on the validator and returns the created block.
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client"))]
+    pub async fn propose_result(&mut self) -> Result<BlockId, ProposeError> {
+        record_propose("propose_result", async {
+            let resp = self
+                .propose_service(ProposeResultQuery {}, |mut client, request| async move {
+                    client.propose_result(request).await
+                })
+                .await?
+                .into_inner()
+                .message
+                .ok_or(ProposeError::InvalidResponse(
+                    "missing propose_result responce",
+                ))?;
+
+            match resp {
+                propose_result_response::Message::Result(result) => created_block(&result),
+                propose_result_response::Message::Error(err) => Err(err.into()),
+            }
+        })
+        .await
     }
 
-    pub async fn full_deploy(
+    pub async fn full_deploy<S>(
         &mut self,
-        key: &SecretKey,
+        signer: &S,
         deploy_data: DeployData,
-    ) -> anyhow::Result<BlockId> {
-        self.deploy(key, deploy_data).await?;
-        self.propose().await
+    ) -> anyhow::Result<BlockId>
+    where
+        S: Signer + ?Sized,
+    {
+        self.deploy(signer, deploy_data).await?;
+        self.propose().await.map_err(Into::into)
     }
 
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client"))]
     pub async fn get_head_block_index(&mut self) -> anyhow::Result<u64> {
         let mut stream = self
-            .deploy_client
-            .show_main_chain(BlocksQuery { depth: 1 })
+            .deploy_service(BlocksQuery { depth: 1 }, |mut client, request| async move {
+                client.show_main_chain(request).await
+            })
             .await?
             .into_inner();
 
@@ -208,26 +496,213 @@ impl WriteNodeClient {
             })
     }
 
-    pub async fn get_channel_value<T>(
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client", %deploy_id))]
+    pub async fn find_deploy(
+        &mut self,
+        deploy_id: &DeployId,
+    ) -> anyhow::Result<Option<LightBlock>> {
+        let deploy_id = hex::decode(deploy_id.as_ref()).context("invalid deploy_id")?;
+
+        let resp = self
+            .deploy_service(
+                FindDeployQuery { deploy_id },
+                |mut client, request| async move { client.find_deploy(request).await },
+            )
+            .await
+            .context("find_deploy grpc error")?
+            .into_inner()
+            .message
+            .context("missing find_deploy responce")?;
+
+        match resp {
+            find_deploy_response::Message::BlockInfo(block_info) => Ok(Some(block_info.into())),
+            find_deploy_response::Message::Error(err)
+                if err
+                    .messages
+                    .iter()
+                    .any(|message| message.contains(DEPLOY_NOT_FOUND_ERROR)) =>
+            {
+                Ok(None)
+            }
+            find_deploy_response::Message::Error(err) => Err(anyhow!("find_deploy error: {err:?}")),
+        }
+    }
+
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client", %block_id))]
+    pub async fn is_finalized(&mut self, block_id: &BlockId) -> anyhow::Result<bool> {
+        let resp = self
+            .deploy_service(
+                IsFinalizedQuery {
+                    hash: block_id.to_string(),
+                },
+                |mut client, request| async move { client.is_finalized(request).await },
+            )
+            .await
+            .context("is_finalized grpc error")?
+            .into_inner()
+            .message
+            .context("missing is_finalized responce")?;
+
+        match resp {
+            is_finalized_response::Message::IsFinalized(is_finalized) => Ok(is_finalized),
+            is_finalized_response::Message::Error(err) => {
+                Err(anyhow!("is_finalized error: {err:?}"))
+            }
+        }
+    }
+
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client"))]
+    pub async fn last_finalized_block(&mut self) -> anyhow::Result<Block> {
+        let resp = self
+            .deploy_service(
+                LastFinalizedBlockQuery {},
+                |mut client, request| async move { client.last_finalized_block(request).await },
+            )
+            .await
+            .context("last_finalized_block grpc error")?
+            .into_inner()
+            .message
+            .context("missing last_finalized_block responce")?;
+
+        match resp {
+            last_finalized_block_response::Message::BlockInfo(block_info) => {
+                block_info.try_into().map_err(Into::into)
+            }
+            last_finalized_block_response::Message::Error(err) => {
+                Err(anyhow!("last_finalized_block error: {err:?}"))
+            }
+        }
+    }
+
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client", %block_id))]
+    pub async fn get_block(&mut self, block_id: &BlockId) -> anyhow::Result<Block> {
+        let resp = self
+            .deploy_service(
+                BlockQuery {
+                    hash: block_id.to_string(),
+                },
+                |mut client, request| async move { client.get_block(request).await },
+            )
+            .await
+            .context("get_block grpc error")?
+            .into_inner()
+            .message
+            .context("missing get_block responce")?;
+
+        match resp {
+            block_response::Message::BlockInfo(block_info) => {
+                block_info.try_into().map_err(Into::into)
+            }
+            block_response::Message::Error(err) => Err(anyhow!("get_block error: {err:?}")),
+        }
+    }
+
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client"))]
+    pub async fn get_blocks_by_heights(
         &mut self,
-        hash: BlockId,
-        channel: String,
-    ) -> anyhow::Result<T>
+        start_block_number: u64,
+        end_block_number: u64,
+    ) -> anyhow::Result<Vec<LightBlock>> {
+        self.deploy_service(
+            BlocksQueryByHeight {
+                start_block_number: start_block_number as _,
+                end_block_number: end_block_number as _,
+            },
+            |mut client, request| async move { client.get_blocks_by_heights(request).await },
+        )
+        .await
+        .context("get_blocks_by_heights grpc error")?
+        .into_inner()
+        .map_err(Into::into)
+        .and_then(|resp| async move {
+            match resp
+                .message
+                .context("missing get_blocks_by_heights responce")?
+            {
+                block_info_response::Message::BlockInfo(block_info) => Ok(block_info.into()),
+                block_info_response::Message::Error(err) => {
+                    Err(anyhow!("get_blocks_by_heights error: {err:?}"))
+                }
+            }
+        })
+        .try_collect()
+        .await
+    }
+
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client"))]
+    pub async fn status(&mut self) -> anyhow::Result<NodeStatus> {
+        let resp = self
+            .deploy_service((), |mut client, request| async move {
+                client.status(request).await
+            })
+            .await
+            .context("status grpc error")?
+            .into_inner()
+            .message
+            .context("missing status responce")?;
+
+        match resp {
+            status_response::Message::Status(status) => Ok(status.into()),
+            status_response::Message::Error(err) => Err(anyhow!("status error: {err:?}")),
+        }
+    }
+
+    /// Unforgeable names a deploy by `deployer` with `timestamp` will create, in the order the
+    /// deploy allocates them: the first one is the first name bound by its top-level `new`.
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client"))]
+    pub async fn preview_private_names(
+        &mut self,
+        deployer: &PublicKey,
+        timestamp: DateTime<Utc>,
+        count: u32,
+    ) -> anyhow::Result<Vec<UnforgeableName>> {
+        let resp = self
+            .deploy_service(
+                PrivateNamePreviewQuery {
+                    user: deployer.serialize_uncompressed().into(),
+                    timestamp: timestamp.timestamp_millis(),
+                    name_qty: count.try_into().context("too many names to preview")?,
+                },
+                |mut client, request| async move { client.preview_private_names(request).await },
+            )
+            .await
+            .context("preview_private_names grpc error")?
+            .into_inner()
+            .message
+            .context("missing preview_private_names responce")?;
+
+        match resp {
+            private_name_preview_response::Message::Payload(payload) => Ok(payload
+                .ids
+                .into_iter()
+                .map(|id| UnforgeableName::Private(hex::encode(id)))
+                .collect()),
+            private_name_preview_response::Message::Error(err) => {
+                Err(anyhow!("preview_private_names error: {err:?}"))
+            }
+        }
+    }
+
+    #[tracing::instrument(level = "info", skip_all, fields(otel.kind = "client", %hash))]
+    pub async fn get_channel_value<T, C>(&mut self, hash: BlockId, channel: C) -> anyhow::Result<T>
     where
-        T: FromExpr,
+        T: crate::helpers::FromExpr,
+        C: IntoValue,
     {
-        let mut par = Par::default();
-        par.exprs.push(Expr {
-            expr_instance: Some(ExprInstance::GString(channel)),
-        });
+        let par: Par = channel
+            .into_value()
+            .try_into()
+            .context("invalid channel name")?;
 
         let resp = self
-            .deploy_client
-            .get_data_at_name(DataAtNameByBlockQuery {
-                par: Some(par),
-                block_hash: hash.into(),
-                use_pre_state_hash: false,
-            })
+            .deploy_service(
+                DataAtNameByBlockQuery {
+                    par: Some(par),
+                    block_hash: hash.into(),
+                    use_pre_state_hash: false,
+                },
+                |mut client, request| async move { client.get_data_at_name(request).await },
+            )
             .await
             .context("get_data_at_name grpc error")?
             .into_inner()
@@ -246,15 +721,93 @@ impl WriteNodeClient {
             .into_iter()
             .next_back()
             .context("missing par in get_data_at_name")?;
-        let expr = par
-            .exprs
-            .into_iter()
-            .next()
-            .context("missing exprs in get_data_at_name")?;
-        let expr = expr
-            .expr_instance
-            .context("missing expr_instance in get_data_at_name")?;
 
-        T::from(expr)
+        T::from_par(par).context("failed to decode get_data_at_name value")
+    }
+}
+
+/// Whether `err` was raised while connecting to the validator, so that it never received the call
+/// and sending it again can't run it twice.
+pub fn never_sent(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if err.is::<ConnectError>() {
+            return true;
+        }
+        source = err.source();
+    }
+    false
+}
+
+/// Records the latency and failure reason of a propose call.
+async fn record_propose<T>(
+    call: &'static str,
+    propose: impl Future<Output = Result<T, ProposeError>>,
+) -> Result<T, ProposeError> {
+    let started = Instant::now();
+    let result = propose.await;
+    metrics::record_propose(
+        call,
+        started,
+        result.as_ref().err().map(ProposeError::reason),
+    );
+    result
+}
+
+fn created_block(result: &str) -> Result<BlockId, ProposeError> {
+    result
+        .strip_prefix("Success! Block ")
+        .and_then(|block_hash| block_hash.strip_suffix(" created and added."))
+        .map(|id| BlockId::from(id.to_owned()))
+        .ok_or(ProposeError::InvalidResponse(
+            "failed to extract block hash",
+        ))
+}
+
+#[async_trait]
+impl DeployTransport for WriteNodeClient {
+    async fn deploy(
+        &self,
+        signer: &dyn Signer,
+        deploy_data: DeployData,
+    ) -> anyhow::Result<DeployId> {
+        Self::deploy(&mut self.clone(), signer, deploy_data).await
+    }
+
+    async fn deploy_signed_contract(&self, contract: SignedCode) -> anyhow::Result<DeployId> {
+        Self::deploy_signed_contract(&mut self.clone(), contract).await
+    }
+
+    async fn get_head_block_index(&self) -> anyhow::Result<u64> {
+        Self::get_head_block_index(&mut self.clone()).await
+    }
+
+    async fn find_deploy(&self, deploy_id: &DeployId) -> anyhow::Result<Option<LightBlock>> {
+        Self::find_deploy(&mut self.clone(), deploy_id).await
+    }
+
+    async fn is_finalized(&self, block_id: &BlockId) -> anyhow::Result<bool> {
+        Self::is_finalized(&mut self.clone(), block_id).await
+    }
+
+    async fn get_block(&self, block_id: &BlockId) -> anyhow::Result<Block> {
+        Self::get_block(&mut self.clone(), block_id).await
+    }
+
+    async fn preview_private_names(
+        &self,
+        deployer: &PublicKey,
+        timestamp: DateTime<Utc>,
+        count: u32,
+    ) -> anyhow::Result<Vec<UnforgeableName>> {
+        Self::preview_private_names(&mut self.clone(), deployer, timestamp, count).await
+    }
+
+    fn is_healthy(&self) -> bool {
+        self.is_healthy()
+    }
+
+    async fn probe(&self, timeout: Duration) -> Vec<ValidatorProbe> {
+        self.probe(timeout).await
     }
 }
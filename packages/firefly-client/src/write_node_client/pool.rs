@@ -0,0 +1,217 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use serde::Deserialize;
+use tracing::{Instrument, info, warn};
+
+use crate::connection::{ConnectionOptions, GrpcChannel};
+use crate::models::casper::v1::deploy_service_client::DeployServiceClient;
+use crate::models::casper::v1::propose_service_client::ProposeServiceClient;
+use crate::models::casper::v1::status_response;
+
+/// gRPC endpoints of a single validator.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ValidatorEndpoint {
+    pub deploy_service_url: String,
+    pub propose_service_url: String,
+}
+
+/// Outcome of calling `status` on both channels of a validator.
+#[derive(Debug, Clone)]
+pub struct ValidatorProbe {
+    pub endpoint: ValidatorEndpoint,
+    /// Whether requests currently go to this validator.
+    pub active: bool,
+    pub deploy: Result<(), String>,
+    pub propose: Result<(), String>,
+}
+
+pub(super) struct Node {
+    pub(super) endpoint: ValidatorEndpoint,
+    pub(super) deploy_client: DeployServiceClient<GrpcChannel>,
+    pub(super) propose_client: ProposeServiceClient<GrpcChannel>,
+    /// The propose service has no `status` RPC, so the propose channel is probed through the
+    /// deploy service the validator serves next to it.
+    propose_status_client: DeployServiceClient<GrpcChannel>,
+    healthy: AtomicBool,
+}
+
+impl Node {
+    pub(super) fn new(
+        endpoint: ValidatorEndpoint,
+        deploy_channel: GrpcChannel,
+        propose_channel: GrpcChannel,
+    ) -> Self {
+        Self {
+            endpoint,
+            deploy_client: DeployServiceClient::new(deploy_channel),
+            propose_client: ProposeServiceClient::new(propose_channel.clone()),
+            propose_status_client: DeployServiceClient::new(propose_channel),
+            healthy: AtomicBool::new(true),
+        }
+    }
+
+    /// Node whose channels connect on first use and which counts as unhealthy until the first
+    /// successful health check.
+    pub(super) fn lazy(
+        endpoint: ValidatorEndpoint,
+        options: &ConnectionOptions,
+    ) -> anyhow::Result<Self> {
+        let deploy_channel = options.grpc_channel(endpoint.deploy_service_url.clone())?;
+        let propose_channel = options.grpc_channel(endpoint.propose_service_url.clone())?;
+
+        Ok(Self {
+            healthy: AtomicBool::new(false),
+            ..Self::new(endpoint, deploy_channel, propose_channel)
+        })
+    }
+
+    async fn check(&self, timeout: Duration) -> bool {
+        let healthy = call_status(self.deploy_client.clone(), timeout)
+            .await
+            .is_ok();
+
+        if self.healthy.swap(healthy, Ordering::Relaxed) != healthy {
+            if healthy {
+                info!(endpoint = ?self.endpoint, "validator is healthy");
+            } else {
+                warn!(endpoint = ?self.endpoint, "validator is unhealthy");
+            }
+        }
+
+        healthy
+    }
+
+    async fn probe(&self, timeout: Duration, active: bool) -> ValidatorProbe {
+        let (deploy, propose) = futures::join!(
+            call_status(self.deploy_client.clone(), timeout),
+            call_status(self.propose_status_client.clone(), timeout),
+        );
+
+        ValidatorProbe {
+            endpoint: self.endpoint.clone(),
+            active,
+            deploy,
+            propose,
+        }
+    }
+}
+
+async fn call_status(
+    mut client: DeployServiceClient<GrpcChannel>,
+    timeout: Duration,
+) -> Result<(), String> {
+    match tokio::time::timeout(timeout, client.status(())).await {
+        Ok(Ok(resp)) => match resp.into_inner().message {
+            Some(status_response::Message::Status(_)) => Ok(()),
+            Some(status_response::Message::Error(err)) => Err(err.messages.join("; ")),
+            None => Err("missing status responce".to_owned()),
+        },
+        Ok(Err(status)) => Err(status.message().to_owned()),
+        Err(_) => Err(format!("no answer within {timeout:?}")),
+    }
+}
+
+/// Validators a [`super::WriteNodeClient`] talks to. Every request goes to the active node, which
+/// moves on to the next healthy one when it fails.
+pub(super) struct NodePool {
+    nodes: Vec<Node>,
+    active: AtomicUsize,
+}
+
+impl NodePool {
+    pub(super) const fn new(nodes: Vec<Node>) -> Self {
+        Self {
+            nodes,
+            active: AtomicUsize::new(0),
+        }
+    }
+
+    pub(super) const fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub(super) fn node(&self, index: usize) -> &Node {
+        &self.nodes[index]
+    }
+
+    /// Index of the node to use: the active one while it is healthy, otherwise the next healthy
+    /// one, falling back to the active one when no node is healthy.
+    pub(super) fn active(&self) -> usize {
+        let active = self.active.load(Ordering::Relaxed);
+        let next = (0..self.nodes.len())
+            .map(|offset| (active + offset) % self.nodes.len())
+            .find(|&index| self.nodes[index].healthy.load(Ordering::Relaxed))
+            .unwrap_or(active);
+
+        if next != active
+            && self
+                .active
+                .compare_exchange(active, next, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+        {
+            info!(endpoint = ?self.nodes[next].endpoint, "switched to validator");
+        }
+
+        next
+    }
+
+    /// Takes the node out of rotation until the next successful health check.
+    pub(super) fn mark_unhealthy(&self, index: usize) {
+        self.nodes[index].healthy.store(false, Ordering::Relaxed);
+        let _ = self.active.compare_exchange(
+            index,
+            (index + 1) % self.nodes.len(),
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+        );
+    }
+
+    pub(super) fn is_healthy(&self) -> bool {
+        self.nodes
+            .iter()
+            .any(|node| node.healthy.load(Ordering::Relaxed))
+    }
+
+    /// Checks every node concurrently and returns whether any of them is healthy.
+    pub(super) async fn check(&self, timeout: Duration) -> bool {
+        futures::future::join_all(self.nodes.iter().map(|node| node.check(timeout)))
+            .await
+            .into_iter()
+            .any(|healthy| healthy)
+    }
+
+    /// Calls `status` on the channels of every node concurrently.
+    pub(super) async fn probe(&self, timeout: Duration) -> Vec<ValidatorProbe> {
+        let active = self.active.load(Ordering::Relaxed);
+        futures::future::join_all(
+            self.nodes
+                .iter()
+                .enumerate()
+                .map(|(index, node)| node.probe(timeout, index == active)),
+        )
+        .await
+    }
+
+    /// Keeps health-checking the nodes every `interval` for as long as a client holds the pool.
+    pub(super) fn spawn_health_checks(self: &Arc<Self>, interval: Duration, timeout: Duration) {
+        let pool = Arc::downgrade(self);
+
+        tokio::spawn(
+            async move {
+                let mut ticker = tokio::time::interval(interval);
+                ticker.tick().await;
+
+                loop {
+                    ticker.tick().await;
+                    let Some(pool) = pool.upgrade() else {
+                        return;
+                    };
+                    pool.check(timeout).await;
+                }
+            }
+            .in_current_span(),
+        );
+    }
+}
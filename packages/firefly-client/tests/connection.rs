@@ -0,0 +1,69 @@
+use firefly_client::{
+    ConnectionOptions,
+    NodeEvents,
+    ReadNodeClient,
+    ValidatorEndpoint,
+    WriteNodeClient,
+};
+
+const URL: &str = "http://127.0.0.1:1";
+
+fn options(name: &str, value: &str) -> ConnectionOptions {
+    ConnectionOptions {
+        headers: [(name.to_owned(), value.to_owned())].into(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_headers() {
+    let headers = options("X-Api-Key", "secret").headers().unwrap();
+
+    assert_eq!(headers.len(), 1);
+    assert_eq!(headers["x-api-key"], "secret");
+}
+
+#[test]
+fn test_headers_rejects_invalid_headers() {
+    for (name, value) in [
+        ("x api key", "secret"),
+        ("", "secret"),
+        ("x-api-key", "line\nbreak"),
+        ("x-api-key", "naïve"),
+        ("x-token-bin", "c2VjcmV0"),
+    ] {
+        assert!(
+            options(name, value).headers().is_err(),
+            "{name:?}: {value:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn test_clients_reject_invalid_headers() {
+    let options = options("x-api-key", "naïve");
+
+    assert!(ReadNodeClient::with_options(URL.to_owned(), &options).is_err());
+    assert!(
+        ReadNodeClient::grpc(URL.to_owned(), &options)
+            .await
+            .is_err()
+    );
+    assert!(NodeEvents::with_options(URL, options.clone(), None).is_err());
+    assert!(
+        WriteNodeClient::with_options(URL.to_owned(), URL.to_owned(), options.clone())
+            .await
+            .is_err()
+    );
+    assert!(
+        WriteNodeClient::builder()
+            .endpoints(vec![ValidatorEndpoint {
+                deploy_service_url: URL.to_owned(),
+                propose_service_url: URL.to_owned(),
+            }])
+            .options(options)
+            .connect()
+            .await
+            .is_err()
+    );
+}
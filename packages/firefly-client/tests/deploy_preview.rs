@@ -0,0 +1,76 @@
+use std::str::FromStr;
+
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use firefly_client::DeployPreview;
+use firefly_client::models::SignedCode;
+use firefly_client::models::casper::DeployDataProto;
+use prost::Message as _;
+use secp256k1::{Message, Secp256k1, SecretKey};
+
+const KEY: &str = "f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc";
+
+fn prepared() -> Vec<u8> {
+    DeployDataProto {
+        term: "Nil".to_owned(),
+        timestamp: 1_700_000_000_000,
+        phlo_price: 1,
+        phlo_limit: 50_000,
+        valid_after_block_number: 42,
+        shard_id: "root".to_owned(),
+        ..Default::default()
+    }
+    .encode_to_vec()
+}
+
+fn sign(contract: Vec<u8>) -> SignedCode {
+    let secp = Secp256k1::new();
+    let key = SecretKey::from_str(KEY).unwrap();
+    let hash = Blake2b::<U32>::new().chain_update(&contract).finalize();
+
+    SignedCode {
+        sig: secp
+            .sign_ecdsa(Message::from_digest(hash.into()), &key)
+            .serialize_der()
+            .to_vec(),
+        sig_algorithm: "secp256k1".to_owned(),
+        deployer: key.public_key(&secp).serialize_uncompressed().to_vec(),
+        contract,
+    }
+}
+
+#[test]
+fn test_preview_prepared_contract() {
+    let preview = DeployPreview::decode(&prepared()).unwrap();
+
+    assert_eq!(preview.term, "Nil");
+    assert_eq!(preview.timestamp.timestamp_millis(), 1_700_000_000_000);
+    assert_eq!(preview.phlo_limit, 50_000);
+    assert_eq!(preview.phlo_price, 1);
+    assert_eq!(preview.shard_id, "root");
+    assert_eq!(preview.valid_after_block_number, 42);
+    assert!(preview.deployer.is_none());
+}
+
+#[test]
+fn test_preview_signed_contract_verifies_signature() {
+    let signed = sign(prepared());
+    let key = SecretKey::from_str(KEY).unwrap();
+
+    let deployer = DeployPreview::try_from(signed.clone())
+        .unwrap()
+        .deployer
+        .unwrap();
+    assert_eq!(deployer.public_key, key.public_key(&Secp256k1::new()));
+    assert_eq!(deployer.wallet_address, deployer.public_key.into());
+    assert!(deployer.signature_valid);
+
+    let mut tampered = signed;
+    tampered.contract = DeployDataProto {
+        phlo_limit: 1,
+        ..tampered.deploy_data().unwrap()
+    }
+    .encode_to_vec();
+    let deployer = DeployPreview::try_from(tampered).unwrap().deployer.unwrap();
+    assert!(!deployer.signature_valid);
+}
@@ -0,0 +1,177 @@
+use std::collections::BTreeSet;
+
+use firefly_client::helpers::FromExpr;
+use firefly_client::models::rhoapi::expr::ExprInstance;
+use firefly_client::models::rhoapi::{EList, EMap, ESet, ETuple, Expr, KeyValuePair, Par};
+
+fn par(expr: ExprInstance) -> Par {
+    Par {
+        exprs: vec![Expr {
+            expr_instance: Some(expr),
+        }],
+        ..Default::default()
+    }
+}
+
+fn string(value: &str) -> ExprInstance {
+    ExprInstance::GString(value.to_owned())
+}
+
+fn tuple(values: Vec<Par>) -> ExprInstance {
+    ExprInstance::ETupleBody(ETuple {
+        ps: values,
+        ..Default::default()
+    })
+}
+
+fn map(entries: Vec<(&str, Par)>) -> ExprInstance {
+    ExprInstance::EMapBody(EMap {
+        kvs: entries
+            .into_iter()
+            .map(|(key, value)| KeyValuePair {
+                key: Some(par(string(key))),
+                value: Some(value),
+            })
+            .collect(),
+        ..Default::default()
+    })
+}
+
+#[derive(Debug, PartialEq, Eq, FromExpr)]
+struct Agent {
+    id: String,
+    #[from_expr(rename = "lastVersion")]
+    last_version: u32,
+    description: Option<String>,
+    #[from_expr(default)]
+    tags: Vec<String>,
+    #[from_expr(default = "default_shard")]
+    shard: String,
+}
+
+fn default_shard() -> String {
+    "root".to_owned()
+}
+
+#[derive(Debug, PartialEq, Eq, FromExpr)]
+struct Version(i64);
+
+#[derive(Debug, PartialEq, Eq, FromExpr)]
+struct Point(i32, i32);
+
+#[derive(Debug, PartialEq, Eq, FromExpr)]
+enum Status {
+    Pending,
+    #[from_expr(rename = "done")]
+    Done(bool),
+    Moved(i32, i32),
+    Failed {
+        reason: String,
+    },
+}
+
+#[test]
+fn test_derive_struct_from_map() {
+    let value = map(vec![
+        ("id", par(string("agent"))),
+        ("lastVersion", par(ExprInstance::GInt(3))),
+        ("description", Par::default()),
+    ]);
+
+    let agent: Agent = FromExpr::from(value).unwrap();
+
+    assert_eq!(
+        agent,
+        Agent {
+            id: "agent".to_owned(),
+            last_version: 3,
+            description: None,
+            tags: vec![],
+            shard: "root".to_owned(),
+        }
+    );
+}
+
+#[test]
+fn test_derive_struct_reports_missing_field() {
+    let value = map(vec![("id", par(string("agent")))]);
+
+    let err = <Agent as FromExpr>::from(value).unwrap_err();
+
+    assert_eq!(err.to_string(), "missing field `lastVersion`");
+}
+
+#[test]
+fn test_derive_newtype_and_tuple_struct() {
+    let version: Version = FromExpr::from(ExprInstance::GInt(7)).unwrap();
+    assert_eq!(version, Version(7));
+
+    let point: Point = FromExpr::from(tuple(vec![
+        par(ExprInstance::GInt(1)),
+        par(ExprInstance::GInt(-2)),
+    ]))
+    .unwrap();
+    assert_eq!(point, Point(1, -2));
+}
+
+#[test]
+fn test_derive_enum() {
+    let pending: Status = FromExpr::from(string("Pending")).unwrap();
+    assert_eq!(pending, Status::Pending);
+
+    let done: Status = FromExpr::from(tuple(vec![
+        par(string("done")),
+        par(ExprInstance::GBool(true)),
+    ]))
+    .unwrap();
+    assert_eq!(done, Status::Done(true));
+
+    let moved: Status = FromExpr::from(tuple(vec![
+        par(string("Moved")),
+        par(tuple(vec![
+            par(ExprInstance::GInt(1)),
+            par(ExprInstance::GInt(2)),
+        ])),
+    ]))
+    .unwrap();
+    assert_eq!(moved, Status::Moved(1, 2));
+
+    let failed: Status = FromExpr::from(tuple(vec![
+        par(string("Failed")),
+        par(map(vec![("reason", par(string("out of phlo")))])),
+    ]))
+    .unwrap();
+    assert_eq!(
+        failed,
+        Status::Failed {
+            reason: "out of phlo".to_owned()
+        }
+    );
+
+    assert!(<Status as FromExpr>::from(string("Unknown")).is_err());
+}
+
+#[test]
+fn test_builtin_impls() {
+    let value: Option<u64> = FromExpr::from_par(Par::default()).unwrap();
+    assert_eq!(value, None);
+
+    assert!(<u16 as FromExpr>::from(ExprInstance::GInt(-1)).is_err());
+
+    let set: BTreeSet<i64> = FromExpr::from(ExprInstance::ESetBody(ESet {
+        ps: vec![par(ExprInstance::GInt(2)), par(ExprInstance::GInt(1))],
+        ..Default::default()
+    }))
+    .unwrap();
+    assert_eq!(set, [1, 2].into_iter().collect());
+
+    let list: Vec<(String, bool)> = FromExpr::from(ExprInstance::EListBody(EList {
+        ps: vec![par(tuple(vec![
+            par(string("a")),
+            par(ExprInstance::GBool(false)),
+        ]))],
+        ..Default::default()
+    }))
+    .unwrap();
+    assert_eq!(list, vec![("a".to_owned(), false)]);
+}
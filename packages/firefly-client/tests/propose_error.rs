@@ -0,0 +1,36 @@
+use firefly_client::errors::ProposeError;
+use firefly_client::models::servicemodelapi::ServiceError;
+
+fn propose_error(message: &str) -> ProposeError {
+    ServiceError {
+        messages: vec![message.to_owned()],
+    }
+    .into()
+}
+
+#[test]
+fn test_propose_error_from_service_error() {
+    assert!(matches!(
+        propose_error("Error: Another propose is in progress"),
+        ProposeError::Busy
+    ));
+    assert!(matches!(
+        propose_error("Proposal failed: NoNewDeploys"),
+        ProposeError::NoNewDeploys
+    ));
+    assert!(matches!(
+        propose_error("Proposal failed: NotBonded"),
+        ProposeError::NotBonded
+    ));
+    assert!(matches!(
+        propose_error("Proposal failed: NotEnoughNewBlocks"),
+        ProposeError::NotEnoughNewBlocks
+    ));
+    assert!(matches!(
+        propose_error("Proposal failed: BugError"),
+        ProposeError::Failed(messages) if messages == ["Proposal failed: BugError"]
+    ));
+
+    assert!(propose_error("another propose is in progress").is_retryable());
+    assert!(!propose_error("NotBonded").is_retryable());
+}
@@ -0,0 +1,152 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use firefly_client::models::rhoapi::expr::ExprInstance;
+use firefly_client::models::rhoapi::g_unforgeable::UnfInstance;
+use firefly_client::models::rhoapi::{EMap, Expr, GPrivate, GUnforgeable, KeyValuePair, Par};
+use firefly_client::models::{ReadNodeExpr, UnforgeableName, Uri};
+use firefly_client::read_node_expr::from_expr;
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use serde::Deserialize;
+use serde_json::json;
+
+fn uri() -> Uri {
+    let key =
+        SecretKey::from_str("f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc")
+            .unwrap();
+    PublicKey::from_secret_key(&Secp256k1::new(), &key).into()
+}
+
+fn par(expr: ExprInstance) -> Par {
+    Par {
+        exprs: vec![Expr {
+            expr_instance: Some(expr),
+        }],
+        ..Default::default()
+    }
+}
+
+fn expr(value: serde_json::Value) -> ReadNodeExpr {
+    serde_json::from_value(value).unwrap()
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize)]
+struct Entry {
+    payload: Vec<u8>,
+    payload_hex: String,
+    tags: BTreeSet<String>,
+    uri: Uri,
+    name: UnforgeableName,
+    parent: Option<String>,
+}
+
+#[test]
+fn test_deserialize_keeps_expr_types() {
+    let uri = uri();
+    let value = expr(json!({
+        "ExprMap": {"data": {
+            "payload": {"ExprBytes": {"data": "cafe"}},
+            "payload_hex": {"ExprBytes": {"data": "cafe"}},
+            "tags": {"ExprSet": {"data": [
+                {"ExprString": {"data": "b"}},
+                {"ExprString": {"data": "a"}},
+            ]}},
+            "uri": {"ExprUri": {"data": uri.as_ref()}},
+            "name": {"ExprUnforg": {"data": {"UnforgPrivate": {"data": "abcd"}}}},
+            "parent": {"ExprNil": {}},
+        }}
+    }));
+
+    let entry: Entry = from_expr(value).unwrap();
+
+    assert_eq!(
+        entry,
+        Entry {
+            payload: vec![0xca, 0xfe],
+            payload_hex: "cafe".to_owned(),
+            tags: BTreeSet::from(["a".to_owned(), "b".to_owned()]),
+            uri,
+            name: UnforgeableName::Private("abcd".to_owned()),
+            parent: None,
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_rejects_string_as_uri() {
+    let value = expr(json!({"ExprString": {"data": "rho:id:not-really"}}));
+
+    assert!(from_expr::<Uri>(value).is_err());
+}
+
+#[test]
+fn test_deserialize_error_reports_path() {
+    #[derive(Debug, Deserialize)]
+    #[allow(dead_code)]
+    struct Agent {
+        name: String,
+        version: i64,
+    }
+
+    let value = expr(json!({
+        "ExprList": {"data": [
+            {"ExprMap": {"data": {
+                "name": {"ExprString": {"data": "first"}},
+                "version": {"ExprInt": {"data": 1}},
+            }}},
+            {"ExprMap": {"data": {
+                "name": {"ExprString": {"data": "second"}},
+                "version": {"ExprString": {"data": "2"}},
+            }}},
+        ]}
+    }));
+
+    let err = from_expr::<Vec<Agent>>(value).unwrap_err();
+
+    assert_eq!(err.path.as_deref(), Some("$[1].version"));
+}
+
+#[test]
+fn test_deserialize_from_grpc_par() {
+    #[derive(Debug, PartialEq, Eq, Deserialize)]
+    struct Entry {
+        payload: Vec<u8>,
+        name: UnforgeableName,
+        parent: Option<String>,
+    }
+
+    let entries = [
+        ("payload", par(ExprInstance::GByteArray(vec![0xca, 0xfe]))),
+        (
+            "name",
+            Par {
+                unforgeables: vec![GUnforgeable {
+                    unf_instance: Some(UnfInstance::GPrivateBody(GPrivate { id: vec![0xab] })),
+                }],
+                ..Default::default()
+            },
+        ),
+        ("parent", Par::default()),
+    ];
+    let value = par(ExprInstance::EMapBody(EMap {
+        kvs: entries
+            .into_iter()
+            .map(|(key, value)| KeyValuePair {
+                key: Some(par(ExprInstance::GString(key.to_owned()))),
+                value: Some(value),
+            })
+            .collect(),
+        ..Default::default()
+    }));
+
+    let entry: Entry = from_expr(ReadNodeExpr::try_from(value).unwrap()).unwrap();
+
+    assert_eq!(
+        entry,
+        Entry {
+            payload: vec![0xca, 0xfe],
+            name: UnforgeableName::Private("ab".to_owned()),
+            parent: None,
+        }
+    );
+}
@@ -2,7 +2,9 @@ use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use firefly_client::models::Uri;
+use firefly_client::models::rhoapi::Par;
 use firefly_client::rendering::*;
+use proptest::prelude::*;
 use secp256k1::PublicKey;
 
 #[test]
@@ -216,6 +218,118 @@ fn test_serialize_str_is_escaped() {
     }
 }
 
+#[test]
+fn test_serialize_str_escapes_control_characters() {
+    let cases = [
+        ("a\nb\tc", "\"a\\nb\\tc\""),
+        ("a\rb\0c", "\"a\rb\0c\""),
+        ("a\u{202e}b", "\"a\u{202e}b\""),
+        ("zażółć", "\"zażółć\""),
+    ];
+    for (value, expected) in cases {
+        let result = value.into_value();
+        assert_eq!(result.to_string(), expected);
+    }
+}
+
+#[test]
+fn test_check_rejects_unprintable_characters() {
+    for c in ['\0', '\u{c}', '\u{85}', '\u{200b}', '\u{202e}', '\u{feff}'] {
+        let string = format!("a{c}b");
+        let values = [
+            Value::String(string.clone()),
+            Value::Uri(string.clone()),
+            Value::Inline(string.clone()),
+            Value::List(vec![Value::Nil, Value::String(string.clone())]),
+            Value::Map([(string.clone(), Value::Nil)].into()),
+        ];
+        for value in values {
+            assert_eq!(value.check(), Err(UnprintableChar(c)), "{value:?}");
+        }
+        assert!(template_value(string).is_err());
+    }
+
+    let value = Value::Map([("a\nb\tc\r\n".to_owned(), "zażółć".into_value())].into());
+    assert_eq!(value.check(), Ok(()));
+    assert_eq!(template_value(value.clone()).unwrap(), value);
+}
+
+#[test]
+fn test_serialize_uri_is_escaped() {
+    let result = Value::Uri("rho:`a\\b`".to_owned());
+    assert_eq!(result.to_string(), "`rho:\\`a\\\\b\\``");
+}
+
+#[test]
+fn test_name_rejects_non_identifiers() {
+    for name in ["", "_", "1a", "a b", "a!(b)", "new", "ż"] {
+        assert!(Name::new(name).is_err(), "{name:?}");
+    }
+    assert!(Name::new("channel0Output").is_ok());
+}
+
+#[test]
+fn test_render_term() {
+    let channel = Name::new("ch").unwrap();
+    let value = Name::new("value").unwrap();
+    let stdout = Name::new("stdout").unwrap();
+
+    let term = Term::new(
+        [
+            NewName::from(channel.clone()),
+            NewName::system(stdout.clone(), "rho:io:stdout"),
+        ],
+        Term::par([
+            Term::send(channel.clone(), [Term::value("a\"b")]),
+            Term::receive(
+                [Bind::process(value.clone(), channel)],
+                Term::match_on(
+                    Term::var(value.clone()),
+                    [
+                        (Pattern::Value(Value::Nil), Term::Nil),
+                        (Pattern::Wildcard, Term::send(stdout, [Term::var(value)])),
+                    ],
+                ),
+            ),
+        ]),
+    );
+
+    let expected = r#"new ch, stdout(`rho:io:stdout`) in {
+    ch!("a\"b") |
+    for(@value <- ch) {
+        match value {
+            Nil => {
+                Nil
+            }
+            _ => {
+                stdout!(value)
+            }
+        }
+    }
+}"#;
+    assert_eq!(term.to_string(), expected);
+}
+
+#[test]
+fn test_render_match_on_par() {
+    let channel = Name::new("ch").unwrap();
+    let term = Term::match_on(
+        Term::par([
+            Term::send(channel.clone(), [Term::Nil]),
+            Term::send(channel, [Term::value(1)]),
+        ]),
+        [(Pattern::Wildcard, Term::Nil)],
+    );
+
+    let expected = "match (ch!(Nil) |
+ch!(1)) {
+    _ => {
+        Nil
+    }
+}";
+    assert_eq!(term.to_string(), expected);
+}
+
 #[test]
 fn test_build_uri_from_public_key() {
     let public_key = PublicKey::from_str("04f4b4417f930e6fab5765ac0defcf9fce169982acfd046e7c27f9b14c0804014623c0439e5c8035e9607599a549303b5b6b90cd9685e6965278bddca65dac7510").unwrap();
@@ -236,3 +350,120 @@ fn test_build_uri_from_string() {
         "rho:id:1qw5ehmq1x49dey4eadr1h4ncm361w3536asho7dr38iyookwcsp6i"
     );
 }
+
+#[test]
+fn test_value_par_roundtrip() {
+    let value = Value::Tuple(vec![
+        Value::Nil,
+        Value::Bool(true),
+        Value::Int(-7),
+        Value::String("channel".to_owned()),
+        Value::Bytes(vec![0xca, 0xfe]),
+        Value::Uri("rho:id:1qw5ehmq1x49dey4eadr1h4ncm361w3536asho7dr38iyookwcsp6i".to_owned()),
+        Value::List(vec![Value::Int(1), Value::Int(2)]),
+        Value::Set([Value::Int(1), Value::Int(2)].into()),
+        Value::Map(BTreeMap::from([("key".to_owned(), Value::Nil)])),
+    ]);
+
+    let par = Par::try_from(value.clone()).unwrap();
+    assert_eq!(Value::try_from(par).unwrap(), value);
+}
+
+#[test]
+fn test_inline_value_is_not_converted_to_par() {
+    let value = Inline::from(Term::eval(Name::new("x").unwrap())).into_value();
+    assert!(Par::try_from(value).is_err());
+}
+
+/// Strings without the control and format characters [`Value::check`] rejects.
+const PRINTABLE: &str = r"[^\x00-\x08\x0B\x0C\x0E-\x1F\x7F-\x9F\x{200B}-\x{200F}\x{202A}-\x{202E}\x{2060}-\x{2064}\x{2066}-\x{2069}\x{FEFF}]*";
+
+/// Strings rendered without loss, i.e. without control or format characters the lexer can't read.
+fn value() -> impl Strategy<Value = Value> {
+    let leaf = prop_oneof![
+        Just(Value::Nil),
+        any::<bool>().prop_map(Value::Bool),
+        any::<i64>().prop_map(Value::Int),
+        PRINTABLE.prop_map(Value::String),
+        any::<Vec<u8>>().prop_map(Value::Bytes),
+        PRINTABLE.prop_map(Value::Uri),
+    ];
+
+    leaf.prop_recursive(4, 64, 8, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..8).prop_map(Value::Tuple),
+            prop::collection::vec(inner.clone(), 0..8).prop_map(Value::List),
+            prop::collection::btree_set(inner.clone(), 0..8).prop_map(Value::Set),
+            prop::collection::btree_map(PRINTABLE, inner, 0..8).prop_map(Value::Map),
+        ]
+    })
+}
+
+proptest! {
+    #[test]
+    fn test_parse_rendered_value_roundtrip(value in value()) {
+        prop_assert_eq!(value.check(), Ok(()));
+        prop_assert_eq!(value.to_string().parse::<Value>(), Ok(value));
+    }
+
+    #[test]
+    fn test_parse_rendered_control_characters_roundtrip(string in "(.|\n|\r|\u{202e})*") {
+        let value = Value::String(string.clone());
+        prop_assert_eq!(value.to_string().parse::<Value>(), Ok(value));
+
+        let uri = Value::Uri(string);
+        prop_assert_eq!(uri.to_string().parse::<Value>(), Ok(uri));
+    }
+}
+
+#[test]
+fn test_parse_value() {
+    let value: Value =
+        r#" ( Nil, [true, -7], Set(1), {"key": "a\"b"}, "cafe".hexToBytes(), `rho:id:x`, ) "#
+            .parse()
+            .unwrap();
+
+    assert_eq!(
+        value,
+        Value::Tuple(vec![
+            Value::Nil,
+            Value::List(vec![Value::Bool(true), Value::Int(-7)]),
+            Value::Set([Value::Int(1)].into()),
+            Value::Map(BTreeMap::from([(
+                "key".to_owned(),
+                Value::String("a\"b".to_owned())
+            )])),
+            Value::Bytes(vec![0xca, 0xfe]),
+            Value::Uri("rho:id:x".to_owned()),
+        ])
+    );
+}
+
+#[test]
+fn test_parse_value_prefix() {
+    let (value, rest) = Value::parse_prefix("(\"boost\", 1, Nil,)\n}").unwrap();
+    assert_eq!(
+        value,
+        Value::Tuple(vec!["boost".into_value(), Value::Int(1), Value::Nil])
+    );
+    assert_eq!(rest, "\n}");
+}
+
+#[test]
+fn test_parse_value_rejects_invalid_input() {
+    let cases = ["", "Nill", "[1, 2", "{1: 2}", "\"zz\".hexToBytes()", "1 2"];
+    for input in cases {
+        assert!(input.parse::<Value>().is_err(), "{input}");
+    }
+}
+
+#[test]
+fn test_parse_value_rejects_escapes_the_lexer_does_not_know() {
+    for (input, position) in [(r#""a\rb""#, 2), (r#""a\fb""#, 2), (r#""\'""#, 1)] {
+        assert_eq!(
+            input.parse::<Value>(),
+            Err(ValueParseError::InvalidEscape(position)),
+            "{input}"
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use std::str::FromStr;
+
+use firefly_client::signer::{
+    Keystore,
+    KeystoreSigner,
+    RemoteSigner,
+    Signer,
+    SignerConfig,
+    serve_unix,
+};
+use secp256k1::SecretKey;
+use tokio::net::UnixListener;
+
+const KEY: &str = "f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc";
+
+fn temp_path(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("firefly-client-{}-{name}", uuid::Uuid::now_v7()))
+}
+
+#[test]
+fn test_keystore_roundtrip() {
+    let key = SecretKey::from_str(KEY).unwrap();
+
+    let keystore = Keystore::encrypt(&key, "password").unwrap();
+
+    assert_eq!(keystore.public_key, Signer::public_key(&key));
+    assert_eq!(keystore.decrypt("password").unwrap(), key);
+    assert!(keystore.decrypt("wrong password").is_err());
+}
+
+#[test]
+fn test_signer_config_debug_skips_keystore_password() {
+    let config = SignerConfig::Keystore {
+        keystore: "keystore.json".into(),
+        password: "hunter2".to_owned(),
+    };
+
+    assert!(!format!("{config:?}").contains("hunter2"));
+}
+
+#[tokio::test]
+async fn test_keystore_signer() {
+    let key = SecretKey::from_str(KEY).unwrap();
+    let path = temp_path("keystore.json");
+
+    Keystore::encrypt(&key, "password")
+        .unwrap()
+        .save(&path)
+        .unwrap();
+    let signer = KeystoreSigner::open(&path, "password").unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(signer.public_key(), Signer::public_key(&key));
+    assert_eq!(
+        signer.sign([7; 32]).await.unwrap(),
+        key.sign([7; 32]).await.unwrap()
+    );
+}
+
+#[tokio::test]
+async fn test_remote_signer_over_unix_socket() {
+    let key = SecretKey::from_str(KEY).unwrap();
+    let path = temp_path("signer.sock");
+
+    let listener = UnixListener::bind(&path).unwrap();
+    let server = tokio::spawn(serve_unix(listener, key));
+
+    let signer = RemoteSigner::connect(&format!("unix:{}", path.display()))
+        .await
+        .unwrap();
+
+    assert_eq!(signer.public_key(), Signer::public_key(&key));
+    assert_eq!(
+        signer.sign([7; 32]).await.unwrap(),
+        key.sign([7; 32]).await.unwrap()
+    );
+
+    server.abort();
+    std::fs::remove_file(&path).unwrap();
+}
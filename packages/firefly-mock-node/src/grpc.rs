@@ -0,0 +1,377 @@
+use std::sync::Arc;
+
+use firefly_client::models::casper::v1::deploy_service_server::DeployService;
+use firefly_client::models::casper::v1::propose_service_server::ProposeService;
+use firefly_client::models::casper::v1::{
+    BlockInfoResponse,
+    BlockResponse,
+    BondStatusResponse,
+    ContinuationAtNameResponse,
+    DeployResponse,
+    EventInfoResponse,
+    ExploratoryDeployResponse,
+    FindDeployResponse,
+    IsFinalizedResponse,
+    LastFinalizedBlockResponse,
+    ListeningNameDataResponse,
+    MachineVerifyResponse,
+    PrivateNamePreviewPayload,
+    PrivateNamePreviewResponse,
+    ProposeResponse,
+    ProposeResultResponse,
+    RhoDataResponse,
+    StatusResponse,
+    VisualizeBlocksResponse,
+    block_info_response,
+    block_response,
+    deploy_response,
+    exploratory_deploy_response,
+    find_deploy_response,
+    is_finalized_response,
+    last_finalized_block_response,
+    private_name_preview_response,
+    propose_response,
+    propose_result_response,
+    status_response,
+};
+use firefly_client::models::casper::{
+    BlockQuery,
+    BlocksQuery,
+    BlocksQueryByHeight,
+    BondStatusQuery,
+    ContinuationAtNameQuery,
+    DataAtNameByBlockQuery,
+    DataAtNameQuery,
+    DataWithBlockInfo,
+    DeployDataProto,
+    ExploratoryDeployQuery,
+    FindDeployQuery,
+    IsFinalizedQuery,
+    LastFinalizedBlockQuery,
+    MachineVerifyQuery,
+    PrivateNamePreviewQuery,
+    ProposeQuery,
+    ProposeResultQuery,
+    ReportQuery,
+    Status,
+    VersionInfo,
+    VisualizeDagQuery,
+};
+use firefly_client::models::servicemodelapi::ServiceError;
+use futures::stream;
+use tonic::{Request, Response};
+
+use crate::state::State;
+
+const MAX_PRIVATE_NAMES: i32 = 1024;
+
+type Stream<T> = stream::Iter<std::vec::IntoIter<Result<T, tonic::Status>>>;
+
+const fn error(messages: Vec<String>) -> ServiceError {
+    ServiceError { messages }
+}
+
+fn unimplemented<T>(method: &str) -> Result<T, tonic::Status> {
+    Err(tonic::Status::unimplemented(format!(
+        "{method} is not supported by the mock node"
+    )))
+}
+
+fn block_stream(
+    blocks: Vec<firefly_client::models::casper::LightBlockInfo>,
+) -> Response<Stream<BlockInfoResponse>> {
+    let blocks: Vec<_> = blocks
+        .into_iter()
+        .map(|block| {
+            Ok(BlockInfoResponse {
+                message: Some(block_info_response::Message::BlockInfo(block)),
+            })
+        })
+        .collect();
+    Response::new(stream::iter(blocks))
+}
+
+/// gRPC deploy and propose services of the node.
+#[derive(Clone)]
+pub struct Services(pub Arc<State>);
+
+impl Services {
+    fn available(&self) -> Result<(), tonic::Status> {
+        if self.0.unavailable() {
+            Err(tonic::Status::unavailable("mock node is unavailable"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl DeployService for Services {
+    type visualizeDagStream = Stream<VisualizeBlocksResponse>;
+    type showMainChainStream = Stream<BlockInfoResponse>;
+    type getBlocksStream = Stream<BlockInfoResponse>;
+    type getBlocksByHeightsStream = Stream<BlockInfoResponse>;
+
+    async fn do_deploy(
+        &self,
+        request: Request<DeployDataProto>,
+    ) -> Result<Response<DeployResponse>, tonic::Status> {
+        self.available()?;
+        let message = match self.0.deploy(request.into_inner()) {
+            Ok(deploy_id) => {
+                deploy_response::Message::Result(format!("Success! DeployId is: {deploy_id}"))
+            }
+            Err(messages) => deploy_response::Message::Error(error(messages)),
+        };
+        Ok(Response::new(DeployResponse {
+            message: Some(message),
+        }))
+    }
+
+    async fn get_block(
+        &self,
+        request: Request<BlockQuery>,
+    ) -> Result<Response<BlockResponse>, tonic::Status> {
+        self.available()?;
+        let hash = request.into_inner().hash;
+        let message = self.0.block(&hash).map_or_else(
+            || {
+                block_response::Message::Error(error(vec![format!(
+                    "Error: Failure to find block with hash: {hash}"
+                )]))
+            },
+            block_response::Message::BlockInfo,
+        );
+        Ok(Response::new(BlockResponse {
+            message: Some(message),
+        }))
+    }
+
+    async fn visualize_dag(
+        &self,
+        _request: Request<VisualizeDagQuery>,
+    ) -> Result<Response<Self::visualizeDagStream>, tonic::Status> {
+        unimplemented("visualizeDag")
+    }
+
+    async fn machine_verifiable_dag(
+        &self,
+        _request: Request<MachineVerifyQuery>,
+    ) -> Result<Response<MachineVerifyResponse>, tonic::Status> {
+        unimplemented("machineVerifiableDag")
+    }
+
+    async fn show_main_chain(
+        &self,
+        request: Request<BlocksQuery>,
+    ) -> Result<Response<Self::showMainChainStream>, tonic::Status> {
+        self.available()?;
+        let depth = request.into_inner().depth.max(0) as _;
+        Ok(block_stream(self.0.head(depth)))
+    }
+
+    async fn get_blocks(
+        &self,
+        request: Request<BlocksQuery>,
+    ) -> Result<Response<Self::getBlocksStream>, tonic::Status> {
+        self.available()?;
+        let depth = request.into_inner().depth.max(0) as _;
+        Ok(block_stream(self.0.head(depth)))
+    }
+
+    async fn listen_for_data_at_name(
+        &self,
+        _request: Request<DataAtNameQuery>,
+    ) -> Result<Response<ListeningNameDataResponse>, tonic::Status> {
+        unimplemented("listenForDataAtName")
+    }
+
+    async fn get_data_at_name(
+        &self,
+        _request: Request<DataAtNameByBlockQuery>,
+    ) -> Result<Response<RhoDataResponse>, tonic::Status> {
+        unimplemented("getDataAtName")
+    }
+
+    async fn listen_for_continuation_at_name(
+        &self,
+        _request: Request<ContinuationAtNameQuery>,
+    ) -> Result<Response<ContinuationAtNameResponse>, tonic::Status> {
+        unimplemented("listenForContinuationAtName")
+    }
+
+    async fn find_deploy(
+        &self,
+        request: Request<FindDeployQuery>,
+    ) -> Result<Response<FindDeployResponse>, tonic::Status> {
+        self.available()?;
+        let deploy_id = request.into_inner().deploy_id;
+        let message = self.0.find_deploy(&deploy_id).map_or_else(
+            || {
+                find_deploy_response::Message::Error(error(vec![format!(
+                    "Couldn't find block containing deploy with id: {}",
+                    hex::encode(&deploy_id)
+                )]))
+            },
+            find_deploy_response::Message::BlockInfo,
+        );
+        Ok(Response::new(FindDeployResponse {
+            message: Some(message),
+        }))
+    }
+
+    async fn preview_private_names(
+        &self,
+        request: Request<PrivateNamePreviewQuery>,
+    ) -> Result<Response<PrivateNamePreviewResponse>, tonic::Status> {
+        self.available()?;
+        let query = request.into_inner();
+        let message = if (0..=MAX_PRIVATE_NAMES).contains(&query.name_qty) {
+            private_name_preview_response::Message::Payload(PrivateNamePreviewPayload {
+                ids: State::preview_private_names(
+                    &query.user,
+                    query.timestamp,
+                    query.name_qty as _,
+                ),
+            })
+        } else {
+            private_name_preview_response::Message::Error(error(vec![format!(
+                "Maximum number of private names is {MAX_PRIVATE_NAMES}"
+            )]))
+        };
+        Ok(Response::new(PrivateNamePreviewResponse {
+            message: Some(message),
+        }))
+    }
+
+    async fn last_finalized_block(
+        &self,
+        _request: Request<LastFinalizedBlockQuery>,
+    ) -> Result<Response<LastFinalizedBlockResponse>, tonic::Status> {
+        self.available()?;
+        let message = self.0.last_finalized().map_or_else(
+            || {
+                last_finalized_block_response::Message::Error(error(vec![
+                    "No finalized block".to_owned(),
+                ]))
+            },
+            last_finalized_block_response::Message::BlockInfo,
+        );
+        Ok(Response::new(LastFinalizedBlockResponse {
+            message: Some(message),
+        }))
+    }
+
+    async fn is_finalized(
+        &self,
+        request: Request<IsFinalizedQuery>,
+    ) -> Result<Response<IsFinalizedResponse>, tonic::Status> {
+        self.available()?;
+        let is_finalized = self.0.is_finalized(&request.into_inner().hash);
+        Ok(Response::new(IsFinalizedResponse {
+            message: Some(is_finalized_response::Message::IsFinalized(is_finalized)),
+        }))
+    }
+
+    async fn bond_status(
+        &self,
+        _request: Request<BondStatusQuery>,
+    ) -> Result<Response<BondStatusResponse>, tonic::Status> {
+        unimplemented("bondStatus")
+    }
+
+    async fn exploratory_deploy(
+        &self,
+        request: Request<ExploratoryDeployQuery>,
+    ) -> Result<Response<ExploratoryDeployResponse>, tonic::Status> {
+        self.available()?;
+        let value = self.0.explore(&request.into_inner().term);
+        let message = match value.map(TryInto::try_into).transpose() {
+            Ok(value) => exploratory_deploy_response::Message::Result(DataWithBlockInfo {
+                post_block_data: value.into_iter().collect(),
+                block: self.0.head(1).into_iter().next(),
+            }),
+            Err(err) => exploratory_deploy_response::Message::Error(error(vec![err.to_string()])),
+        };
+        Ok(Response::new(ExploratoryDeployResponse {
+            message: Some(message),
+        }))
+    }
+
+    async fn get_blocks_by_heights(
+        &self,
+        request: Request<BlocksQueryByHeight>,
+    ) -> Result<Response<Self::getBlocksByHeightsStream>, tonic::Status> {
+        self.available()?;
+        let query = request.into_inner();
+        Ok(block_stream(self.0.blocks_by_heights(
+            query.start_block_number,
+            query.end_block_number,
+        )))
+    }
+
+    async fn get_event_by_hash(
+        &self,
+        _request: Request<ReportQuery>,
+    ) -> Result<Response<EventInfoResponse>, tonic::Status> {
+        unimplemented("getEventByHash")
+    }
+
+    async fn status(
+        &self,
+        _request: Request<()>,
+    ) -> Result<Response<StatusResponse>, tonic::Status> {
+        self.available()?;
+        Ok(Response::new(StatusResponse {
+            message: Some(status_response::Message::Status(Status {
+                version: Some(VersionInfo {
+                    api: "1".to_owned(),
+                    node: "firefly-mock-node".to_owned(),
+                }),
+                shard_id: "root".to_owned(),
+                min_phlo_price: 1,
+                ..Default::default()
+            })),
+        }))
+    }
+}
+
+#[tonic::async_trait]
+impl ProposeService for Services {
+    async fn propose(
+        &self,
+        request: Request<ProposeQuery>,
+    ) -> Result<Response<ProposeResponse>, tonic::Status> {
+        self.available()?;
+        let is_async = request.into_inner().is_async;
+        let message = match self.0.propose() {
+            Ok(_) if is_async => propose_response::Message::Result("Propose started".to_owned()),
+            Ok(block_hash) => propose_response::Message::Result(format!(
+                "Success! Block {block_hash} created and added."
+            )),
+            Err(messages) => propose_response::Message::Error(error(messages)),
+        };
+        Ok(Response::new(ProposeResponse {
+            message: Some(message),
+        }))
+    }
+
+    async fn propose_result(
+        &self,
+        _request: Request<ProposeResultQuery>,
+    ) -> Result<Response<ProposeResultResponse>, tonic::Status> {
+        self.available()?;
+        let message = match self.0.take_propose_result() {
+            Some(Ok(block_hash)) => propose_result_response::Message::Result(format!(
+                "Success! Block {block_hash} created and added."
+            )),
+            Some(Err(messages)) => propose_result_response::Message::Error(error(messages)),
+            None => propose_result_response::Message::Error(error(vec![
+                "Error: no propose in progress".to_owned(),
+            ])),
+        };
+        Ok(Response::new(ProposeResultResponse {
+            message: Some(message),
+        }))
+    }
+}
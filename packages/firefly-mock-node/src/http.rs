@@ -0,0 +1,142 @@
+use std::sync::Arc;
+
+use firefly_client::rendering::Value;
+use futures::SinkExt;
+use poem::http::StatusCode;
+use poem::web::websocket::{Message, WebSocket};
+use poem::web::{Data, Json};
+use poem::{Body, EndpointExt, IntoResponse, Route, get, handler, post};
+use serde::Deserialize;
+use serde_json::json;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::state::State;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExploreByBlockHash {
+    term: String,
+}
+
+fn exprs<'a>(
+    values: impl IntoIterator<Item = &'a Value>,
+) -> Result<Vec<serde_json::Value>, String> {
+    values.into_iter().map(expr).collect()
+}
+
+/// Value in the node's REST representation, which [`firefly_client::read_node_expr`] reads.
+fn expr(value: &Value) -> Result<serde_json::Value, String> {
+    Ok(match value {
+        Value::Nil => json!({ "ExprNil": {} }),
+        Value::Bool(data) => json!({ "ExprBool": { "data": data } }),
+        Value::Int(data) => json!({ "ExprInt": { "data": data } }),
+        Value::String(data) => json!({ "ExprString": { "data": data } }),
+        Value::Bytes(data) => json!({ "ExprBytes": { "data": hex::encode(data) } }),
+        Value::Uri(data) => json!({ "ExprUri": { "data": data } }),
+        Value::Tuple(values) => json!({ "ExprTuple": { "data": exprs(values)? } }),
+        Value::List(values) => json!({ "ExprList": { "data": exprs(values)? } }),
+        Value::Set(values) => json!({ "ExprSet": { "data": exprs(values)? } }),
+        Value::Map(map) => {
+            let data = map
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), expr(value)?)))
+                .collect::<Result<serde_json::Map<_, _>, String>>()?;
+            json!({ "ExprMap": { "data": data } })
+        }
+        Value::Inline(code) => return Err(format!("inline code can't be returned: {code}")),
+    })
+}
+
+fn explore(state: &State, term: &str) -> poem::Response {
+    let value = match state.explore(term).as_ref().map(expr).transpose() {
+        Ok(value) => value,
+        Err(err) => return (StatusCode::BAD_REQUEST, err).into_response(),
+    };
+
+    let block = state.head(1).into_iter().next();
+    Json(json!({
+        "expr": value.into_iter().collect::<Vec<_>>(),
+        "block": {
+            "blockHash": block.as_ref().map(|block| &block.block_hash),
+            "blockNumber": block.as_ref().map(|block| block.block_number),
+        },
+    }))
+    .into_response()
+}
+
+#[handler]
+async fn explore_deploy(
+    body: Body,
+    Data(state): Data<&Arc<State>>,
+) -> poem::Result<poem::Response> {
+    let term = body.into_string().await?;
+    Ok(explore(state, &term))
+}
+
+#[handler]
+fn explore_deploy_by_block_hash(
+    Json(query): Json<ExploreByBlockHash>,
+    Data(state): Data<&Arc<State>>,
+) -> poem::Response {
+    explore(state, &query.term)
+}
+
+#[handler]
+fn status() -> Json<serde_json::Value> {
+    Json(json!({
+        "version": { "api": "1", "node": "firefly-mock-node" },
+        "shardId": "root",
+        "minPhloPrice": 1,
+    }))
+}
+
+#[handler]
+fn events(ws: WebSocket, Data(state): Data<&Arc<State>>) -> poem::Response {
+    let mut down = state.events_down();
+    if *down.borrow_and_update() {
+        return StatusCode::SERVICE_UNAVAILABLE.into_response();
+    }
+    let mut events = state.events();
+
+    ws.on_upgrade(|mut socket| async move {
+        let started = json!({ "event": "started" }).to_string();
+        if socket.send(Message::Text(started)).await.is_err() {
+            return;
+        }
+
+        loop {
+            let event = tokio::select! {
+                biased;
+                changed = down.changed() => {
+                    if changed.is_err() || *down.borrow_and_update() {
+                        let _ = socket.close().await;
+                        return;
+                    }
+                    continue;
+                }
+                event = events.recv() => match event {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return,
+                },
+            };
+            if socket.send(Message::Text(event)).await.is_err() {
+                return;
+            }
+        }
+    })
+    .into_response()
+}
+
+/// REST and websocket surface of the node.
+pub fn routes(state: Arc<State>) -> impl poem::Endpoint {
+    Route::new()
+        .at("/api/explore-deploy", post(explore_deploy))
+        .at(
+            "/api/explore-deploy-by-block-hash",
+            post(explore_deploy_by_block_hash),
+        )
+        .at("/api/status", get(status))
+        .at("/ws/events", get(events))
+        .data(state)
+}
@@ -0,0 +1,176 @@
+//! In-process Firefly node for tests. Serves the gRPC deploy and propose services, the exploratory
+//! deploy REST API and the websocket events on local ports, so that clients can be tested without
+//! a real node.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use firefly_client::models::BlockId;
+use firefly_client::models::casper::DeployDataProto;
+use firefly_client::rendering::Value;
+use firefly_client::models::casper::v1::deploy_service_server::DeployServiceServer;
+use firefly_client::models::casper::v1::propose_service_server::ProposeServiceServer;
+use poem::listener::TcpAcceptor;
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::TcpListenerStream;
+
+mod grpc;
+mod http;
+mod state;
+
+pub use state::Execution;
+
+use crate::grpc::Services;
+use crate::state::State;
+
+const LISTENERS_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Mock node listening on `127.0.0.1`. The servers stop when it is dropped.
+///
+/// Deploys are accepted when validly signed and recorded. Each propose puts the pending deploys in
+/// a new block and emits `block-created` and `block-added`, while blocks are only finalized with
+/// [`Self::finalize`] or [`Self::set_auto_finalize`].
+pub struct MockNode {
+    state: Arc<State>,
+    grpc_addr: SocketAddr,
+    http_addr: SocketAddr,
+    servers: Vec<JoinHandle<()>>,
+}
+
+impl MockNode {
+    pub async fn start() -> anyhow::Result<Self> {
+        Self::start_on(([127, 0, 0, 1], 0).into()).await
+    }
+
+    /// Starts with the gRPC services on `grpc_addr`, so that clients can be pointed at the node
+    /// before it runs.
+    pub async fn start_on(grpc_addr: SocketAddr) -> anyhow::Result<Self> {
+        let state = Arc::new(State::default());
+
+        let grpc_listener = TcpListener::bind(grpc_addr).await?;
+        let grpc_addr = grpc_listener.local_addr()?;
+        let services = Services(state.clone());
+        let grpc = tonic::transport::Server::builder()
+            .add_service(DeployServiceServer::new(services.clone()))
+            .add_service(ProposeServiceServer::new(services))
+            .serve_with_incoming(TcpListenerStream::new(grpc_listener));
+
+        let http_listener = TcpListener::bind("127.0.0.1:0").await?;
+        let http_addr = http_listener.local_addr()?;
+        let http = poem::Server::new_with_acceptor(TcpAcceptor::from_tokio(http_listener)?)
+            .run(http::routes(state.clone()));
+
+        let servers = vec![
+            tokio::spawn(async move {
+                if let Err(err) = grpc.await {
+                    tracing::error!("mock node grpc server failed: {err:?}");
+                }
+            }),
+            tokio::spawn(async move {
+                if let Err(err) = http.await {
+                    tracing::error!("mock node http server failed: {err:?}");
+                }
+            }),
+        ];
+
+        Ok(Self {
+            state,
+            grpc_addr,
+            http_addr,
+            servers,
+        })
+    }
+
+    /// Url of the deploy and propose services.
+    pub fn grpc_url(&self) -> String {
+        format!("http://{}", self.grpc_addr)
+    }
+
+    /// Url of the REST API.
+    pub fn http_url(&self) -> String {
+        format!("http://{}", self.http_addr)
+    }
+
+    /// Url the websocket events are served under.
+    pub fn ws_url(&self) -> String {
+        format!("ws://{}", self.http_addr)
+    }
+
+    /// Every deploy accepted so far, in order.
+    pub fn deploys(&self) -> Vec<DeployDataProto> {
+        self.state.deploys()
+    }
+
+    /// Answers exploratory deploys with the first value `explore` sends to the return channel,
+    /// given the deployed term. By default they return nothing.
+    pub fn on_explore<F>(&self, explore: F)
+    where
+        F: Fn(&str) -> Option<Value> + Send + Sync + 'static,
+    {
+        self.state.set_explore(Box::new(explore));
+    }
+
+    /// Decides the cost and outcome of each deploy when it is put in a block. By default deploys
+    /// succeed and cost nothing.
+    pub fn on_execute<F>(&self, execute: F)
+    where
+        F: Fn(&DeployDataProto) -> Execution + Send + Sync + 'static,
+    {
+        self.state.set_execute(Box::new(execute));
+    }
+
+    /// Fails the next propose with the given node error messages, e.g.
+    /// `"Error: Another propose is in progress"`. Queued errors are used in order.
+    pub fn fail_next_propose<I>(&self, messages: I)
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.state
+            .push_propose_error(messages.into_iter().map(Into::into).collect());
+    }
+
+    /// Finalizes every block as soon as it is proposed.
+    pub fn set_auto_finalize(&self, auto_finalize: bool) {
+        self.state.set_auto_finalize(auto_finalize);
+    }
+
+    /// Finalizes `block` and its ancestors, emitting `block-finalised` for each of them. Returns
+    /// whether the block exists.
+    pub fn finalize(&self, block: &BlockId) -> bool {
+        self.state.finalize(&block.to_string())
+    }
+
+    /// Answers every gRPC call with `UNAVAILABLE` while `unavailable`, the way a proxy in front of
+    /// a node does once the request reached it but the node went away.
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.state.set_unavailable(unavailable);
+    }
+
+    /// Sends an arbitrary event to every websocket listener.
+    pub fn emit_event(&self, event: &serde_json::Value) {
+        self.state.emit_raw(event);
+    }
+
+    /// Closes every websocket listener and refuses new ones while `down`, so that clients miss the
+    /// events sent in the meantime.
+    pub fn set_events_down(&self, down: bool) {
+        self.state.set_events_down(down);
+    }
+
+    /// Waits until at least `count` websocket listeners are connected, so that no event sent
+    /// afterwards is missed.
+    pub async fn wait_for_listeners(&self, count: usize) {
+        while self.state.listeners() < count {
+            tokio::time::sleep(LISTENERS_POLL_INTERVAL).await;
+        }
+    }
+}
+
+impl Drop for MockNode {
+    fn drop(&mut self) {
+        self.servers.iter().for_each(JoinHandle::abort);
+    }
+}
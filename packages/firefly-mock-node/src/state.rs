@@ -0,0 +1,378 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, RwLock};
+
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use firefly_client::DeployPreview;
+use firefly_client::models::casper::{BlockInfo, DeployDataProto, DeployInfo, LightBlockInfo};
+use firefly_client::rendering::Value;
+use serde_json::json;
+use tokio::sync::{broadcast, watch};
+
+const SHARD_ID: &str = "root";
+const SENDER: &str = "mock-validator";
+
+/// Outcome of a deploy once it is in a block, see [`crate::MockNode::on_execute`].
+#[derive(Debug, Clone, Default)]
+pub struct Execution {
+    pub cost: u64,
+    pub errored: bool,
+}
+
+type ExploreFn = dyn Fn(&str) -> Option<Value> + Send + Sync;
+type ExecuteFn = dyn Fn(&DeployDataProto) -> Execution + Send + Sync;
+
+struct Block {
+    info: LightBlockInfo,
+    deploys: Vec<DeployInfo>,
+    finalized: bool,
+}
+
+impl Block {
+    fn block_info(&self) -> BlockInfo {
+        BlockInfo {
+            block_info: Some(self.info.clone()),
+            deploys: self.deploys.clone(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Chain {
+    blocks: Vec<Block>,
+    pending: Vec<DeployDataProto>,
+    deploys: Vec<DeployDataProto>,
+    propose_errors: VecDeque<Vec<String>>,
+    last_propose: Option<Result<String, Vec<String>>>,
+    auto_finalize: bool,
+}
+
+/// Node state shared by the gRPC and HTTP services.
+pub struct State {
+    chain: Mutex<Chain>,
+    events: broadcast::Sender<String>,
+    events_down: watch::Sender<bool>,
+    explore: RwLock<Box<ExploreFn>>,
+    execute: RwLock<Box<ExecuteFn>>,
+    unavailable: AtomicBool,
+}
+
+fn hash(parts: &[&[u8]]) -> String {
+    let hash = parts
+        .iter()
+        .fold(Blake2b::<U32>::new(), |hasher, part| {
+            hasher.chain_update(part)
+        })
+        .finalize();
+    hex::encode(hash)
+}
+
+fn event(name: &str, block: &Block) -> String {
+    let deploys: Vec<_> = block
+        .deploys
+        .iter()
+        .map(|deploy| {
+            json!({
+                "id": deploy.sig,
+                "cost": deploy.cost,
+                "deployer": deploy.deployer,
+                "errored": deploy.errored,
+            })
+        })
+        .collect();
+
+    json!({
+        "event": name,
+        "payload": {
+            "block-hash": block.info.block_hash,
+            "deploys": deploys,
+        },
+    })
+    .to_string()
+}
+
+impl Default for State {
+    fn default() -> Self {
+        let genesis = Block {
+            info: LightBlockInfo {
+                block_hash: hash(&[b"genesis"]),
+                sender: SENDER.to_owned(),
+                shard_id: SHARD_ID.to_owned(),
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                ..Default::default()
+            },
+            deploys: Vec::new(),
+            finalized: true,
+        };
+
+        Self {
+            chain: Mutex::new(Chain {
+                blocks: vec![genesis],
+                ..Default::default()
+            }),
+            events: broadcast::Sender::new(64),
+            events_down: watch::Sender::new(false),
+            explore: RwLock::new(Box::new(|_| None)),
+            execute: RwLock::new(Box::new(|_| Execution::default())),
+            unavailable: AtomicBool::new(false),
+        }
+    }
+}
+
+impl State {
+    fn chain(&self) -> std::sync::MutexGuard<'_, Chain> {
+        self.chain
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn emit(&self, event: String) {
+        let _ = self.events.send(event);
+    }
+
+    pub fn events(&self) -> broadcast::Receiver<String> {
+        self.events.subscribe()
+    }
+
+    pub fn events_down(&self) -> watch::Receiver<bool> {
+        self.events_down.subscribe()
+    }
+
+    pub fn set_events_down(&self, down: bool) {
+        self.events_down.send_replace(down);
+    }
+
+    pub fn listeners(&self) -> usize {
+        self.events.receiver_count()
+    }
+
+    pub fn emit_raw(&self, event: &serde_json::Value) {
+        self.emit(event.to_string());
+    }
+
+    pub fn set_explore(&self, explore: Box<ExploreFn>) {
+        *self
+            .explore
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = explore;
+    }
+
+    pub fn set_execute(&self, execute: Box<ExecuteFn>) {
+        *self
+            .execute
+            .write()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) = execute;
+    }
+
+    pub fn explore(&self, term: &str) -> Option<Value> {
+        (self
+            .explore
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner))(term)
+    }
+
+    pub fn set_auto_finalize(&self, auto_finalize: bool) {
+        self.chain().auto_finalize = auto_finalize;
+    }
+
+    pub fn set_unavailable(&self, unavailable: bool) {
+        self.unavailable.store(unavailable, Ordering::Relaxed);
+    }
+
+    pub fn unavailable(&self) -> bool {
+        self.unavailable.load(Ordering::Relaxed)
+    }
+
+    pub fn push_propose_error(&self, messages: Vec<String>) {
+        self.chain().propose_errors.push_back(messages);
+    }
+
+    pub fn deploys(&self) -> Vec<DeployDataProto> {
+        self.chain().deploys.clone()
+    }
+
+    /// Accepts a signed deploy, returning its id.
+    pub fn deploy(&self, deploy: DeployDataProto) -> Result<String, Vec<String>> {
+        let signature_valid = DeployPreview::try_from(deploy.clone())
+            .map_err(|err| vec![err.to_string()])?
+            .deployer
+            .is_some_and(|deployer| deployer.signature_valid);
+        if !signature_valid {
+            return Err(vec!["Invalid deploy signature".to_owned()]);
+        }
+
+        let deploy_id = hex::encode(&deploy.sig);
+        let mut chain = self.chain();
+        chain.deploys.push(deploy.clone());
+        chain.pending.push(deploy);
+        drop(chain);
+        Ok(deploy_id)
+    }
+
+    fn create_block(&self, chain: &mut Chain) -> String {
+        let execute = self
+            .execute
+            .read()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let deploys: Vec<_> = std::mem::take(&mut chain.pending)
+            .into_iter()
+            .map(|deploy| {
+                let execution = execute(&deploy);
+                DeployInfo {
+                    deployer: hex::encode(&deploy.deployer),
+                    term: deploy.term,
+                    timestamp: deploy.timestamp,
+                    sig: hex::encode(&deploy.sig),
+                    sig_algorithm: deploy.sig_algorithm,
+                    phlo_price: deploy.phlo_price,
+                    phlo_limit: deploy.phlo_limit,
+                    valid_after_block_number: deploy.valid_after_block_number,
+                    cost: execution.cost,
+                    errored: execution.errored,
+                    system_deploy_error: String::new(),
+                }
+            })
+            .collect();
+        drop(execute);
+
+        let parent = chain.blocks.last().map(|block| block.info.clone());
+        let block_number = parent.as_ref().map_or(0, |parent| parent.block_number + 1);
+        let sigs: Vec<_> = deploys.iter().map(|deploy| deploy.sig.as_bytes()).collect();
+        let block = Block {
+            info: LightBlockInfo {
+                block_hash: hash(&[&block_number.to_le_bytes(), &sigs.concat()]),
+                sender: SENDER.to_owned(),
+                seq_num: block_number,
+                shard_id: SHARD_ID.to_owned(),
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                parents_hash_list: parent.into_iter().map(|parent| parent.block_hash).collect(),
+                block_number,
+                deploy_count: deploys.len().try_into().unwrap_or(i32::MAX),
+                ..Default::default()
+            },
+            deploys,
+            finalized: false,
+        };
+
+        self.emit(event("block-created", &block));
+        self.emit(event("block-added", &block));
+        let block_hash = block.info.block_hash.clone();
+        chain.blocks.push(block);
+        block_hash
+    }
+
+    /// Creates a block out of the pending deploys, returning its hash.
+    pub fn propose(&self) -> Result<String, Vec<String>> {
+        let mut chain = self.chain();
+
+        let result = match chain.propose_errors.pop_front() {
+            Some(messages) => Err(messages),
+            None if chain.pending.is_empty() => {
+                Err(vec!["Proposal failed: NoNewDeploys".to_owned()])
+            }
+            None => Ok(self.create_block(&mut chain)),
+        };
+
+        chain.last_propose = Some(result.clone());
+        let auto_finalize = chain.auto_finalize;
+        drop(chain);
+
+        if let (Ok(block_hash), true) = (&result, auto_finalize) {
+            self.finalize(block_hash);
+        }
+        result
+    }
+
+    pub fn take_propose_result(&self) -> Option<Result<String, Vec<String>>> {
+        self.chain().last_propose.take()
+    }
+
+    /// Finalizes the block and its ancestors, emitting `block-finalised` for each of them.
+    pub fn finalize(&self, block_hash: &str) -> bool {
+        let mut chain = self.chain();
+        let Some(index) = chain
+            .blocks
+            .iter()
+            .position(|block| block.info.block_hash == block_hash)
+        else {
+            return false;
+        };
+
+        for block in &mut chain.blocks[..=index] {
+            if !block.finalized {
+                block.finalized = true;
+                self.emit(event("block-finalised", block));
+            }
+        }
+        drop(chain);
+        true
+    }
+
+    pub fn head(&self, depth: usize) -> Vec<LightBlockInfo> {
+        self.chain()
+            .blocks
+            .iter()
+            .rev()
+            .take(depth)
+            .map(|block| block.info.clone())
+            .collect()
+    }
+
+    pub fn blocks_by_heights(&self, start: i64, end: i64) -> Vec<LightBlockInfo> {
+        self.chain()
+            .blocks
+            .iter()
+            .filter(|block| (start..=end).contains(&block.info.block_number))
+            .map(|block| block.info.clone())
+            .collect()
+    }
+
+    pub fn block(&self, block_hash: &str) -> Option<BlockInfo> {
+        self.chain()
+            .blocks
+            .iter()
+            .find(|block| block.info.block_hash == block_hash)
+            .map(Block::block_info)
+    }
+
+    pub fn is_finalized(&self, block_hash: &str) -> bool {
+        self.chain()
+            .blocks
+            .iter()
+            .any(|block| block.info.block_hash == block_hash && block.finalized)
+    }
+
+    pub fn last_finalized(&self) -> Option<BlockInfo> {
+        self.chain()
+            .blocks
+            .iter()
+            .rev()
+            .find(|block| block.finalized)
+            .map(Block::block_info)
+    }
+
+    pub fn find_deploy(&self, deploy_id: &[u8]) -> Option<LightBlockInfo> {
+        let deploy_id = hex::encode(deploy_id);
+        self.chain()
+            .blocks
+            .iter()
+            .find(|block| block.deploys.iter().any(|deploy| deploy.sig == deploy_id))
+            .map(|block| block.info.clone())
+    }
+
+    /// Names are derived from the deployer and timestamp, but are not the ones a real node would
+    /// compute.
+    pub fn preview_private_names(user: &[u8], timestamp: i64, count: usize) -> Vec<Vec<u8>> {
+        (0..count)
+            .map(|index| {
+                Blake2b::<U32>::new()
+                    .chain_update(user)
+                    .chain_update(timestamp.to_le_bytes())
+                    .chain_update(index.to_le_bytes())
+                    .finalize()
+                    .to_vec()
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use firefly_client::errors::ProposeError;
+use firefly_client::models::DeployData;
+use firefly_client::rendering::Value;
+use firefly_client::{
+    ConnectionOptions,
+    DeployOutcome,
+    DeployTracker,
+    NodeEvents,
+    ProposeScheduler,
+    ReadNodeClient,
+    WriteNodeClient,
+};
+use firefly_mock_node::{Execution, MockNode};
+use secp256k1::SecretKey;
+
+const KEY: &str = "f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc";
+
+async fn write_client(node: &MockNode) -> WriteNodeClient {
+    WriteNodeClient::new(node.grpc_url(), node.grpc_url())
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_deploy_and_propose() {
+    let node = MockNode::start().await.unwrap();
+    let mut client = write_client(&node).await;
+    let key = SecretKey::from_str(KEY).unwrap();
+
+    let deploy_id = client
+        .deploy(&key, DeployData::builder("Nil".to_owned()).build())
+        .await
+        .unwrap();
+    assert_eq!(node.deploys().len(), 1);
+    assert_eq!(node.deploys()[0].term, "Nil");
+
+    let block = client.propose().await.unwrap();
+    assert_eq!(client.get_head_block_index().await.unwrap(), 1);
+    assert_eq!(
+        client
+            .find_deploy(&deploy_id)
+            .await
+            .unwrap()
+            .unwrap()
+            .block_hash,
+        block
+    );
+    assert!(!client.is_finalized(&block).await.unwrap());
+
+    assert!(matches!(
+        client.propose().await,
+        Err(ProposeError::NoNewDeploys)
+    ));
+}
+
+#[tokio::test]
+async fn test_propose_scheduler_retries_busy_validator() {
+    let node = MockNode::start().await.unwrap();
+    let mut client = write_client(&node).await;
+    let key = SecretKey::from_str(KEY).unwrap();
+    node.fail_next_propose(["Error: Another propose is in progress"]);
+
+    let scheduler = ProposeScheduler::builder()
+        .write_client(client.clone())
+        .debounce(Duration::from_millis(10))
+        .retry_interval(Duration::from_millis(10))
+        .build();
+
+    let deploy_id = client
+        .deploy(&key, DeployData::builder("Nil".to_owned()).build())
+        .await
+        .unwrap();
+    let block = scheduler.enqueue(deploy_id).await.unwrap();
+
+    assert_eq!(client.get_head_block_index().await.unwrap(), 1);
+    assert_eq!(client.get_block(&block).await.unwrap().deploys.len(), 1);
+}
+
+#[tokio::test]
+async fn test_probe_node() {
+    let node = MockNode::start().await.unwrap();
+    let client = write_client(&node).await;
+
+    let probes = client.probe(Duration::from_secs(1)).await;
+    assert_eq!(probes.len(), 1);
+    assert!(probes[0].active);
+    assert_eq!(probes[0].deploy, Ok(()));
+    assert_eq!(probes[0].propose, Ok(()));
+
+    ReadNodeClient::new(node.http_url()).status().await.unwrap();
+
+    let node_events = NodeEvents::new(&node.ws_url());
+    node.wait_for_listeners(1).await;
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    let status = node_events.status();
+    assert!(status.connected);
+    assert!(status.since_last_event.is_some());
+}
+
+#[tokio::test]
+async fn test_explore_deploy() {
+    let node = MockNode::start().await.unwrap();
+    node.on_explore(|term| {
+        Some(Value::Map(BTreeMap::from([(
+            "term".to_owned(),
+            Value::String(term.to_owned()),
+        )])))
+    });
+
+    let http = ReadNodeClient::new(node.http_url());
+    let grpc = ReadNodeClient::grpc(node.grpc_url(), &ConnectionOptions::default())
+        .await
+        .unwrap();
+
+    for client in [http, grpc] {
+        let data: serde_json::Value = client.get_data("Nil".to_owned()).await.unwrap();
+        assert_eq!(data, serde_json::json!({ "term": "Nil" }));
+    }
+}
+
+#[tokio::test]
+async fn test_track_finalized_deploy() {
+    let node = MockNode::start().await.unwrap();
+    let mut client = write_client(&node).await;
+    let key = SecretKey::from_str(KEY).unwrap();
+    node.on_execute(|_| Execution {
+        cost: 42,
+        errored: false,
+    });
+
+    let node_events = NodeEvents::new(&node.ws_url());
+    node.wait_for_listeners(1).await;
+
+    let deploy_id = client
+        .deploy(&key, DeployData::builder("Nil".to_owned()).build())
+        .await
+        .unwrap();
+    let block = client.propose().await.unwrap();
+
+    let tracker = DeployTracker::builder()
+        .write_client(Arc::new(client))
+        .node_events(Arc::new(node_events))
+        .build();
+    let outcome = tokio::join!(
+        tracker.track(&deploy_id, None, Duration::from_secs(5)),
+        async {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            assert!(node.finalize(&block));
+        }
+    )
+    .0;
+
+    assert_eq!(
+        outcome,
+        DeployOutcome::Finalized {
+            block,
+            cost: 42,
+            errored: false,
+        }
+    );
+}
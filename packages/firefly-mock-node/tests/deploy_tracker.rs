@@ -0,0 +1,114 @@
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use firefly_client::models::{DeployData, DeployId};
+use firefly_client::{DeployOutcome, DeployTracker, NodeEvents, WriteNodeClient};
+use firefly_mock_node::{Execution, MockNode};
+use secp256k1::SecretKey;
+
+const KEY: &str = "f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc";
+const MAX_WAIT: Duration = Duration::from_millis(300);
+
+async fn write_client(node: &MockNode) -> WriteNodeClient {
+    WriteNodeClient::new(node.grpc_url(), node.grpc_url())
+        .await
+        .unwrap()
+}
+
+fn tracker(client: WriteNodeClient, node: &MockNode) -> DeployTracker {
+    DeployTracker::builder()
+        .write_client(Arc::new(client))
+        .node_events(Arc::new(NodeEvents::new(&node.ws_url())))
+        .poll_interval(Duration::from_millis(20))
+        .deploy_lifespan(1)
+        .build()
+}
+
+async fn deploy(client: &mut WriteNodeClient) -> DeployId {
+    let key = SecretKey::from_str(KEY).unwrap();
+    client
+        .deploy(&key, DeployData::builder("Nil".to_owned()).build())
+        .await
+        .unwrap()
+}
+
+fn unknown_deploy_id() -> DeployId {
+    "00".repeat(64).into()
+}
+
+#[tokio::test]
+async fn test_finalized_by_polling() {
+    let node = MockNode::start().await.unwrap();
+    let mut client = write_client(&node).await;
+    node.on_execute(|_| Execution {
+        cost: 7,
+        errored: true,
+    });
+
+    let deploy_id = deploy(&mut client).await;
+    let block = client.propose().await.unwrap();
+    assert!(node.finalize(&block));
+
+    // the finalization event was sent before the tracker subscribed
+    let outcome = tracker(client, &node)
+        .track(&deploy_id, None, MAX_WAIT)
+        .await;
+
+    assert_eq!(
+        outcome,
+        DeployOutcome::Finalized {
+            block,
+            cost: 7,
+            errored: true,
+        }
+    );
+}
+
+#[tokio::test]
+async fn test_included_but_not_finalized() {
+    let node = MockNode::start().await.unwrap();
+    let mut client = write_client(&node).await;
+
+    let deploy_id = deploy(&mut client).await;
+    let block = client.propose().await.unwrap();
+
+    let outcome = tracker(client, &node)
+        .track(&deploy_id, Some(0), MAX_WAIT)
+        .await;
+
+    assert_eq!(outcome, DeployOutcome::Included { block });
+}
+
+#[tokio::test]
+async fn test_expired() {
+    let node = MockNode::start().await.unwrap();
+    let mut client = write_client(&node).await;
+
+    for _ in 0..2 {
+        deploy(&mut client).await;
+        client.propose().await.unwrap();
+    }
+
+    let outcome = tracker(client, &node)
+        .track(&unknown_deploy_id(), Some(0), MAX_WAIT)
+        .await;
+
+    assert_eq!(outcome, DeployOutcome::Expired);
+}
+
+#[tokio::test]
+async fn test_unknown() {
+    let node = MockNode::start().await.unwrap();
+    let client = write_client(&node).await;
+    let tracker = tracker(client, &node);
+
+    assert_eq!(
+        tracker.track(&unknown_deploy_id(), Some(0), MAX_WAIT).await,
+        DeployOutcome::Unknown
+    );
+    assert_eq!(
+        tracker.track(&unknown_deploy_id(), None, MAX_WAIT).await,
+        DeployOutcome::Unknown
+    );
+}
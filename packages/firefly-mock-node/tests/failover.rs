@@ -0,0 +1,100 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use firefly_client::errors::ProposeError;
+use firefly_client::models::{DeployData, ValidAfter};
+use firefly_client::{ValidatorEndpoint, WriteNodeClient};
+use firefly_mock_node::MockNode;
+use secp256k1::SecretKey;
+use tokio::net::TcpListener;
+use tonic::Code;
+
+const KEY: &str = "f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc";
+
+/// Url nothing listens on, so that connections to it are refused.
+async fn dead_url() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    format!("http://{}", listener.local_addr().unwrap())
+}
+
+/// Client over `urls` that starts without waiting for health checks, so that requests go to the
+/// first validator until it fails.
+async fn pool(urls: &[String]) -> WriteNodeClient {
+    WriteNodeClient::builder()
+        .endpoints(
+            urls.iter()
+                .map(|url| ValidatorEndpoint {
+                    deploy_service_url: url.clone(),
+                    propose_service_url: url.clone(),
+                })
+                .collect(),
+        )
+        .connect_deadline(Duration::ZERO)
+        .health_check_interval(Duration::from_secs(3600))
+        .connect()
+        .await
+        .unwrap()
+}
+
+fn deploy_data() -> DeployData {
+    DeployData::builder("Nil".to_owned())
+        .valid_after_block_number(ValidAfter::Index(0))
+        .build()
+}
+
+#[tokio::test]
+async fn test_deploy_fails_over_when_connection_is_refused() {
+    let node = MockNode::start().await.unwrap();
+    let mut client = pool(&[dead_url().await, node.grpc_url()]).await;
+    let key = SecretKey::from_str(KEY).unwrap();
+
+    client.deploy(&key, deploy_data()).await.unwrap();
+    assert_eq!(node.deploys().len(), 1);
+
+    client.propose().await.unwrap();
+    assert_eq!(client.get_head_block_index().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_reads_fail_over_when_validator_is_unavailable() {
+    let unavailable = MockNode::start().await.unwrap();
+    unavailable.set_unavailable(true);
+    let node = MockNode::start().await.unwrap();
+    let mut client = pool(&[unavailable.grpc_url(), node.grpc_url()]).await;
+
+    assert_eq!(client.get_head_block_index().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_deploy_and_propose_do_not_fail_over_once_sent() {
+    let unavailable = MockNode::start().await.unwrap();
+    unavailable.set_unavailable(true);
+    let node = MockNode::start().await.unwrap();
+    let urls = [unavailable.grpc_url(), node.grpc_url()];
+    let key = SecretKey::from_str(KEY).unwrap();
+
+    let mut client = pool(&urls).await;
+    let err = client.deploy(&key, deploy_data()).await.unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<tonic::Status>().unwrap().code(),
+        Code::Unavailable
+    );
+    assert!(node.deploys().is_empty());
+
+    client.deploy(&key, deploy_data()).await.unwrap();
+    assert_eq!(node.deploys().len(), 1);
+
+    let mut client = pool(&urls).await;
+    assert!(matches!(
+        client.propose().await,
+        Err(ProposeError::Grpc(status)) if status.code() == Code::Unavailable
+    ));
+    assert_eq!(client.get_head_block_index().await.unwrap(), 0);
+}
+
+#[tokio::test(start_paused = true)]
+async fn test_connect_gives_up() {
+    let url = dead_url().await;
+
+    assert!(WriteNodeClient::new(url.clone(), url).await.is_err());
+}
@@ -0,0 +1,56 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use firefly_client::models::DeployData;
+use firefly_client::{NodeEvents, WriteNodeClient};
+use firefly_mock_node::MockNode;
+use secp256k1::SecretKey;
+
+const KEY: &str = "f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc";
+const MISSED_BLOCKS: usize = 40;
+const MAX_WAIT: Duration = Duration::from_secs(30);
+
+#[tokio::test]
+async fn test_backfill_replays_every_missed_block() {
+    let node = MockNode::start().await.unwrap();
+    node.set_auto_finalize(true);
+    let mut client = WriteNodeClient::new(node.grpc_url(), node.grpc_url())
+        .await
+        .unwrap();
+    let key = SecretKey::from_str(KEY).unwrap();
+
+    let node_events = NodeEvents::with_backfill(&node.ws_url(), client.clone());
+    node.wait_for_listeners(1).await;
+
+    // let the client see a finalized block to resume from
+    let deploy_id = client
+        .deploy(&key, DeployData::builder("Nil".to_owned()).build())
+        .await
+        .unwrap();
+    let seen = node_events.wait_for_deploy(&deploy_id, MAX_WAIT);
+    client.propose().await.unwrap();
+    assert!(seen.await);
+
+    node.set_events_down(true);
+
+    let mut deploy_ids = Vec::with_capacity(MISSED_BLOCKS);
+    for index in 0..MISSED_BLOCKS {
+        let term = format!("@{index}!(Nil)");
+        deploy_ids.push(
+            client
+                .deploy(&key, DeployData::builder(term).build())
+                .await
+                .unwrap(),
+        );
+        client.propose().await.unwrap();
+    }
+
+    let waits: Vec<_> = deploy_ids
+        .iter()
+        .map(|deploy_id| node_events.wait_for_deploy(deploy_id, MAX_WAIT))
+        .collect();
+    node.set_events_down(false);
+
+    let finalized = futures::future::join_all(waits).await;
+    assert!(finalized.into_iter().all(|finalized| finalized));
+}
@@ -0,0 +1,109 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use firefly_client::errors::ProposeSchedulerError;
+use firefly_client::models::{DeployData, DeployId};
+use firefly_client::{ProposeScheduler, WriteNodeClient};
+use firefly_mock_node::MockNode;
+use secp256k1::SecretKey;
+
+const KEY: &str = "f450b26bac63e5dd9343cd46f5fae1986d367a893cd21eedd98a4cb3ac699abc";
+const BUSY: &str = "Error: Another propose is in progress";
+
+async fn write_client(node: &MockNode) -> WriteNodeClient {
+    WriteNodeClient::new(node.grpc_url(), node.grpc_url())
+        .await
+        .unwrap()
+}
+
+fn scheduler(client: &WriteNodeClient, max_attempts: u32) -> ProposeScheduler {
+    ProposeScheduler::builder()
+        .write_client(client.clone())
+        .debounce(Duration::from_millis(10))
+        .retry_interval(Duration::from_millis(10))
+        .max_attempts(max_attempts)
+        .build()
+}
+
+async fn deploy(client: &mut WriteNodeClient) -> DeployId {
+    let key = SecretKey::from_str(KEY).unwrap();
+    client
+        .deploy(&key, DeployData::builder("Nil".to_owned()).build())
+        .await
+        .unwrap()
+}
+
+fn not_included(err: &anyhow::Error) -> (u32, Option<String>) {
+    match err.downcast_ref::<ProposeSchedulerError>() {
+        Some(ProposeSchedulerError::NotIncluded {
+            attempts,
+            last_error,
+        }) => (*attempts, last_error.clone()),
+        _ => panic!("unexpected error: {err:?}"),
+    }
+}
+
+#[tokio::test]
+async fn test_retries_not_enough_new_blocks() {
+    let node = MockNode::start().await.unwrap();
+    let mut client = write_client(&node).await;
+    node.fail_next_propose(["Error: NotEnoughNewBlocks"]);
+    node.fail_next_propose(["Error: NotEnoughNewBlocks"]);
+
+    let deploy_id = deploy(&mut client).await;
+    let block = scheduler(&client, 5).enqueue(deploy_id).await.unwrap();
+
+    assert_eq!(client.get_head_block_index().await.unwrap(), 1);
+    assert_eq!(client.get_block(&block).await.unwrap().deploys.len(), 1);
+}
+
+#[tokio::test]
+async fn test_gives_up_on_busy_validator() {
+    let node = MockNode::start().await.unwrap();
+    let mut client = write_client(&node).await;
+    // every propose of both rounds is retried twice, then the round gives up
+    for _ in 0..6 {
+        node.fail_next_propose([BUSY]);
+    }
+
+    let deploy_id = deploy(&mut client).await;
+    let err = scheduler(&client, 2).enqueue(deploy_id).await.unwrap_err();
+
+    let (attempts, last_error) = not_included(&err);
+    assert_eq!(attempts, 2);
+    assert!(
+        last_error
+            .unwrap()
+            .contains("another propose is in progress")
+    );
+    assert_eq!(client.get_head_block_index().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_does_not_retry_failed_propose() {
+    let node = MockNode::start().await.unwrap();
+    let mut client = write_client(&node).await;
+    node.fail_next_propose(["Error: validator is not bonded"]);
+
+    let deploy_id = deploy(&mut client).await;
+    let err = scheduler(&client, 1).enqueue(deploy_id).await.unwrap_err();
+
+    let (attempts, last_error) = not_included(&err);
+    assert_eq!(attempts, 1);
+    assert!(last_error.unwrap().contains("bonded"));
+    // the next round would have proposed the deploy
+    assert_eq!(client.get_head_block_index().await.unwrap(), 0);
+}
+
+#[tokio::test]
+async fn test_resolves_deploy_proposed_elsewhere() {
+    let node = MockNode::start().await.unwrap();
+    let mut client = write_client(&node).await;
+
+    let deploy_id = deploy(&mut client).await;
+    let block = client.propose().await.unwrap();
+
+    // the scheduler's own propose has no new deploys
+    let scheduled = scheduler(&client, 1).enqueue(deploy_id).await.unwrap();
+    assert_eq!(scheduled, block);
+}
@@ -1,25 +1,23 @@
-use std::collections::HashMap;
 use std::fmt::Display;
+use std::net::Ipv4Addr;
 use std::process::Command;
 use std::time::Duration;
 
-use anyhow::{Context, Ok, anyhow};
+use anyhow::{Ok, anyhow};
 use base64::Engine;
 use base64::prelude::BASE64_STANDARD;
 use clap::{Parser, Subcommand};
 use firefly_client::helpers::FromExpr;
-use firefly_client::models::rhoapi::expr::ExprInstance;
 use firefly_client::models::{BlockId, DeployData};
-use secp256k1::SecretKey;
+use firefly_client::signer::{SignerArgs, SignerConfig};
 use serde::{Deserialize, Serialize};
 use tokio::select;
 use uuid::Uuid;
 
 #[derive(Debug, Parser)]
 struct Args {
-    /// Wallet key in hex format
-    #[arg(long)]
-    wallet_key: SecretKey,
+    #[command(flatten)]
+    signer: SignerArgs,
 
     /// Firefly deploy service url
     #[arg(long)]
@@ -33,6 +31,10 @@ struct Args {
     #[arg(long)]
     service_id: String,
 
+    /// Port to serve Prometheus metrics on, metrics are not collected when unset
+    #[arg(long)]
+    metrics_port: Option<u16>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -64,6 +66,10 @@ enum Commands {
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let args = Args::parse();
+    if let Some(port) = args.metrics_port {
+        firefly_client::metrics::install_prometheus((Ipv4Addr::UNSPECIFIED, port))?;
+    }
+    let signer = SignerConfig::from(args.signer).into_signer().await?;
 
     let mut client =
         firefly_client::WriteNodeClient::new(args.deploy_service_url, args.propose_service_url)
@@ -86,7 +92,7 @@ async fn main() -> anyhow::Result<()> {
 
                 let rho_code = rho_sql_dump_template(channel_name, sql);
                 let deploy_data = DeployData::builder(rho_code).build();
-                let hash = client.full_deploy(&args.wallet_key, deploy_data).await?;
+                let hash = client.full_deploy(&signer, deploy_data).await?;
                 println!("dump hash: {hash}");
 
                 let rho_code = rho_save_hash_template(
@@ -97,7 +103,7 @@ async fn main() -> anyhow::Result<()> {
                     },
                 );
                 let deploy_data = DeployData::builder(rho_code).build();
-                let hash = client.full_deploy(&args.wallet_key, deploy_data).await?;
+                let hash = client.full_deploy(&signer, deploy_data).await?;
                 println!("save hash: {hash}");
             }
         }
@@ -120,7 +126,7 @@ async fn main() -> anyhow::Result<()> {
         Commands::Init => {
             let rho_code = rho_save_hash_contract(&args.service_id);
             let deploy_data = DeployData::builder(rho_code).build();
-            let hash = client.full_deploy(&args.wallet_key, deploy_data).await?;
+            let hash = client.full_deploy(&signer, deploy_data).await?;
             println!("{hash}");
         }
     }
@@ -153,29 +159,12 @@ fn rho_save_hash_contract(service_id: &str) -> String {
     )
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromExpr)]
 struct ServiceHash {
     block_hash: BlockId,
     channel_name: Uuid,
 }
 
-impl FromExpr for ServiceHash {
-    fn from(val: ExprInstance) -> anyhow::Result<Self> {
-        let mut map: HashMap<String, String> = FromExpr::from(val)?;
-
-        let block_hash = map.remove("block_hash").context("block_hash is missing")?;
-        let channel_name = map
-            .remove("channel_name")
-            .context("channel_name is missing")?;
-        let channel_name: Uuid = channel_name.parse()?;
-
-        Ok(Self {
-            block_hash: block_hash.into(),
-            channel_name,
-        })
-    }
-}
-
 fn run_pg_dump(db_url: &str) -> anyhow::Result<String> {
     let mut command = Command::new("pg_dump");
 